@@ -0,0 +1,149 @@
+use crate::note::Note;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Attachment blobs live here, next to the notes file, each named by the SHA-256 of its content.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// A file attached to a note. The content is stored once per distinct hash.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Attachment {
+    /// Original file name, used when opening or exporting.
+    pub name: String,
+    pub hash: String,
+    pub size: u64,
+}
+
+pub fn blob_path(hash: &str) -> PathBuf {
+    Path::new(ATTACHMENTS_DIR).join(hash)
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Add `bytes` to the store, unless identical content is already there.
+pub fn store_bytes(name: &str, bytes: &[u8]) -> io::Result<Attachment> {
+    let hash = hash_bytes(bytes);
+    let path = blob_path(&hash);
+    if !path.exists() {
+        fs::create_dir_all(ATTACHMENTS_DIR)?;
+        // Write under a temporary name first so a crash never leaves a truncated blob.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &path)?;
+    }
+    Ok(Attachment {
+        name: name.to_owned(),
+        hash,
+        size: bytes.len() as u64,
+    })
+}
+
+pub fn store_file(path: &Path) -> io::Result<Attachment> {
+    let bytes = fs::read(path)?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "attachment".to_owned());
+    store_bytes(&name, &bytes)
+}
+
+/// Delete every blob (and thumbnail) no note refers to. Returns how many files were removed.
+pub fn collect_garbage<'a>(notes: impl IntoIterator<Item = &'a Note>) -> usize {
+    let used: HashSet<&str> = notes
+        .into_iter()
+        .flat_map(|n| n.attachments.iter().map(|a| a.hash.as_str()))
+        .collect();
+    remove_unused(Path::new(ATTACHMENTS_DIR), &used)
+}
+
+fn remove_unused(dir: &Path, used: &HashSet<&str>) -> usize {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            removed += remove_unused(&path, used);
+            continue;
+        }
+        // Blobs are named by their hash; derived files such as thumbnails add an extension.
+        let hash = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        if used.contains(hash.as_str()) {
+            continue;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => removed += 1,
+            Err(e) => eprintln!("Error removing attachment {}: {e}", path.display()),
+        }
+    }
+    removed
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Copy the attachment out of the store to `dest`.
+pub fn export(attachment: &Attachment, dest: &Path) -> io::Result<()> {
+    fs::copy(blob_path(&attachment.hash), dest).map(|_| ())
+}
+
+/// Open the attachment with the system's default application. Blobs have no extension,
+/// so a copy under the original name is opened from the temp directory.
+pub fn open(attachment: &Attachment) -> io::Result<()> {
+    let dir = std::env::temp_dir().join("purpose-notes").join(&attachment.hash);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(safe_file_name(&attachment.name));
+    export(attachment, &path)?;
+    open_path(&path)
+}
+
+/// The last component of `name` with anything but letters, digits, spaces and `.-_()`
+/// replaced, so an imported name can't point outside a folder or be read as shell syntax.
+pub fn safe_file_name(name: &str) -> String {
+    let name = Path::new(name)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || " .-_()".contains(c) { c } else { '_' })
+        .collect();
+    let safe = safe.trim_start_matches('.').trim();
+    if safe.is_empty() {
+        "attachment".to_owned()
+    } else {
+        safe.to_owned()
+    }
+}
+
+/// Hand `path` straight to the platform's opener, without going through a shell.
+fn open_path(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let mut command = Command::new("explorer.exe");
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+    command.arg(path).spawn().map(|_| ())
+}
@@ -0,0 +1,135 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use zeroize::Zeroizing;
+
+/// Identifies sealed data and its format version.
+const MAGIC: &[u8; 6] = b"PNENC\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 3 * 4 + SALT_LEN;
+
+/// Argon2id cost parameters, stored with the sealed data so they can be raised later.
+#[derive(Clone, Copy)]
+pub struct KdfParams {
+    /// Memory in KiB.
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: 64 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+/// A key derived from a passphrase, with the salt and parameters needed to derive it again.
+/// The key bytes are wiped from memory when it is dropped.
+pub struct SealingKey {
+    key: Zeroizing<[u8; 32]>,
+    salt: [u8; SALT_LEN],
+    params: KdfParams,
+}
+
+impl SealingKey {
+    /// Derive a key from `passphrase` with a fresh random salt.
+    pub fn new(passphrase: &str) -> Result<Self, String> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt, KdfParams::default())
+    }
+
+    fn derive(passphrase: &str, salt: [u8; SALT_LEN], params: KdfParams) -> Result<Self, String> {
+        let argon_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+            .map_err(|e| format!("Invalid key derivation parameters: {e}"))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| format!("Key derivation failed: {e}"))?;
+        Ok(Self { key, salt, params })
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&self.params.m_cost.to_le_bytes());
+        header.extend_from_slice(&self.params.t_cost.to_le_bytes());
+        header.extend_from_slice(&self.params.p_cost.to_le_bytes());
+        header.extend_from_slice(&self.salt);
+        header
+    }
+
+    /// Encrypt `plaintext`. The output holds everything except the passphrase needed to decrypt it.
+    pub fn seal(&self, plaintext: &[u8]) -> Vec<u8> {
+        let cipher = XChaCha20Poly1305::new(self.key.as_ref().into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut sealed = self.header();
+        // The header is authenticated too, so its salt and parameters can't be swapped.
+        let ciphertext = cipher
+            .encrypt(&nonce, Payload { msg: plaintext, aad: &sealed })
+            .expect("encrypting an in-memory buffer cannot fail");
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        sealed
+    }
+
+    /// Whether `sealed` was written with a key derived from the same salt and parameters.
+    pub fn matches(&self, sealed: &[u8]) -> bool {
+        sealed.starts_with(&self.header())
+    }
+
+    /// Decrypt data written by `seal` with this key.
+    pub fn unseal(&self, sealed: &[u8]) -> Result<Zeroizing<Vec<u8>>, String> {
+        if sealed.len() < HEADER_LEN + NONCE_LEN || &sealed[..MAGIC.len()] != MAGIC {
+            return Err("Not an encrypted note store".to_owned());
+        }
+        let (header, rest) = sealed.split_at(HEADER_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let cipher = XChaCha20Poly1305::new(self.key.as_ref().into());
+        cipher
+            .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+            .map(Zeroizing::new)
+            .map_err(|_| "Wrong passphrase or damaged data".to_owned())
+    }
+
+    /// Derive the key for `sealed` from `passphrase` and decrypt it.
+    pub fn open(sealed: &[u8], passphrase: &str) -> Result<(Self, Zeroizing<Vec<u8>>), String> {
+        if sealed.len() < HEADER_LEN || &sealed[..MAGIC.len()] != MAGIC {
+            return Err("Not an encrypted note store".to_owned());
+        }
+        let u32_at = |i: usize| u32::from_le_bytes(sealed[i..i + 4].try_into().unwrap());
+        let params = KdfParams {
+            m_cost: u32_at(MAGIC.len()),
+            t_cost: u32_at(MAGIC.len() + 4),
+            p_cost: u32_at(MAGIC.len() + 8),
+        };
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&sealed[HEADER_LEN - SALT_LEN..HEADER_LEN]);
+        let key = Self::derive(passphrase, salt, params)?;
+        let plaintext = key.unseal(sealed)?;
+        Ok((key, plaintext))
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+    hex.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .filter(|p| p.len() == 2)
+                .and_then(|p| u8::from_str_radix(p, 16).ok())
+                .ok_or_else(|| "Invalid hex data".to_owned())
+        })
+        .collect()
+}
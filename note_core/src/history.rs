@@ -0,0 +1,214 @@
+use crate::attachments;
+use crate::note::Note;
+use crate::ops::{self, NoteFields};
+use std::collections::HashMap;
+
+/// How many commands can be undone.
+const HISTORY_LIMIT: usize = 100;
+
+/// A change to the notes that can be undone.
+pub enum Command {
+    Create(NoteFields),
+    Edit(usize, NoteFields),
+    /// Edit several notes at once, e.g. a find and replace.
+    EditMany(Vec<(usize, NoteFields)>),
+    Delete(usize),
+    /// Move a note to another board column.
+    Move(usize, Option<String>),
+    Tag(usize, Vec<String>),
+    /// Add whole notes, or replace those with the same ID, keeping their dates, e.g. from an import.
+    Import(Vec<Note>),
+}
+
+impl Command {
+    /// What the command does, for undo and redo buttons.
+    fn label(&self, notes: &[Note]) -> String {
+        let title = |idx: &usize| notes.get(*idx).map_or("", |n| n.title.as_str()).to_owned();
+        match self {
+            Command::Create(fields) => format!("Create \"{}\"", fields.title.as_deref().unwrap_or("").trim()),
+            Command::Edit(idx, _) => format!("Edit \"{}\"", title(idx)),
+            Command::EditMany(edits) => format!("Edit {} note(s)", edits.len()),
+            Command::Delete(idx) => format!("Delete \"{}\"", title(idx)),
+            Command::Move(idx, Some(status)) => format!("Move \"{}\" to {status}", title(idx)),
+            Command::Move(idx, None) => format!("Clear the status of \"{}\"", title(idx)),
+            Command::Tag(idx, _) => format!("Change the tags of \"{}\"", title(idx)),
+            Command::Import(imported) => format!("Import {} note(s)", imported.len()),
+        }
+    }
+
+    /// Carry out the command. Returns the index of the note it created or changed.
+    fn run(self, notes: &mut Vec<Note>) -> Result<Option<usize>, String> {
+        match self {
+            Command::Create(fields) => ops::create(notes, fields).map(Some),
+            Command::Edit(idx, fields) => ops::update(notes, idx, fields).map(|()| Some(idx)),
+            Command::EditMany(edits) => {
+                for (idx, fields) in edits {
+                    ops::update(notes, idx, fields)?;
+                }
+                Ok(None)
+            }
+            Command::Delete(idx) => ops::delete(notes, idx).map(|_| None),
+            Command::Move(idx, status) => {
+                let fields = NoteFields { status: Some(status), ..Default::default() };
+                ops::update(notes, idx, fields).map(|()| Some(idx))
+            }
+            Command::Tag(idx, tags) => {
+                let fields = NoteFields { tags: Some(tags), ..Default::default() };
+                ops::update(notes, idx, fields).map(|()| Some(idx))
+            }
+            Command::Import(imported) => {
+                for note in imported {
+                    match notes.iter().position(|n| n.id == note.id) {
+                        Some(i) if notes[i].locked => return Err(format!("\"{}\" is locked", notes[i].title)),
+                        Some(i) => notes[i] = note,
+                        None => notes.push(note),
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// One note before and after a command. `None` means it didn't exist.
+struct Change {
+    index: usize,
+    before: Option<Note>,
+    after: Option<Note>,
+}
+
+/// A command that was applied, with the notes it changed.
+pub struct Entry {
+    pub label: String,
+    /// ID of the note the command was about, if it was about one.
+    pub note: Option<u64>,
+    changes: Vec<Change>,
+}
+
+/// The notes that differ between `before` and `after`, matched by ID.
+fn diff(before: &[Note], after: &[Note]) -> Vec<Change> {
+    let old: HashMap<u64, usize> = before.iter().enumerate().map(|(i, n)| (n.id, i)).collect();
+    let new: HashMap<u64, usize> = after.iter().enumerate().map(|(i, n)| (n.id, i)).collect();
+    let mut changes = Vec::new();
+    for (index, note) in before.iter().enumerate() {
+        match new.get(&note.id).map(|&i| &after[i]) {
+            None => changes.push(Change { index, before: Some(note.clone()), after: None }),
+            Some(changed) if changed != note => {
+                changes.push(Change { index, before: Some(note.clone()), after: Some(changed.clone()) });
+            }
+            Some(_) => {}
+        }
+    }
+    for (index, note) in after.iter().enumerate() {
+        if !old.contains_key(&note.id) {
+            changes.push(Change { index, before: None, after: Some(note.clone()) });
+        }
+    }
+    changes
+}
+
+/// Put each changed note into its `to` state, from its `from` state.
+fn restore<'a>(notes: &mut Vec<Note>, changes: impl Iterator<Item = (usize, &'a Option<Note>, &'a Option<Note>)>) {
+    for (index, from, to) in changes {
+        let position = from.as_ref().and_then(|f| notes.iter().position(|n| n.id == f.id));
+        match (position, to) {
+            (Some(i), Some(to)) => notes[i] = to.clone(),
+            (Some(i), None) => {
+                notes.remove(i);
+            }
+            (None, Some(to)) => notes.insert(index.min(notes.len()), to.clone()),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Applies commands to the notes and keeps them for undo and redo.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+impl History {
+    /// Apply `command` and record it. Returns the index of the note it created or changed.
+    /// A command that fails or changes nothing isn't recorded.
+    pub fn apply(&mut self, notes: &mut Vec<Note>, command: Command) -> Result<Option<usize>, String> {
+        let label = command.label(notes);
+        let before = notes.clone();
+        let result = command.run(notes);
+        if result.is_err() {
+            // Leave the notes as they were if a command stopped part way.
+            *notes = before;
+            return result;
+        }
+        let changes = diff(&before, notes);
+        if changes.is_empty() {
+            return result;
+        }
+        let note = match result {
+            Ok(Some(idx)) => Some(notes[idx].id),
+            _ => changes.first().and_then(|c| c.before.as_ref().or(c.after.as_ref())).map(|n| n.id),
+        };
+        self.undo.push(Entry { label, note, changes });
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        result
+    }
+
+    /// Revert the last command. Returns it, or `None` if there is nothing to undo.
+    pub fn undo(&mut self, notes: &mut Vec<Note>) -> Option<&Entry> {
+        let entry = self.undo.pop()?;
+        restore(notes, entry.changes.iter().rev().map(|c| (c.index, &c.after, &c.before)));
+        self.redo.push(entry);
+        self.redo.last()
+    }
+
+    /// Apply the last undone command again.
+    pub fn redo(&mut self, notes: &mut Vec<Note>) -> Option<&Entry> {
+        let entry = self.redo.pop()?;
+        restore(notes, entry.changes.iter().map(|c| (c.index, &c.before, &c.after)));
+        self.undo.push(entry);
+        self.undo.last()
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|e| e.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|e| e.label.as_str())
+    }
+
+    /// Every version of a note that undo or redo can bring back, so files they refer to are kept.
+    pub fn notes(&self) -> impl Iterator<Item = &Note> {
+        self.undo
+            .iter()
+            .chain(&self.redo)
+            .flat_map(|e| &e.changes)
+            .flat_map(|c| c.before.iter().chain(&c.after))
+    }
+
+    /// Delete attachment blobs that neither `notes` nor a command that can be undone or redone
+    /// refers to. Returns how many files were removed.
+    pub fn collect_garbage(&self, notes: &[Note]) -> usize {
+        attachments::collect_garbage(notes.iter().chain(self.notes()))
+    }
+
+    /// Forget the commands that hold a copy of note `id`, e.g. once it is locked so no copy of
+    /// its content is kept. Older commands go too, so the ones left still undo in order.
+    pub fn forget(&mut self, id: u64) {
+        let holds = |e: &Entry| e.changes.iter().any(|c| c.before.iter().chain(&c.after).any(|n| n.id == id));
+        if let Some(last) = self.undo.iter().rposition(holds) {
+            self.undo.drain(..=last);
+        }
+        self.redo.clear();
+    }
+
+    /// Forget every command, e.g. when the store is locked and the notes leave memory.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
@@ -0,0 +1,10 @@
+pub mod attachments;
+pub mod crypto;
+pub mod history;
+pub mod links;
+pub mod note;
+pub mod note_lock;
+pub mod ops;
+pub mod properties;
+pub mod records;
+pub mod storage;
@@ -0,0 +1,144 @@
+use crate::note::Note;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// A `[[Title]]` or `[[Title|id]]` reference found in note content.
+///
+/// Links are bound to the target's ID when the note is saved, so the stored
+/// form is `[[Title|id]]`. The title part is only display text; the ID decides
+/// which note the link opens, so renaming the target never breaks it.
+pub struct Link {
+    pub range: Range<usize>,
+    pub title: String,
+    pub id: Option<u64>,
+}
+
+fn link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[\[([^\[\]|\n]+?)(?:\|(\d+))?\]\]").unwrap())
+}
+
+/// Find every wiki link in `content`, in order.
+pub fn parse_links(content: &str) -> Vec<Link> {
+    link_regex()
+        .captures_iter(content)
+        .map(|caps| Link {
+            range: caps.get(0).unwrap().range(),
+            title: caps[1].trim().to_owned(),
+            id: caps.get(2).and_then(|m| m.as_str().parse().ok()),
+        })
+        .collect()
+}
+
+/// Index of the note with the given title, ignoring case and surrounding whitespace.
+pub fn find_by_title(notes: &[Note], title: &str) -> Option<usize> {
+    let title = title.trim().to_lowercase();
+    notes.iter().position(|n| n.title.trim().to_lowercase() == title)
+}
+
+/// Index of the note a link points to. Bound links resolve by ID only;
+/// unbound links fall back to a title lookup.
+pub fn resolve(link: &Link, notes: &[Note]) -> Option<usize> {
+    match link.id {
+        Some(id) => notes.iter().position(|n| n.id == id),
+        None => find_by_title(notes, &link.title),
+    }
+}
+
+/// Format a link to `note` in its stored form.
+pub fn link_to(note: &Note) -> String {
+    format!("[[{}|{}]]", note.title.trim(), note.id)
+}
+
+/// Bind every unbound link whose title matches an existing note to that note's ID.
+pub fn bind_links(content: &str, notes: &[Note]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in parse_links(content) {
+        if link.id.is_none() {
+            if let Some(idx) = find_by_title(notes, &link.title) {
+                out.push_str(&content[last..link.range.start]);
+                out.push_str(&link_to(&notes[idx]));
+                last = link.range.end;
+            }
+        }
+    }
+    out.push_str(&content[last..]);
+    out
+}
+
+/// Refresh the display text of every link bound to `id` after that note was renamed.
+pub fn rename_links(notes: &mut [Note], id: u64, new_title: &str) {
+    let replacement = format!("[[{}|{id}]]", new_title.trim());
+    for note in notes.iter_mut() {
+        let links = parse_links(&note.content);
+        if !links.iter().any(|l| l.id == Some(id)) {
+            continue;
+        }
+        let mut content = String::with_capacity(note.content.len());
+        let mut last = 0;
+        for link in links.into_iter().filter(|l| l.id == Some(id)) {
+            content.push_str(&note.content[last..link.range.start]);
+            content.push_str(&replacement);
+            last = link.range.end;
+        }
+        content.push_str(&note.content[last..]);
+        note.content = content;
+    }
+}
+
+/// IDs of the notes that `note` links to.
+pub fn outgoing(note: &Note, notes: &[Note]) -> Vec<u64> {
+    let mut ids: Vec<u64> = parse_links(&note.content)
+        .iter()
+        .filter_map(|l| resolve(l, notes))
+        .map(|idx| notes[idx].id)
+        .filter(|&id| id != note.id)
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/// Indices of the notes that link to the note with the given ID.
+pub fn backlinks(notes: &[Note], id: u64) -> Vec<usize> {
+    notes
+        .iter()
+        .enumerate()
+        .filter(|(_, n)| n.id != id && outgoing(n, notes).contains(&id))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// If the text before `cursor` (a byte offset) ends in an unfinished `[[...`,
+/// returns where the `[[` starts and the partial title typed so far.
+pub fn pending_link(text: &str, cursor: usize) -> Option<(usize, &str)> {
+    let before = &text[..cursor];
+    let start = before.rfind("[[")?;
+    let partial = &before[start + 2..];
+    if partial.contains(']') || partial.contains('\n') || partial.contains('|') {
+        return None;
+    }
+    Some((start, partial))
+}
+
+/// Titles matching a partially typed link, best matches first.
+pub fn suggestions<'a>(notes: &'a [Note], partial: &str, limit: usize) -> Vec<&'a Note> {
+    let partial = partial.trim().to_lowercase();
+    let mut matches: Vec<(bool, &Note)> = notes
+        .iter()
+        .filter_map(|n| {
+            let title = n.title.to_lowercase();
+            if title.starts_with(&partial) {
+                Some((true, n))
+            } else if title.contains(&partial) {
+                Some((false, n))
+            } else {
+                None
+            }
+        })
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.title.cmp(&b.1.title)));
+    matches.into_iter().take(limit).map(|(_, n)| n).collect()
+}
@@ -0,0 +1,149 @@
+use crate::attachments::Attachment;
+use crate::properties::Properties;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Serialize, Deserialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The highest ID handed out or known to have been used, so a deleted note's ID is never reused.
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+/// Date-only due dates fall at this local time.
+const DEFAULT_DUE_HOUR: u32 = 9;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Note {
+    /// Stable identifier used by links. Notes saved before IDs existed load as 0
+    /// and are given a fresh ID by `Storage::load_notes`.
+    #[serde(default)]
+    pub id: u64,
+    pub title: String,
+    pub content: String,
+    pub created: u64,
+    pub edited: u64,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When the note is due, as a Unix timestamp.
+    #[serde(default)]
+    pub due: Option<u64>,
+    /// Board column the note is in, e.g. "In Progress".
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Custom typed properties, keyed by name.
+    #[serde(default)]
+    pub properties: Properties,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// Content is encrypted with the locked-notes passphrase. On disk `content` is empty
+    /// and `sealed` holds the encrypted content as hex.
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub sealed: String,
+    /// A locked note whose content has been decrypted into `content` for this session.
+    #[serde(skip)]
+    pub unsealed: bool,
+}
+
+impl Note {
+    pub fn new(id: u64, title: &str) -> Self {
+        let now = Self::now_ts();
+        Self {
+            id,
+            title: title.to_owned(),
+            content: String::new(),
+            created: now,
+            edited: now,
+            tags: Vec::new(),
+            due: None,
+            status: None,
+            properties: Properties::new(),
+            attachments: Vec::new(),
+            locked: false,
+            sealed: String::new(),
+            unsealed: false,
+        }
+    }
+
+    pub fn now_ts() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    /// Parse a comma-separated tag list as typed in the editor.
+    /// Leading `#` is dropped and duplicates (ignoring case) are removed.
+    pub fn parse_tags(input: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in input.split(',') {
+            let tag = tag.trim().trim_start_matches('#').trim();
+            if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_owned());
+            }
+        }
+        tags
+    }
+
+    /// Whether the title, content or a tag contains `query`, ignoring case.
+    pub fn matches_text(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        query.is_empty()
+            || self.title.to_lowercase().contains(&query)
+            || self.content.to_lowercase().contains(&query)
+            || self.tags.iter().any(|t| t.to_lowercase().contains(&query))
+    }
+
+    /// A locked note whose content is not available right now.
+    pub fn is_sealed(&self) -> bool {
+        self.locked && !self.unsealed
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// A new ID, greater than every ID in `notes` and every ID used before. Links bound to a
+    /// deleted note therefore never point at a new one.
+    pub fn next_id(notes: &[Note]) -> u64 {
+        Self::reserve_ids(notes.iter().map(|n| n.id).max().unwrap_or(0));
+        LAST_ID.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Record that IDs up to `id` have been used, so `next_id` only hands out higher ones.
+    pub fn reserve_ids(id: u64) {
+        LAST_ID.fetch_max(id, Ordering::SeqCst);
+    }
+
+    /// The highest ID used so far, saved with the notes by `Storage`.
+    pub fn last_id() -> u64 {
+        LAST_ID.load(Ordering::SeqCst)
+    }
+}
+
+/// A Unix timestamp as an RFC 3339 date and time in UTC.
+pub fn rfc3339(ts: u64) -> String {
+    Utc.timestamp_opt(ts as i64, 0)
+        .single()
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default()
+}
+
+/// Parse `YYYY-MM-DD` or `YYYY-MM-DD HH:MM` in local time.
+pub fn parse_due(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let naive = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::from_hms_opt(DEFAULT_DUE_HOUR, 0, 0).unwrap()))
+        })?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    u64::try_from(local.timestamp()).ok()
+}
+
+/// Format a due timestamp the way `parse_due` reads it.
+pub fn format_due(ts: u64) -> String {
+    Local
+        .timestamp_opt(ts as i64, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_owned())
+}
@@ -0,0 +1,122 @@
+use crate::crypto::{from_hex, to_hex, SealingKey};
+use crate::history::History;
+use crate::note::Note;
+use std::borrow::Cow;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+/// Key for locked notes while they are unlocked, and when they were unlocked.
+/// Separate from the key of the encrypted store.
+static NOTE_KEY: Mutex<Option<(SealingKey, Instant)>> = Mutex::new(None);
+
+pub fn is_unlocked() -> bool {
+    NOTE_KEY.lock().unwrap().is_some()
+}
+
+/// Whether the unlock has lasted longer than `timeout`. A zero timeout lasts the whole session.
+pub fn expired(timeout: Duration) -> bool {
+    !timeout.is_zero()
+        && NOTE_KEY
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|(_, since)| since.elapsed() >= timeout)
+}
+
+/// Decrypt every locked note `passphrase` opens. Returns how many were unlocked.
+pub fn unlock(notes: &mut [Note], passphrase: &str) -> Result<usize, String> {
+    let mut session = NOTE_KEY.lock().unwrap();
+    // Keys derived from the typed passphrase, one per distinct salt.
+    let mut keys: Vec<SealingKey> = Vec::new();
+    let mut opened = 0;
+    let mut error = None;
+    for note in notes.iter_mut().filter(|n| n.locked && !n.unsealed) {
+        let sealed = from_hex(&note.sealed)?;
+        let plaintext = match keys.iter().find(|k| k.matches(&sealed)) {
+            Some(key) => key.unseal(&sealed),
+            None => SealingKey::open(&sealed, passphrase).map(|(key, plaintext)| {
+                keys.push(key);
+                plaintext
+            }),
+        };
+        match plaintext.and_then(|p| String::from_utf8(p.to_vec()).map_err(|e| e.to_string())) {
+            Ok(content) => {
+                note.content = content;
+                note.unsealed = true;
+                opened += 1;
+            }
+            Err(e) => error = Some(e),
+        }
+    }
+    if opened == 0 {
+        return Err(error.unwrap_or_else(|| "No locked notes to unlock".to_owned()));
+    }
+    // Notes are sealed again with this key on save, so they converge on one salt.
+    *session = keys.into_iter().next().map(|key| (key, Instant::now()));
+    Ok(opened)
+}
+
+/// Lock the note at `idx`. Without an unlocked key, `passphrase` must open the other locked
+/// notes, or becomes the locked-notes passphrase if there are none yet.
+pub fn lock_note(notes: &mut [Note], idx: usize, passphrase: &str) -> Result<(), String> {
+    let mut session = NOTE_KEY.lock().unwrap();
+    if session.is_none() {
+        let key = match notes.iter().find(|n| n.locked && !n.sealed.is_empty()) {
+            Some(other) => SealingKey::open(&from_hex(&other.sealed)?, passphrase)?.0,
+            None => SealingKey::new(passphrase)?,
+        };
+        *session = Some((key, Instant::now()));
+    }
+    notes[idx].locked = true;
+    notes[idx].unsealed = true;
+    Ok(())
+}
+
+/// Take the lock off `note`; its content is stored in plain text again.
+pub fn remove_lock(note: &mut Note) {
+    note.locked = false;
+    note.unsealed = false;
+    note.sealed.clear();
+}
+
+/// Seal the current content of every unlocked note, then forget the key and wipe the content
+/// from memory. Later saves write the sealed content as it is.
+pub fn lock_all(notes: &mut [Note]) {
+    let session = NOTE_KEY.lock().unwrap().take();
+    for note in notes.iter_mut().filter(|n| n.locked) {
+        if let (true, Some((key, _))) = (note.unsealed, &session) {
+            note.sealed = to_hex(&key.seal(note.content.as_bytes()));
+        }
+        note.content.zeroize();
+        note.unsealed = false;
+    }
+}
+
+/// Lock every locked note again, and drop the commands that hold a plain copy of their content.
+pub fn relock(notes: &mut [Note], history: &mut History) {
+    lock_all(notes);
+    for note in notes.iter().filter(|n| n.locked) {
+        history.forget(note.id);
+    }
+}
+
+/// The notes as they should be written to disk: locked notes hold only their sealed content.
+pub fn sealed_copy(notes: &[Note]) -> Cow<'_, [Note]> {
+    if !notes.iter().any(|n| n.locked && (n.unsealed || !n.content.is_empty())) {
+        return Cow::Borrowed(notes);
+    }
+    let session = NOTE_KEY.lock().unwrap();
+    let mut copy = notes.to_vec();
+    for note in copy.iter_mut().filter(|n| n.locked) {
+        if note.unsealed {
+            match session.as_ref() {
+                Some((key, _)) => note.sealed = to_hex(&key.seal(note.content.as_bytes())),
+                None => eprintln!("Locked note \"{}\" has no key; keeping its last saved content", note.title),
+            }
+        }
+        note.content.zeroize();
+        note.unsealed = false;
+    }
+    Cow::Owned(copy)
+}
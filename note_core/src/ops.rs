@@ -0,0 +1,158 @@
+use crate::attachments::Attachment;
+use crate::links;
+use crate::note::{parse_due, Note};
+use crate::properties::Properties;
+
+/// Fields to set on a note. `None` leaves a field as it is; for `due` and `status`,
+/// `Some(None)` clears it.
+#[derive(Default, Clone)]
+pub struct NoteFields {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub due: Option<Option<u64>>,
+    pub status: Option<Option<String>>,
+    pub properties: Option<Properties>,
+    pub attachments: Option<Vec<Attachment>>,
+}
+
+impl NoteFields {
+    /// Fields from the text typed into the editor: a title, a comma-separated tag list and a
+    /// due date as `parse_due` reads it. An empty due date clears it; one that can't be read
+    /// is an error rather than clearing it.
+    pub fn from_editor(title: &str, tags: &str, due: &str) -> Result<Self, String> {
+        if title.trim().is_empty() {
+            return Err("A note needs a title".to_owned());
+        }
+        let due = match due.trim() {
+            "" => None,
+            due => Some(parse_due(due).ok_or_else(|| format!("\"{due}\" isn't a date; use YYYY-MM-DD or YYYY-MM-DD HH:MM"))?),
+        };
+        Ok(Self {
+            title: Some(title.to_owned()),
+            tags: Some(Note::parse_tags(tags)),
+            due: Some(due),
+            ..Default::default()
+        })
+    }
+
+    fn apply(self, note: &mut Note) {
+        if let Some(title) = self.title {
+            note.title = title.trim().to_owned();
+        }
+        if let Some(content) = self.content {
+            note.content = content;
+        }
+        if let Some(tags) = self.tags {
+            note.tags = tags;
+        }
+        if let Some(due) = self.due {
+            note.due = due;
+        }
+        if let Some(status) = self.status {
+            note.status = status;
+        }
+        if let Some(properties) = self.properties {
+            note.properties = properties;
+        }
+        if let Some(attachments) = self.attachments {
+            note.attachments = attachments;
+        }
+    }
+}
+
+fn check_index(notes: &[Note], idx: usize) -> Result<(), String> {
+    if idx >= notes.len() {
+        return Err(format!("No note at index {idx}"));
+    }
+    Ok(())
+}
+
+fn check_unsealed(note: &Note) -> Result<(), String> {
+    if note.is_sealed() {
+        return Err(format!("\"{}\" is locked; unlock it first", note.title));
+    }
+    Ok(())
+}
+
+/// Index of the note with ID `reference`, or else the title `reference`.
+pub fn find(notes: &[Note], reference: &str) -> Result<usize, String> {
+    reference
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| notes.iter().position(|n| n.id == id))
+        .or_else(|| links::find_by_title(notes, reference))
+        .ok_or_else(|| format!("No note with ID or title \"{reference}\""))
+}
+
+/// Indices of the notes whose title, content or tags contain `query`, optionally only those
+/// tagged `tag`.
+pub fn search(notes: &[Note], query: &str, tag: Option<&str>) -> Vec<usize> {
+    (0..notes.len())
+        .filter(|&i| tag.is_none_or(|t| notes[i].has_tag(t)) && notes[i].matches_text(query))
+        .collect()
+}
+
+/// Add a note and return its index. Links in the content to existing notes are bound to them.
+pub fn create(notes: &mut Vec<Note>, fields: NoteFields) -> Result<usize, String> {
+    let title = fields.title.as_deref().unwrap_or("").trim();
+    if title.is_empty() {
+        return Err("A note needs a title".to_owned());
+    }
+    let mut note = Note::new(Note::next_id(notes), title);
+    let mut fields = fields;
+    fields.content = fields.content.map(|c| links::bind_links(&c, notes));
+    fields.apply(&mut note);
+    notes.push(note);
+    Ok(notes.len() - 1)
+}
+
+/// Change the given fields of a note. Links to it from other notes follow a new title.
+/// The edit time is only updated if something changed.
+pub fn update(notes: &mut [Note], idx: usize, fields: NoteFields) -> Result<(), String> {
+    check_index(notes, idx)?;
+    if fields.title.as_ref().is_some_and(|t| t.trim().is_empty()) {
+        return Err("A note needs a title".to_owned());
+    }
+    if fields.content.is_some() {
+        check_unsealed(&notes[idx])?;
+    }
+    let mut fields = fields;
+    fields.content = fields.content.map(|c| links::bind_links(&c, notes));
+    let before = notes[idx].clone();
+    let note = &mut notes[idx];
+    fields.apply(note);
+    if *note == before {
+        return Ok(());
+    }
+    note.edited = Note::now_ts();
+    if note.title != before.title {
+        let (id, title) = (note.id, note.title.clone());
+        links::rename_links(notes, id, &title);
+    }
+    Ok(())
+}
+
+/// Add `text` to the end of a note's content, on a new line.
+pub fn append(notes: &mut [Note], idx: usize, text: &str) -> Result<(), String> {
+    check_index(notes, idx)?;
+    check_unsealed(&notes[idx])?;
+    if text.trim().is_empty() {
+        return Err("Nothing to append".to_owned());
+    }
+    let text = links::bind_links(text, notes);
+    let note = &mut notes[idx];
+    if !note.content.is_empty() && !note.content.ends_with('\n') {
+        note.content.push('\n');
+    }
+    note.content.push_str(&text);
+    note.edited = Note::now_ts();
+    Ok(())
+}
+
+/// Remove a note and return it. Its attachments stay on disk until
+/// `attachments::collect_garbage` runs.
+pub fn delete(notes: &mut Vec<Note>, idx: usize) -> Result<Note, String> {
+    check_index(notes, idx)?;
+    Ok(notes.remove(idx))
+}
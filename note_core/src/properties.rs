@@ -0,0 +1,110 @@
+use crate::note::Note;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A typed value of a custom note property.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum PropertyValue {
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    Checkbox(bool),
+    /// One of the values used for this property across all notes, or a new one.
+    Select(String),
+}
+
+pub type Properties = BTreeMap<String, PropertyValue>;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PropertyKind {
+    Text,
+    Number,
+    Date,
+    Checkbox,
+    Select,
+}
+
+impl PropertyKind {
+    pub const ALL: [PropertyKind; 5] = [
+        PropertyKind::Text,
+        PropertyKind::Number,
+        PropertyKind::Date,
+        PropertyKind::Checkbox,
+        PropertyKind::Select,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PropertyKind::Text => "Text",
+            PropertyKind::Number => "Number",
+            PropertyKind::Date => "Date",
+            PropertyKind::Checkbox => "Checkbox",
+            PropertyKind::Select => "Select",
+        }
+    }
+
+    pub fn empty_value(self) -> PropertyValue {
+        match self {
+            PropertyKind::Text => PropertyValue::Text(String::new()),
+            PropertyKind::Number => PropertyValue::Number(0.0),
+            PropertyKind::Date => PropertyValue::Date(Local::now().date_naive()),
+            PropertyKind::Checkbox => PropertyValue::Checkbox(false),
+            PropertyKind::Select => PropertyValue::Select(String::new()),
+        }
+    }
+}
+
+impl PropertyValue {
+    pub fn display(&self) -> String {
+        match self {
+            PropertyValue::Text(s) | PropertyValue::Select(s) => s.clone(),
+            PropertyValue::Number(n) => n.to_string(),
+            PropertyValue::Date(d) => d.format("%Y-%m-%d").to_string(),
+            PropertyValue::Checkbox(b) => if *b { "✔" } else { "✖" }.to_owned(),
+        }
+    }
+
+    /// Order two values of the same property. Values of different types compare by their text.
+    pub fn compare(&self, other: &PropertyValue) -> Ordering {
+        match (self, other) {
+            (PropertyValue::Number(a), PropertyValue::Number(b)) => a.total_cmp(b),
+            (PropertyValue::Date(a), PropertyValue::Date(b)) => a.cmp(b),
+            (PropertyValue::Checkbox(a), PropertyValue::Checkbox(b)) => a.cmp(b),
+            _ => self.display().to_lowercase().cmp(&other.display().to_lowercase()),
+        }
+    }
+
+    /// Whether the value matches text typed into the sidebar property filter.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        match self {
+            PropertyValue::Checkbox(b) => matches!(
+                (b, query.as_str()),
+                (true, "true" | "yes" | "✔") | (false, "false" | "no" | "✖")
+            ),
+            _ => self.display().to_lowercase().contains(&query),
+        }
+    }
+}
+
+/// Every property name used by any note.
+pub fn all_names(notes: &[Note]) -> BTreeSet<String> {
+    notes.iter().flat_map(|n| n.properties.keys().cloned()).collect()
+}
+
+/// Values already used for a select property, for its dropdown.
+pub fn select_options(notes: &[Note], name: &str) -> BTreeSet<String> {
+    notes
+        .iter()
+        .filter_map(|n| match n.properties.get(name) {
+            Some(PropertyValue::Select(s)) if !s.is_empty() => Some(s.clone()),
+            _ => None,
+        })
+        .collect()
+}
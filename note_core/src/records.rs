@@ -0,0 +1,478 @@
+use crate::links;
+use crate::note::{rfc3339, Note};
+use chrono::DateTime;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Every field of the JSON Lines and CSV schema, in export order; this is also the CSV header.
+/// The schema is documented in the README and changes only by adding fields.
+pub const FIELDS: [&str; 8] = ["id", "title", "content", "created", "edited", "tags", "due", "status"];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecordFormat {
+    JsonLines,
+    Csv,
+}
+
+impl RecordFormat {
+    /// `.csv` files are CSV; anything else is read as JSON Lines.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(e) if e.eq_ignore_ascii_case("csv") => RecordFormat::Csv,
+            _ => RecordFormat::JsonLines,
+        }
+    }
+}
+
+/// What identifies the note a record refers to.
+#[derive(Clone, Copy, PartialEq)]
+pub enum MatchOn {
+    Id,
+    /// Case-insensitive title.
+    Title,
+}
+
+/// What to do with a record that matches an existing note.
+#[derive(Clone, Copy, PartialEq)]
+pub enum OnMatch {
+    Skip,
+    /// Update the note only if the record's `edited` time is later than the note's.
+    Newer,
+    Overwrite,
+}
+
+impl OnMatch {
+    pub const ALL: [OnMatch; 3] = [OnMatch::Skip, OnMatch::Newer, OnMatch::Overwrite];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OnMatch::Skip => "Skip",
+            OnMatch::Newer => "Update if newer",
+            OnMatch::Overwrite => "Overwrite",
+        }
+    }
+}
+
+/// A problem with one line of the input. Line 1 of a CSV file is its header.
+#[derive(Debug)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+/// A validated record. `None` means the field was left out, so a matched note keeps its value;
+/// for `due` and `status`, `Some(None)` clears it.
+#[derive(Clone, Debug, Default)]
+pub struct Record {
+    pub line: usize,
+    pub id: Option<u64>,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub created: Option<u64>,
+    pub edited: Option<u64>,
+    pub tags: Option<Vec<String>>,
+    pub due: Option<Option<u64>>,
+    pub status: Option<Option<String>>,
+}
+
+/// A note as it is exported, with fields in schema order.
+#[derive(Serialize)]
+pub struct ExportRecord<'a> {
+    id: u64,
+    title: &'a str,
+    content: &'a str,
+    created: String,
+    edited: String,
+    tags: &'a [String],
+    due: Option<String>,
+    status: Option<&'a str>,
+}
+
+impl<'a> ExportRecord<'a> {
+    pub fn new(note: &'a Note) -> Self {
+        Self {
+            id: note.id,
+            title: &note.title,
+            content: &note.content,
+            created: rfc3339(note.created),
+            edited: rfc3339(note.edited),
+            tags: &note.tags,
+            due: note.due.map(rfc3339),
+            status: note.status.as_deref(),
+        }
+    }
+}
+
+/// The notes at `indices`, one JSON object per line.
+pub fn to_jsonl(notes: &[Note], indices: &[usize]) -> Result<String, String> {
+    let mut out = String::new();
+    for &i in indices {
+        out.push_str(&serde_json::to_string(&ExportRecord::new(&notes[i])).map_err(|e| e.to_string())?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The notes at `indices` as CSV with a header row. Tags are comma-separated in one column.
+pub fn to_csv(notes: &[Note], indices: &[usize]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(FIELDS).map_err(|e| e.to_string())?;
+    for &i in indices {
+        let record = ExportRecord::new(&notes[i]);
+        writer
+            .write_record([
+                record.id.to_string(),
+                record.title.to_owned(),
+                record.content.to_owned(),
+                record.created,
+                record.edited,
+                record.tags.join(","),
+                record.due.unwrap_or_default(),
+                record.status.unwrap_or_default().to_owned(),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+pub fn export(notes: &[Note], indices: &[usize], format: RecordFormat) -> Result<String, String> {
+    match format {
+        RecordFormat::JsonLines => to_jsonl(notes, indices),
+        RecordFormat::Csv => to_csv(notes, indices),
+    }
+}
+
+/// A missing value: JSON null or an empty CSV cell.
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn string(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err("expected a string".to_owned()),
+    }
+}
+
+fn id(value: &Value) -> Result<u64, String> {
+    let id = match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    id.filter(|&id| id > 0).ok_or_else(|| "expected a positive whole number".to_owned())
+}
+
+fn time(value: &Value) -> Result<u64, String> {
+    let ts = match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .or_else(|| DateTime::parse_from_rfc3339(s.trim()).ok().and_then(|dt| u64::try_from(dt.timestamp()).ok())),
+        _ => None,
+    };
+    ts.ok_or_else(|| "expected an RFC 3339 time like 2024-05-01T09:30:00Z, or Unix seconds".to_owned())
+}
+
+fn tags(value: &Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::Null => Ok(Vec::new()),
+        Value::String(s) => Ok(Note::parse_tags(s)),
+        Value::Array(items) => {
+            let tags: Result<Vec<&str>, String> = items
+                .iter()
+                .map(|t| t.as_str().ok_or_else(|| "expected a list of strings".to_owned()))
+                .collect();
+            Ok(Note::parse_tags(&tags?.join(",")))
+        }
+        _ => Err("expected a list of strings or a comma-separated string".to_owned()),
+    }
+}
+
+/// Validate the fields of one line into a record.
+fn record(line: usize, fields: Map<String, Value>) -> Result<Record, RowError> {
+    let error = |field: &str, message: String| RowError { line, message: format!("{field}: {message}") };
+    let mut record = Record { line, ..Record::default() };
+    for (field, value) in &fields {
+        match field.as_str() {
+            "id" if !is_empty(value) => record.id = Some(id(value).map_err(|e| error(field, e))?),
+            "title" => {
+                let title = string(value).map_err(|e| error(field, e))?;
+                let title = title.trim();
+                if title.is_empty() {
+                    return Err(error(field, "must not be empty".to_owned()));
+                }
+                if title.contains('\n') {
+                    return Err(error(field, "must be a single line".to_owned()));
+                }
+                record.title = Some(title.to_owned());
+            }
+            "content" => record.content = Some(string(value).map_err(|e| error(field, e))?),
+            "created" if !is_empty(value) => record.created = Some(time(value).map_err(|e| error(field, e))?),
+            "edited" if !is_empty(value) => record.edited = Some(time(value).map_err(|e| error(field, e))?),
+            "tags" => record.tags = Some(tags(value).map_err(|e| error(field, e))?),
+            "due" if is_empty(value) => record.due = Some(None),
+            "due" => record.due = Some(Some(time(value).map_err(|e| error(field, e))?)),
+            "status" if is_empty(value) => record.status = Some(None),
+            "status" => record.status = Some(Some(string(value).map_err(|e| error(field, e))?.trim().to_owned())),
+            "id" | "created" | "edited" => {}
+            _ => return Err(error(field, format!("unknown field; expected one of {}", FIELDS.join(", ")))),
+        }
+    }
+    if let (Some(created), Some(edited)) = (record.created, record.edited) {
+        if edited < created {
+            return Err(error("edited", "is before created".to_owned()));
+        }
+    }
+    Ok(record)
+}
+
+/// Collect records, or every error if any line is invalid.
+fn finish(results: Vec<Result<Record, RowError>>) -> Result<Vec<Record>, Vec<RowError>> {
+    let (records, errors): (Vec<_>, Vec<_>) = results.into_iter().partition(Result::is_ok);
+    if errors.is_empty() {
+        Ok(records.into_iter().filter_map(Result::ok).collect())
+    } else {
+        Err(errors.into_iter().filter_map(Result::err).collect())
+    }
+}
+
+/// Parse JSON Lines: one object per line. Blank lines are ignored.
+pub fn parse_jsonl(text: &str) -> Result<Vec<Record>, Vec<RowError>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let results = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| match serde_json::from_str::<Value>(line) {
+            Ok(Value::Object(fields)) => record(i + 1, fields),
+            Ok(_) => Err(RowError { line: i + 1, message: "expected a JSON object".to_owned() }),
+            Err(e) => Err(RowError { line: i + 1, message: format!("invalid JSON: {e}") }),
+        })
+        .collect();
+    finish(results)
+}
+
+/// Parse CSV with a header row naming the columns. Columns may be in any order and any may be
+/// left out, but every row must have the same number of cells as the header.
+pub fn parse_csv(text: &str) -> Result<Vec<Record>, Vec<RowError>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new().from_reader(text.as_bytes());
+    let header: Vec<String> = match reader.headers() {
+        Ok(header) => header.iter().map(|h| h.trim().to_lowercase()).collect(),
+        Err(e) => return Err(vec![RowError { line: 1, message: e.to_string() }]),
+    };
+    for (i, column) in header.iter().enumerate() {
+        if !FIELDS.contains(&column.as_str()) {
+            let message = format!("unknown column \"{column}\"; expected some of {}", FIELDS.join(", "));
+            return Err(vec![RowError { line: 1, message }]);
+        }
+        if header[..i].contains(column) {
+            return Err(vec![RowError { line: 1, message: format!("column \"{column}\" appears twice") }]);
+        }
+    }
+    let mut results = Vec::new();
+    for row in reader.records() {
+        results.push(match row {
+            Ok(row) => {
+                let line = row.position().map_or(0, |p| p.line() as usize);
+                let fields = header.iter().cloned().zip(row.iter().map(|cell| Value::String(cell.to_owned()))).collect();
+                record(line, fields)
+            }
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line() as usize);
+                let message = match e.kind() {
+                    csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+                        format!("expected {expected_len} cell(s) like the header, found {len}")
+                    }
+                    csv::ErrorKind::Utf8 { .. } => "not valid UTF-8".to_owned(),
+                    _ => e.to_string(),
+                };
+                Err(RowError { line, message })
+            }
+        });
+    }
+    finish(results)
+}
+
+pub fn parse(text: &str, format: RecordFormat) -> Result<Vec<Record>, Vec<RowError>> {
+    match format {
+        RecordFormat::JsonLines => parse_jsonl(text),
+        RecordFormat::Csv => parse_csv(text),
+    }
+}
+
+/// What importing one record will do.
+#[derive(Debug)]
+pub enum Action {
+    Add(Record),
+    /// Merge the record into the note with this ID.
+    Update(u64, Record),
+    /// The record matches a note but is left out by the merge rule.
+    Skip,
+}
+
+/// Match records against `notes`. Fails with every problem found, e.g. two records with the
+/// same key, or a new note without a title.
+pub fn plan(records: Vec<Record>, notes: &[Note], match_on: MatchOn, on_match: OnMatch) -> Result<Vec<Action>, Vec<RowError>> {
+    let mut actions = Vec::new();
+    let mut errors = Vec::new();
+    // Key of each record seen so far, with its line, to catch duplicates in the input.
+    let mut seen: Vec<(String, usize)> = Vec::new();
+    for record in records {
+        let line = record.line;
+        let key = match match_on {
+            MatchOn::Id => record.id.map(|id| id.to_string()),
+            MatchOn::Title => record.title.as_ref().map(|t| t.to_lowercase()),
+        };
+        if let Some(key) = &key {
+            if let Some((_, first)) = seen.iter().find(|(k, _)| k == key) {
+                let field = if match_on == MatchOn::Id { "id" } else { "title" };
+                errors.push(RowError { line, message: format!("duplicate {field} (first on line {first})") });
+                continue;
+            }
+            seen.push((key.clone(), line));
+        } else if match_on == MatchOn::Title {
+            errors.push(RowError { line, message: "title is required when matching on title".to_owned() });
+            continue;
+        }
+        let existing = match match_on {
+            MatchOn::Id => record.id.and_then(|id| notes.iter().position(|n| n.id == id)),
+            MatchOn::Title => record.title.as_deref().and_then(|t| links::find_by_title(notes, t)),
+        };
+        match existing {
+            Some(i) if notes[i].locked => {
+                errors.push(RowError { line, message: format!("note \"{}\" is locked", notes[i].title) });
+            }
+            Some(i) => actions.push(match on_match {
+                OnMatch::Skip => Action::Skip,
+                OnMatch::Newer if record.edited.is_some_and(|edited| edited <= notes[i].edited) => Action::Skip,
+                OnMatch::Newer | OnMatch::Overwrite => Action::Update(notes[i].id, record),
+            }),
+            None if record.title.is_none() => {
+                errors.push(RowError { line, message: "title is required for a new note".to_owned() });
+            }
+            None => actions.push(Action::Add(record)),
+        }
+    }
+    if errors.is_empty() {
+        Ok(actions)
+    } else {
+        Err(errors)
+    }
+}
+
+#[derive(Default)]
+pub struct MergeSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+}
+
+impl MergeSummary {
+    /// Counts of what `actions` will do, before applying them.
+    pub fn of(actions: &[Action]) -> Self {
+        let mut summary = Self::default();
+        for action in actions {
+            match action {
+                Action::Add(_) => summary.added += 1,
+                Action::Update(..) => summary.updated += 1,
+                Action::Skip => summary.skipped += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn message(&self) -> String {
+        let mut parts = vec![format!("{} added", self.added), format!("{} updated", self.updated)];
+        if self.unchanged > 0 {
+            parts.push(format!("{} unchanged", self.unchanged));
+        }
+        if self.skipped > 0 {
+            parts.push(format!("{} skipped", self.skipped));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Set the fields the record has. Returns true if the note changed.
+fn merge(note: &mut Note, record: Record) -> bool {
+    let before = (note.title.clone(), note.content.clone(), note.created, note.tags.clone(), note.due, note.status.clone());
+    if let Some(title) = record.title {
+        note.title = title;
+    }
+    if let Some(content) = record.content {
+        note.content = content;
+    }
+    if let Some(created) = record.created {
+        note.created = created;
+    }
+    if let Some(tags) = record.tags {
+        note.tags = tags;
+    }
+    if let Some(due) = record.due {
+        note.due = due;
+    }
+    if let Some(status) = record.status {
+        note.status = status;
+    }
+    let after = (note.title.clone(), note.content.clone(), note.created, note.tags.clone(), note.due, note.status.clone());
+    if before == after && record.edited.is_none_or(|edited| edited == note.edited) {
+        return false;
+    }
+    note.edited = record.edited.unwrap_or_else(Note::now_ts).max(note.created);
+    true
+}
+
+/// Carry out a plan from [`plan`]. Updates to notes that were deleted or locked since are
+/// skipped.
+pub fn apply(actions: Vec<Action>, notes: &mut Vec<Note>) -> MergeSummary {
+    let mut summary = MergeSummary::default();
+    for action in actions {
+        match action {
+            Action::Add(record) => {
+                // Keep the record's ID when it is free, so links exported with it still work.
+                let id = record
+                    .id
+                    .filter(|&id| !notes.iter().any(|n| n.id == id))
+                    .unwrap_or_else(|| Note::next_id(notes));
+                Note::reserve_ids(id);
+                let mut note = Note::new(id, "");
+                let created = record.created;
+                let edited = record.edited;
+                merge(&mut note, record);
+                note.edited = edited.or(created).unwrap_or(note.created);
+                notes.push(note);
+                summary.added += 1;
+            }
+            Action::Update(id, record) => {
+                let Some(note) = notes.iter_mut().find(|n| n.id == id && !n.locked) else {
+                    summary.skipped += 1;
+                    continue;
+                };
+                if merge(note, record) {
+                    summary.updated += 1;
+                } else {
+                    summary.unchanged += 1;
+                }
+            }
+            Action::Skip => summary.skipped += 1,
+        }
+    }
+    summary
+}
@@ -0,0 +1,244 @@
+use crate::attachments::ATTACHMENTS_DIR;
+use crate::crypto::SealingKey;
+use crate::history::History;
+use crate::note::Note;
+use crate::note_lock;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use zeroize::{Zeroize, Zeroizing};
+
+const NOTES_FILE: &str = "notes.json";
+/// Replaces `notes.json` when encryption is turned on.
+const ENCRYPTED_NOTES_FILE: &str = "notes.enc";
+/// The highest note ID used so far, kept apart from the notes so it outlives deleted ones.
+const LAST_ID_FILE: &str = "last_id.json";
+const BACKUPS_DIR: &str = "backups";
+/// Files copied into a backup alongside the attachments directory.
+const BACKUP_FILES: [&str; 5] = [NOTES_FILE, ENCRYPTED_NOTES_FILE, LAST_ID_FILE, "settings.json", "reminders.json"];
+
+/// Key for the encrypted store while it is unlocked.
+static SESSION_KEY: Mutex<Option<SealingKey>> = Mutex::new(None);
+
+pub struct Storage;
+
+impl Storage {
+    /// Load notes from a JSON file. If the file does not exist or is invalid, returns an empty Vec.
+    /// An encrypted store loads nothing until it is unlocked with `unlock`.
+    pub fn load_notes() -> Vec<Note> {
+        Self::try_load_notes().unwrap_or_else(|e| {
+            eprintln!("{e}");
+            Vec::new()
+        })
+    }
+
+    /// Like `load_notes`, but an unreadable file is an error rather than an empty list, so
+    /// callers that save right away don't overwrite it.
+    pub fn try_load_notes() -> Result<Vec<Note>, String> {
+        if Self::is_encrypted() {
+            return Ok(Vec::new());
+        }
+        let path = Path::new(NOTES_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = File::open(path).map_err(|e| format!("Error opening notes file: {e}"))?;
+        let reader = BufReader::new(file);
+        let mut notes: Vec<Note> =
+            serde_json::from_reader(reader).map_err(|e| format!("Error parsing notes file: {e}"))?;
+        Self::assign_missing_ids(&mut notes);
+        Ok(notes)
+    }
+
+    /// Reserve the IDs used so far, including those of deleted notes, and give every note
+    /// without an ID (from files written before IDs existed) a unique one.
+    fn assign_missing_ids(notes: &mut [Note]) {
+        Note::reserve_ids(Self::load_json(LAST_ID_FILE));
+        Note::reserve_ids(notes.iter().map(|n| n.id).max().unwrap_or(0));
+        while let Some(i) = notes.iter().position(|n| n.id == 0) {
+            notes[i].id = Note::next_id(notes);
+        }
+    }
+
+    /// Save notes to a JSON file. Overwrites the file if it exists.
+    /// Locked notes are written sealed; with encryption on, the whole file is sealed with the session key.
+    pub fn save_notes(notes: &[Note]) -> io::Result<()> {
+        let notes = &*note_lock::sealed_copy(notes);
+        if Self::is_encrypted() {
+            let session = SESSION_KEY.lock().unwrap();
+            // Never overwrite the store with the empty list the app holds while locked.
+            let key = session
+                .as_ref()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::PermissionDenied, "the note store is locked"))?;
+            Self::write_sealed(key, notes)?;
+        } else {
+            Self::write_plain(notes)?;
+        }
+        Self::save_json(LAST_ID_FILE, &Note::last_id());
+        Ok(())
+    }
+
+    fn write_plain(notes: &[Note]) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(NOTES_FILE)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, notes)?;
+        writer.flush()
+    }
+
+    pub fn is_encrypted() -> bool {
+        Path::new(ENCRYPTED_NOTES_FILE).exists()
+    }
+
+    pub fn is_locked() -> bool {
+        Self::is_encrypted() && SESSION_KEY.lock().unwrap().is_none()
+    }
+
+    fn write_sealed(key: &SealingKey, notes: &[Note]) -> io::Result<()> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(notes)?);
+        // Write to a temporary file first so a crash can't leave a half-written store.
+        let tmp = Path::new(ENCRYPTED_NOTES_FILE).with_extension("enc.tmp");
+        fs::write(&tmp, key.seal(&plaintext))?;
+        fs::rename(&tmp, ENCRYPTED_NOTES_FILE)
+    }
+
+    /// Decrypt the store with `passphrase` and keep the key for later saves.
+    pub fn unlock(passphrase: &str) -> Result<Vec<Note>, String> {
+        let sealed = fs::read(ENCRYPTED_NOTES_FILE).map_err(|e| format!("Error reading {ENCRYPTED_NOTES_FILE}: {e}"))?;
+        let (key, plaintext) = SealingKey::open(&sealed, passphrase)?;
+        let mut notes: Vec<Note> =
+            serde_json::from_slice(&plaintext).map_err(|e| format!("Error parsing notes: {e}"))?;
+        Self::assign_missing_ids(&mut notes);
+        *SESSION_KEY.lock().unwrap() = Some(key);
+        Ok(notes)
+    }
+
+    /// Forget the session key. The key bytes are wiped as it is dropped.
+    pub fn lock() {
+        *SESSION_KEY.lock().unwrap() = None;
+    }
+
+    /// Lock the encrypted store: forget its key, then wipe every note and the undo history,
+    /// which holds copies of them, from memory.
+    pub fn lock_session(notes: &mut Vec<Note>, history: &mut History) {
+        Self::lock();
+        note_lock::lock_all(notes);
+        for note in notes.iter_mut() {
+            note.title.zeroize();
+            note.content.zeroize();
+        }
+        notes.clear();
+        history.clear();
+    }
+
+    /// Turn on encryption: seal `notes` with a key from `passphrase` and remove the plaintext file.
+    pub fn enable_encryption(passphrase: &str, notes: &[Note]) -> Result<(), String> {
+        let key = SealingKey::new(passphrase)?;
+        Self::write_sealed(&key, notes).map_err(|e| format!("Error writing {ENCRYPTED_NOTES_FILE}: {e}"))?;
+        *SESSION_KEY.lock().unwrap() = Some(key);
+        if Path::new(NOTES_FILE).exists() {
+            fs::remove_file(NOTES_FILE).map_err(|e| format!("Error removing {NOTES_FILE}: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Turn off encryption after checking `passphrase`, writing `notes` back as plain JSON.
+    pub fn disable_encryption(passphrase: &str, notes: &[Note]) -> Result<(), String> {
+        Self::check_passphrase(passphrase)?;
+        // Write the plain file first, so the notes aren't lost if that fails.
+        Self::write_plain(&note_lock::sealed_copy(notes)).map_err(|e| format!("Error writing {NOTES_FILE}: {e}"))?;
+        fs::remove_file(ENCRYPTED_NOTES_FILE).map_err(|e| format!("Error removing {ENCRYPTED_NOTES_FILE}: {e}"))?;
+        Self::lock();
+        Ok(())
+    }
+
+    /// Re-encrypt `notes` under a key from `new` with a fresh salt, after checking `current`.
+    pub fn change_passphrase(current: &str, new: &str, notes: &[Note]) -> Result<(), String> {
+        Self::check_passphrase(current)?;
+        let key = SealingKey::new(new)?;
+        Self::write_sealed(&key, notes).map_err(|e| format!("Error writing {ENCRYPTED_NOTES_FILE}: {e}"))?;
+        *SESSION_KEY.lock().unwrap() = Some(key);
+        Ok(())
+    }
+
+    fn check_passphrase(passphrase: &str) -> Result<(), String> {
+        let sealed = fs::read(ENCRYPTED_NOTES_FILE).map_err(|e| format!("Error reading {ENCRYPTED_NOTES_FILE}: {e}"))?;
+        SealingKey::open(&sealed, passphrase).map(|_| ())
+    }
+
+    /// Load a JSON side file such as settings. Returns the default value if it is missing or invalid.
+    pub fn load_json<T: DeserializeOwned + Default>(file_name: &str) -> T {
+        let path = Path::new(file_name);
+        if !path.exists() {
+            return T::default();
+        }
+        let file = match File::open(path) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("Error opening {file_name}: {e}");
+                return T::default();
+            }
+        };
+        match serde_json::from_reader(BufReader::new(file)) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("Error parsing {file_name}: {e}");
+                T::default()
+            }
+        }
+    }
+
+    /// Save a JSON side file. Overwrites the file if it exists.
+    pub fn save_json<T: Serialize>(file_name: &str, value: &T) {
+        let file = match OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(file_name)
+        {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("Error opening {file_name} for writing: {e}");
+                return;
+            }
+        };
+        if let Err(e) = serde_json::to_writer_pretty(BufWriter::new(file), value) {
+            eprintln!("Error saving {file_name}: {e}");
+        }
+    }
+
+    /// Copy the notes, side files and attachments into a new timestamped folder under `backups/`.
+    pub fn backup() -> io::Result<PathBuf> {
+        let dir = Path::new(BACKUPS_DIR).join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+        fs::create_dir_all(&dir)?;
+        for file in BACKUP_FILES {
+            if Path::new(file).exists() {
+                fs::copy(file, dir.join(file))?;
+            }
+        }
+        if Path::new(ATTACHMENTS_DIR).exists() {
+            Self::copy_dir(Path::new(ATTACHMENTS_DIR), &dir.join(ATTACHMENTS_DIR))?;
+        }
+        Ok(dir)
+    }
+
+    fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            let dest = to.join(entry.file_name());
+            if entry.path().is_dir() {
+                Self::copy_dir(&entry.path(), &dest)?;
+            } else {
+                fs::copy(entry.path(), dest)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+use note_core::attachments;
+
+#[test]
+fn opened_names_stay_inside_the_folder() {
+    assert_eq!(attachments::safe_file_name("report (1).pdf"), "report (1).pdf");
+    assert_eq!(attachments::safe_file_name("../../etc/passwd"), "passwd");
+    assert_eq!(attachments::safe_file_name("a&b|c\".txt"), "a_b_c_.txt");
+    assert_eq!(attachments::safe_file_name(".."), "attachment");
+    assert_eq!(attachments::safe_file_name(""), "attachment");
+    assert_eq!(attachments::safe_file_name(".hidden"), "hidden");
+}
@@ -0,0 +1,65 @@
+use note_core::crypto::{from_hex, to_hex, SealingKey};
+use note_core::note::Note;
+use note_core::note_lock;
+
+#[test]
+fn sealed_data_opens_only_with_its_passphrase() {
+    let key = SealingKey::new("correct horse").unwrap();
+    let sealed = key.seal(b"secret");
+    assert_eq!(&key.unseal(&sealed).unwrap()[..], b"secret");
+    assert!(key.matches(&sealed));
+
+    let (reopened, plaintext) = SealingKey::open(&sealed, "correct horse").unwrap();
+    assert_eq!(&plaintext[..], b"secret");
+    assert!(reopened.matches(&sealed));
+    assert!(SealingKey::open(&sealed, "wrong").is_err());
+}
+
+#[test]
+fn tampered_data_does_not_open() {
+    let key = SealingKey::new("passphrase").unwrap();
+    let mut sealed = key.seal(b"secret");
+    let last = sealed.len() - 1;
+    sealed[last] ^= 1;
+    assert!(key.unseal(&sealed).is_err());
+}
+
+#[test]
+fn hex_round_trips() {
+    let bytes = [0u8, 1, 127, 128, 255];
+    assert_eq!(to_hex(&bytes), "00017f80ff");
+    assert_eq!(from_hex("00017f80ff").unwrap(), bytes);
+    assert!(from_hex("abc").is_err());
+    assert!(from_hex("zz").is_err());
+}
+
+// The locked-notes key is shared by the whole process, so this runs as one test.
+#[test]
+fn locked_notes_are_sealed_on_save_and_unlock_again() {
+    let mut notes = vec![Note::new(1, "Diary"), Note::new(2, "Open")];
+    notes[0].content = "Dear diary".to_owned();
+    notes[1].content = "Nothing to hide".to_owned();
+
+    note_lock::lock_note(&mut notes, 0, "pass").unwrap();
+    assert!(note_lock::is_unlocked());
+    let saved = note_lock::sealed_copy(&notes).into_owned();
+    assert!(saved[0].content.is_empty());
+    assert!(!saved[0].sealed.is_empty());
+    assert_eq!(saved[1].content, "Nothing to hide");
+
+    note_lock::lock_all(&mut notes);
+    assert!(!note_lock::is_unlocked());
+    assert!(notes[0].is_sealed());
+    assert!(notes[0].content.is_empty());
+
+    let mut loaded = saved;
+    assert!(note_lock::unlock(&mut loaded, "wrong").is_err());
+    assert!(loaded[0].is_sealed());
+    assert_eq!(note_lock::unlock(&mut loaded, "pass"), Ok(1));
+    assert_eq!(loaded[0].content, "Dear diary");
+
+    note_lock::remove_lock(&mut loaded[0]);
+    assert!(!loaded[0].locked);
+    assert_eq!(note_lock::sealed_copy(&loaded)[0].content, "Dear diary");
+    note_lock::lock_all(&mut loaded);
+}
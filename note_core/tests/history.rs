@@ -0,0 +1,163 @@
+use note_core::history::{Command, History};
+use note_core::note::Note;
+use note_core::ops::NoteFields;
+
+fn titled(title: &str, content: &str) -> NoteFields {
+    NoteFields {
+        title: Some(title.to_owned()),
+        content: Some(content.to_owned()),
+        ..Default::default()
+    }
+}
+
+fn titles(notes: &[Note]) -> Vec<&str> {
+    notes.iter().map(|n| n.title.as_str()).collect()
+}
+
+fn sample(history: &mut History) -> Vec<Note> {
+    let mut notes = Vec::new();
+    history.apply(&mut notes, Command::Create(titled("Groceries", "milk"))).unwrap();
+    history.apply(&mut notes, Command::Create(titled("Plans", "See [[Groceries]]"))).unwrap();
+    notes
+}
+
+#[test]
+fn undo_and_redo_walk_through_several_commands() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    history.apply(&mut notes, Command::Move(0, Some("Done".to_owned()))).unwrap();
+    history.apply(&mut notes, Command::Tag(1, vec!["home".to_owned()])).unwrap();
+    assert_eq!(history.undo_label(), Some("Change the tags of \"Plans\""));
+
+    history.undo(&mut notes).unwrap();
+    assert!(notes[1].tags.is_empty());
+    history.undo(&mut notes).unwrap();
+    assert_eq!(notes[0].status, None);
+    history.undo(&mut notes).unwrap();
+    assert_eq!(titles(&notes), vec!["Groceries"]);
+    history.undo(&mut notes).unwrap();
+    assert!(notes.is_empty());
+    assert!(history.undo(&mut notes).is_none());
+
+    for _ in 0..4 {
+        history.redo(&mut notes).unwrap();
+    }
+    assert_eq!(titles(&notes), vec!["Groceries", "Plans"]);
+    assert_eq!(notes[0].status.as_deref(), Some("Done"));
+    assert_eq!(notes[1].tags, vec!["home".to_owned()]);
+    assert!(history.redo(&mut notes).is_none());
+}
+
+#[test]
+fn undoing_a_delete_puts_the_note_back_in_place() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    history.apply(&mut notes, Command::Create(titled("Ideas", ""))).unwrap();
+    let plans = notes[1].id;
+    history.apply(&mut notes, Command::Delete(1)).unwrap();
+    assert_eq!(titles(&notes), vec!["Groceries", "Ideas"]);
+
+    let entry = history.undo(&mut notes).unwrap();
+    assert_eq!(entry.label, "Delete \"Plans\"");
+    assert_eq!(entry.note, Some(plans));
+    assert_eq!(titles(&notes), vec!["Groceries", "Plans", "Ideas"]);
+    assert_eq!(notes[1].id, plans);
+}
+
+#[test]
+fn undoing_a_rename_restores_links_to_it() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    let fields = NoteFields { title: Some("Shopping".to_owned()), ..Default::default() };
+    history.apply(&mut notes, Command::Edit(0, fields)).unwrap();
+    assert_eq!(notes[1].content, format!("See [[Shopping|{}]]", notes[0].id));
+
+    history.undo(&mut notes).unwrap();
+    assert_eq!(notes[0].title, "Groceries");
+    assert_eq!(notes[1].content, format!("See [[Groceries|{}]]", notes[0].id));
+}
+
+#[test]
+fn a_new_command_clears_redo() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    history.undo(&mut notes).unwrap();
+    assert!(history.redo_label().is_some());
+    history.apply(&mut notes, Command::Create(titled("Other", ""))).unwrap();
+    assert!(history.redo_label().is_none());
+}
+
+#[test]
+fn failed_and_empty_commands_are_not_recorded() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    assert!(history.apply(&mut notes, Command::Create(titled("", "text"))).is_err());
+    assert!(history.apply(&mut notes, Command::Delete(7)).is_err());
+    history.apply(&mut notes, Command::Move(0, None)).unwrap();
+    assert_eq!(history.undo_label(), Some("Create \"Plans\""));
+}
+
+#[test]
+fn a_failed_edit_of_many_notes_changes_none() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    notes[1].locked = true;
+    let edits = vec![
+        (0, NoteFields { content: Some("eggs".to_owned()), ..Default::default() }),
+        (1, NoteFields { content: Some("secret".to_owned()), ..Default::default() }),
+    ];
+    assert!(history.apply(&mut notes, Command::EditMany(edits)).is_err());
+    assert_eq!(notes[0].content, "milk");
+
+    let edits = vec![(0, NoteFields { content: Some("eggs".to_owned()), ..Default::default() })];
+    history.apply(&mut notes, Command::EditMany(edits)).unwrap();
+    assert_eq!(history.undo_label(), Some("Edit 1 note(s)"));
+}
+
+#[test]
+fn old_versions_are_listed_and_cleared() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    history.apply(&mut notes, Command::Delete(0)).unwrap();
+    assert!(history.notes().any(|n| n.title == "Groceries"));
+    history.clear();
+    assert_eq!(history.notes().count(), 0);
+    assert!(history.undo(&mut notes).is_none());
+}
+
+#[test]
+fn imported_notes_replace_by_id_and_undo_together() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    let mut replaced = notes[0].clone();
+    replaced.content = "bread".to_owned();
+    let mut added = Note::new(Note::next_id(&notes), "Imported");
+    added.created = 1_600_000_000;
+    history.apply(&mut notes, Command::Import(vec![replaced, added])).unwrap();
+    assert_eq!(history.undo_label(), Some("Import 2 note(s)"));
+    assert_eq!(notes[0].content, "bread");
+    assert_eq!(notes[2].created, 1_600_000_000);
+
+    history.undo(&mut notes).unwrap();
+    assert_eq!(titles(&notes), vec!["Groceries", "Plans"]);
+    assert_eq!(notes[0].content, "milk");
+
+    notes[0].locked = true;
+    let mut replaced = notes[0].clone();
+    replaced.content = "bread".to_owned();
+    assert!(history.apply(&mut notes, Command::Import(vec![replaced])).is_err());
+}
+
+#[test]
+fn forgetting_a_note_drops_it_and_older_commands() {
+    let mut history = History::default();
+    let mut notes = sample(&mut history);
+    history.apply(&mut notes, Command::Tag(0, vec!["food".to_owned()])).unwrap();
+    history.apply(&mut notes, Command::Tag(1, vec!["home".to_owned()])).unwrap();
+    let groceries = notes[0].id;
+    history.forget(groceries);
+    assert!(!history.notes().any(|n| n.id == groceries));
+    assert_eq!(history.undo_label(), Some("Change the tags of \"Plans\""));
+    history.undo(&mut notes).unwrap();
+    assert!(history.undo(&mut notes).is_none());
+}
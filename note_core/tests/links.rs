@@ -0,0 +1,49 @@
+use note_core::links;
+use note_core::note::Note;
+
+fn notes() -> Vec<Note> {
+    let mut a = Note::new(1, "Alpha");
+    a.content = "Links to [[Beta|2]] and [[Gamma]]".to_owned();
+    let mut b = Note::new(2, "Beta");
+    b.content = "Back to [[alpha]]".to_owned();
+    vec![a, b]
+}
+
+#[test]
+fn parses_bound_and_unbound_links() {
+    let parsed = links::parse_links("[[Beta|2]] and [[Gamma]]");
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[0].title, "Beta");
+    assert_eq!(parsed[0].id, Some(2));
+    assert_eq!(parsed[1].title, "Gamma");
+    assert_eq!(parsed[1].id, None);
+}
+
+#[test]
+fn titles_match_ignoring_case() {
+    let notes = notes();
+    assert_eq!(links::find_by_title(&notes, "BETA"), Some(1));
+    assert_eq!(links::find_by_title(&notes, "Gamma"), None);
+}
+
+#[test]
+fn binding_leaves_unknown_titles_alone() {
+    let notes = notes();
+    assert_eq!(links::bind_links(&notes[0].content, &notes), "Links to [[Beta|2]] and [[Gamma]]");
+    assert_eq!(links::bind_links(&notes[1].content, &notes), "Back to [[Alpha|1]]");
+}
+
+#[test]
+fn backlinks_and_outgoing_links() {
+    let mut notes = notes();
+    notes[1].content = links::bind_links(&notes[1].content, &notes);
+    assert_eq!(links::outgoing(&notes[0], &notes), vec![2]);
+    assert_eq!(links::backlinks(&notes, 1), vec![1]);
+    assert_eq!(links::backlinks(&notes, 2), vec![0]);
+}
+
+#[test]
+fn pending_link_is_the_text_after_an_open_bracket() {
+    assert_eq!(links::pending_link("See [[Be", 8), Some((4, "Be")));
+    assert_eq!(links::pending_link("See [[Beta]] ", 13), None);
+}
@@ -0,0 +1,174 @@
+use note_core::note::{format_due, parse_due, Note};
+use note_core::ops::{self, NoteFields};
+
+fn titled(title: &str, content: &str) -> NoteFields {
+    NoteFields {
+        title: Some(title.to_owned()),
+        content: Some(content.to_owned()),
+        ..Default::default()
+    }
+}
+
+fn sample() -> Vec<Note> {
+    let mut notes = Vec::new();
+    ops::create(&mut notes, titled("Groceries", "milk, eggs")).unwrap();
+    ops::create(&mut notes, titled("Plans", "See [[Groceries]]")).unwrap();
+    notes
+}
+
+#[test]
+fn create_assigns_increasing_ids() {
+    let notes = sample();
+    assert_eq!(notes.len(), 2);
+    assert!(notes[1].id > notes[0].id);
+}
+
+#[test]
+fn a_deleted_notes_id_is_not_used_again() {
+    let mut notes = sample();
+    let plans = notes[1].id;
+    ops::delete(&mut notes, 1).unwrap();
+    let idx = ops::create(&mut notes, titled("Ideas", "")).unwrap();
+    assert!(notes[idx].id > plans);
+}
+
+#[test]
+fn create_binds_links_to_existing_notes() {
+    let notes = sample();
+    assert_eq!(notes[1].content, format!("See [[Groceries|{}]]", notes[0].id));
+}
+
+#[test]
+fn create_trims_the_title_and_rejects_an_empty_one() {
+    let mut notes = Vec::new();
+    let idx = ops::create(&mut notes, titled("  Padded  ", "")).unwrap();
+    assert_eq!(notes[idx].title, "Padded");
+    assert!(ops::create(&mut notes, titled("   ", "text")).is_err());
+    assert!(ops::create(&mut notes, NoteFields::default()).is_err());
+    assert_eq!(notes.len(), 1);
+}
+
+#[test]
+fn update_changes_only_the_given_fields() {
+    let mut notes = sample();
+    let fields = NoteFields { status: Some(Some("Done".to_owned())), ..Default::default() };
+    ops::update(&mut notes, 0, fields).unwrap();
+    assert_eq!(notes[0].status.as_deref(), Some("Done"));
+    assert_eq!(notes[0].title, "Groceries");
+    assert_eq!(notes[0].content, "milk, eggs");
+
+    let fields = NoteFields { status: Some(None), ..Default::default() };
+    ops::update(&mut notes, 0, fields).unwrap();
+    assert_eq!(notes[0].status, None);
+}
+
+#[test]
+fn update_sets_the_edit_time() {
+    let mut notes = sample();
+    notes[0].edited = 0;
+    ops::update(&mut notes, 0, NoteFields { tags: Some(vec!["food".to_owned()]), ..Default::default() }).unwrap();
+    assert!(notes[0].edited > 0);
+    assert!(notes[0].has_tag("Food"));
+}
+
+#[test]
+fn renaming_a_note_updates_links_to_it() {
+    let mut notes = sample();
+    let fields = NoteFields { title: Some("Shopping".to_owned()), ..Default::default() };
+    ops::update(&mut notes, 0, fields).unwrap();
+    assert_eq!(notes[1].content, format!("See [[Shopping|{}]]", notes[0].id));
+}
+
+#[test]
+fn update_rejects_an_empty_title_and_a_bad_index() {
+    let mut notes = sample();
+    let fields = NoteFields { title: Some(" ".to_owned()), ..Default::default() };
+    assert!(ops::update(&mut notes, 0, fields).is_err());
+    assert_eq!(notes[0].title, "Groceries");
+    assert!(ops::update(&mut notes, 5, NoteFields::default()).is_err());
+}
+
+#[test]
+fn sealed_notes_keep_their_content() {
+    let mut notes = sample();
+    notes[0].locked = true;
+    notes[0].content.clear();
+    let fields = NoteFields { content: Some("overwritten".to_owned()), ..Default::default() };
+    assert!(ops::update(&mut notes, 0, fields).is_err());
+    assert!(ops::append(&mut notes, 0, "more").is_err());
+    assert_eq!(notes[0].content, "");
+
+    // Fields other than the content can still change, e.g. from the board.
+    let fields = NoteFields { status: Some(Some("Done".to_owned())), ..Default::default() };
+    ops::update(&mut notes, 0, fields).unwrap();
+}
+
+#[test]
+fn append_starts_a_new_line() {
+    let mut notes = sample();
+    ops::append(&mut notes, 0, "bread").unwrap();
+    assert_eq!(notes[0].content, "milk, eggs\nbread");
+    assert!(ops::append(&mut notes, 0, "  \n").is_err());
+}
+
+#[test]
+fn delete_returns_the_note() {
+    let mut notes = sample();
+    let removed = ops::delete(&mut notes, 0).unwrap();
+    assert_eq!(removed.title, "Groceries");
+    assert_eq!(notes.len(), 1);
+    assert!(ops::delete(&mut notes, 1).is_err());
+}
+
+#[test]
+fn find_prefers_ids_then_titles() {
+    let mut notes = sample();
+    let id = notes[0].id.to_string();
+    ops::create(&mut notes, titled(&id, "")).unwrap();
+    assert_eq!(ops::find(&notes, &id), Ok(0));
+    assert_eq!(ops::find(&notes, "plans"), Ok(1));
+    assert!(ops::find(&notes, "Nothing").is_err());
+}
+
+#[test]
+fn search_matches_text_and_tags() {
+    let mut notes = sample();
+    notes[1].tags = vec!["home".to_owned()];
+    assert_eq!(ops::search(&notes, "EGGS", None), vec![0]);
+    assert_eq!(ops::search(&notes, "home", None), vec![1]);
+    assert_eq!(ops::search(&notes, "", Some("Home")), vec![1]);
+    assert_eq!(ops::search(&notes, "", None), vec![0, 1]);
+    assert!(ops::search(&notes, "eggs", Some("home")).is_empty());
+}
+
+#[test]
+fn an_update_that_changes_nothing_keeps_the_edit_time() {
+    let mut notes = sample();
+    notes[0].edited = 0;
+    ops::update(&mut notes, 0, titled("Groceries", "milk, eggs")).unwrap();
+    assert_eq!(notes[0].edited, 0);
+}
+
+#[test]
+fn editor_text_becomes_fields() {
+    let fields = NoteFields::from_editor("Plans", "home, work", "2024-11-23").unwrap();
+    assert_eq!(fields.title.as_deref(), Some("Plans"));
+    assert_eq!(fields.tags, Some(vec!["home".to_owned(), "work".to_owned()]));
+    assert_eq!(fields.due, Some(parse_due("2024-11-23 09:00")));
+    assert_eq!(NoteFields::from_editor("Plans", "", " ").unwrap().due, Some(None));
+}
+
+#[test]
+fn editor_text_without_a_title_or_with_a_bad_date_is_refused() {
+    assert!(NoteFields::from_editor("  ", "", "").is_err());
+    let err = NoteFields::from_editor("Plans", "", "next friday").err().unwrap();
+    assert!(err.contains("next friday"));
+}
+
+#[test]
+fn due_dates_read_back_as_written() {
+    let due = parse_due("2024-11-23 18:30").unwrap();
+    assert_eq!(format_due(due), "2024-11-23 18:30");
+    assert_eq!(parse_due(" 2024-11-23 ").map(format_due).as_deref(), Some("2024-11-23 09:00"));
+    assert_eq!(parse_due("23/11/2024"), None);
+}
@@ -0,0 +1,130 @@
+use note_core::note::Note;
+use note_core::records::{self, Action, MatchOn, MergeSummary, OnMatch, RecordFormat};
+
+fn notes() -> Vec<Note> {
+    let mut a = Note::new(1, "Groceries");
+    a.content = "milk, \"eggs\"\nbread".to_owned();
+    a.tags = vec!["home".to_owned(), "food".to_owned()];
+    a.created = 1_700_000_000;
+    a.edited = 1_700_000_600;
+    a.due = Some(1_700_086_400);
+    a.status = Some("To Do".to_owned());
+    let mut b = Note::new(2, "Plans");
+    b.created = 1_700_000_000;
+    b.edited = 1_700_000_000;
+    vec![a, b]
+}
+
+/// Import `text` into an empty note list.
+fn import(text: &str, format: RecordFormat) -> Vec<Note> {
+    let parsed = records::parse(text, format).unwrap();
+    let actions = records::plan(parsed, &[], MatchOn::Id, OnMatch::Newer).unwrap();
+    let mut imported = Vec::new();
+    records::apply(actions, &mut imported);
+    imported
+}
+
+fn assert_same(a: &[Note], b: &[Note]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        assert_eq!(a.id, b.id);
+        assert_eq!(a.title, b.title);
+        assert_eq!(a.content, b.content);
+        assert_eq!(a.created, b.created);
+        assert_eq!(a.edited, b.edited);
+        assert_eq!(a.tags, b.tags);
+        assert_eq!(a.due, b.due);
+        assert_eq!(a.status, b.status);
+    }
+}
+
+#[test]
+fn json_lines_round_trip() {
+    let notes = notes();
+    let text = records::to_jsonl(&notes, &[0, 1]).unwrap();
+    assert_eq!(text.lines().count(), 2);
+    assert!(text.starts_with("{\"id\":1,\"title\":\"Groceries\""));
+    assert_same(&import(&text, RecordFormat::JsonLines), &notes);
+}
+
+#[test]
+fn csv_round_trip() {
+    let notes = notes();
+    let text = records::to_csv(&notes, &[0, 1]).unwrap();
+    assert!(text.starts_with("id,title,content,created,edited,tags,due,status\n"));
+    assert_same(&import(&text, RecordFormat::Csv), &notes);
+}
+
+#[test]
+fn every_bad_line_is_reported() {
+    let text = "{\"title\": \"Fine\"}\nnot json\n\n{\"title\": \"\"}\n{\"title\": \"X\", \"colour\": \"red\"}\n";
+    let errors = records::parse_jsonl(text).unwrap_err();
+    let lines: Vec<usize> = errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![2, 4, 5]);
+    assert!(errors[2].to_string().starts_with("Line 5: colour: unknown field"));
+}
+
+#[test]
+fn csv_rows_must_match_the_header() {
+    let errors = records::parse_csv("title,content\nOne,two,three\n").unwrap_err();
+    assert_eq!(errors[0].line, 2);
+    assert_eq!(errors[0].message, "expected 2 cell(s) like the header, found 3");
+    assert!(records::parse_csv("title,colour\nOne,red\n").is_err());
+}
+
+#[test]
+fn times_accept_rfc3339_or_unix_seconds() {
+    let parsed = records::parse_jsonl("{\"created\": \"2024-05-01T09:30:00Z\", \"edited\": 1714555800}").unwrap();
+    assert_eq!(parsed[0].created, Some(1_714_555_800));
+    assert_eq!(parsed[0].edited, Some(1_714_555_800));
+    assert!(records::parse_jsonl("{\"created\": 10, \"edited\": 5}").is_err());
+}
+
+#[test]
+fn merge_rules_decide_what_matched_notes_get() {
+    let notes = notes();
+    let text = "{\"id\": 1, \"content\": \"old\", \"edited\": 1600000000}\n{\"id\": 2, \"content\": \"new\", \"edited\": 1800000000}\n{\"title\": \"Fresh\"}";
+    let parsed = records::parse_jsonl(text).unwrap();
+
+    let actions = records::plan(parsed.clone(), &notes, MatchOn::Id, OnMatch::Newer).unwrap();
+    assert!(matches!(actions[..], [Action::Skip, Action::Update(2, _), Action::Add(_)]));
+    let mut merged = notes.clone();
+    let summary = records::apply(actions, &mut merged);
+    assert_eq!(summary.message(), "1 added, 1 updated, 1 skipped");
+    assert_eq!(merged[0].content, notes[0].content);
+    assert_eq!(merged[1].content, "new");
+    assert_eq!(merged[1].title, "Plans");
+    assert!(merged[2].id > 2);
+
+    let actions = records::plan(parsed.clone(), &notes, MatchOn::Id, OnMatch::Overwrite).unwrap();
+    let summary = MergeSummary::of(&actions);
+    assert_eq!((summary.added, summary.updated, summary.skipped), (1, 2, 0));
+
+    let actions = records::plan(parsed, &notes, MatchOn::Id, OnMatch::Skip).unwrap();
+    assert_eq!(MergeSummary::of(&actions).skipped, 2);
+}
+
+#[test]
+fn planning_rejects_duplicates_and_locked_notes() {
+    let mut notes = notes();
+    let parsed = records::parse_jsonl("{\"title\": \"a\"}\n{\"title\": \"A\"}").unwrap();
+    let errors = records::plan(parsed, &notes, MatchOn::Title, OnMatch::Overwrite).unwrap_err();
+    assert_eq!(errors[0].message, "duplicate title (first on line 1)");
+
+    notes[0].locked = true;
+    let parsed = records::parse_jsonl("{\"title\": \"groceries\", \"content\": \"x\"}").unwrap();
+    assert!(records::plan(parsed, &notes, MatchOn::Title, OnMatch::Overwrite).is_err());
+}
+
+#[test]
+fn updates_follow_the_note_id_after_planning() {
+    let notes = notes();
+    let parsed = records::parse_jsonl("{\"id\": 1, \"content\": \"a\"}\n{\"id\": 2, \"content\": \"b\"}").unwrap();
+    let actions = records::plan(parsed, &notes, MatchOn::Id, OnMatch::Overwrite).unwrap();
+    // The first note is deleted and the second locked while the preview is open.
+    let mut changed = notes[1..].to_vec();
+    changed[0].locked = true;
+    let summary = records::apply(actions, &mut changed);
+    assert_eq!((summary.updated, summary.skipped), (0, 2));
+    assert_eq!(changed[0].content, notes[1].content);
+}
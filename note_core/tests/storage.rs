@@ -0,0 +1,212 @@
+use note_core::attachments;
+use note_core::history::{Command, History};
+use note_core::note::Note;
+use note_core::note_lock;
+use note_core::ops::NoteFields;
+use note_core::storage::Storage;
+use std::env;
+use std::fs;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+// Storage works on files in the current directory, which every test in this file shares.
+static CWD: Mutex<()> = Mutex::new(());
+
+/// Run `test` inside a fresh empty directory.
+fn in_temp_dir(test: impl FnOnce()) {
+    let _guard = CWD.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = TempDir::new().unwrap();
+    let previous = env::current_dir().unwrap();
+    env::set_current_dir(dir.path()).unwrap();
+    test();
+    Storage::lock();
+    env::set_current_dir(previous).unwrap();
+}
+
+fn sample() -> Vec<Note> {
+    let mut note = Note::new(1, "First");
+    note.content = "Hello".to_owned();
+    note.tags = vec!["greeting".to_owned()];
+    vec![note, Note::new(2, "Second")]
+}
+
+#[test]
+fn notes_round_trip_through_the_json_file() {
+    in_temp_dir(|| {
+        assert!(Storage::try_load_notes().unwrap().is_empty());
+        Storage::save_notes(&sample()).unwrap();
+        let loaded = Storage::try_load_notes().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].title, "First");
+        assert_eq!(loaded[0].content, "Hello");
+        assert_eq!(loaded[0].tags, vec!["greeting".to_owned()]);
+    });
+}
+
+#[test]
+fn an_unreadable_file_is_an_error_not_an_empty_list() {
+    in_temp_dir(|| {
+        fs::write("notes.json", "{ not json").unwrap();
+        assert!(Storage::try_load_notes().is_err());
+        assert!(Storage::load_notes().is_empty());
+    });
+}
+
+#[test]
+fn notes_without_ids_get_unique_ones() {
+    in_temp_dir(|| {
+        let old = r#"[{"title":"a","content":"","created":1,"edited":1},{"id":5,"title":"b","content":"","created":1,"edited":1},{"title":"c","content":"","created":1,"edited":1}]"#;
+        fs::write("notes.json", old).unwrap();
+        let ids: Vec<u64> = Storage::try_load_notes().unwrap().iter().map(|n| n.id).collect();
+        assert_eq!(ids[1], 5);
+        assert!(ids[0] > 5 && ids[2] > ids[0]);
+    });
+}
+
+#[test]
+fn ids_of_deleted_notes_are_remembered_across_loads() {
+    in_temp_dir(|| {
+        let mut notes = sample();
+        let mut newest = Note::new(Note::next_id(&notes), "Newest");
+        newest.content = "gone soon".to_owned();
+        let deleted = newest.id;
+        notes.push(newest);
+        Storage::save_notes(&notes).unwrap();
+        notes.pop();
+        Storage::save_notes(&notes).unwrap();
+
+        let saved: u64 = Storage::load_json("last_id.json");
+        assert!(saved >= deleted);
+        let loaded = Storage::try_load_notes().unwrap();
+        assert!(Note::next_id(&loaded) > deleted);
+    });
+}
+
+#[test]
+fn the_encrypted_store_needs_its_passphrase() {
+    in_temp_dir(|| {
+        let notes = sample();
+        Storage::save_notes(&notes).unwrap();
+        Storage::enable_encryption("pass", &notes).unwrap();
+        assert!(Storage::is_encrypted());
+        assert!(!fs::exists("notes.json").unwrap());
+        assert!(!fs::read("notes.enc").unwrap().windows(5).any(|w| w == b"Hello"));
+
+        Storage::lock();
+        assert!(Storage::is_locked());
+        // The empty list held while locked must never replace the store.
+        assert!(Storage::save_notes(&[]).is_err());
+        assert!(Storage::try_load_notes().unwrap().is_empty());
+        assert!(Storage::unlock("wrong").is_err());
+        assert_eq!(Storage::unlock("pass").unwrap().len(), 2);
+
+        Storage::change_passphrase("pass", "new", &notes).unwrap();
+        Storage::lock();
+        assert!(Storage::unlock("pass").is_err());
+        assert!(Storage::unlock("new").is_ok());
+
+        Storage::disable_encryption("new", &notes).unwrap();
+        assert!(!Storage::is_encrypted());
+        assert_eq!(Storage::try_load_notes().unwrap().len(), 2);
+    });
+}
+
+#[test]
+fn unused_attachments_are_collected() {
+    in_temp_dir(|| {
+        let kept = attachments::store_bytes("a.txt", b"kept").unwrap();
+        let dropped = attachments::store_bytes("b.txt", b"dropped").unwrap();
+        assert_eq!(kept.hash, attachments::hash_bytes(b"kept"));
+        assert_eq!(kept.size, 4);
+
+        let mut notes = sample();
+        notes[0].attachments = vec![kept.clone()];
+        assert_eq!(attachments::collect_garbage(&notes), 1);
+        assert!(attachments::blob_path(&kept.hash).exists());
+        assert!(!attachments::blob_path(&dropped.hash).exists());
+    });
+}
+
+#[test]
+fn a_newly_locked_note_survives_locking_and_saving_again() {
+    in_temp_dir(|| {
+        let mut notes = sample();
+        note_lock::lock_note(&mut notes, 0, "pass").unwrap();
+        Storage::save_notes(&notes).unwrap();
+        note_lock::lock_all(&mut notes);
+        Storage::save_notes(&notes).unwrap();
+
+        let mut loaded = Storage::try_load_notes().unwrap();
+        assert!(loaded[0].is_sealed());
+        assert_eq!(note_lock::unlock(&mut loaded, "pass"), Ok(1));
+        assert_eq!(loaded[0].content, "Hello");
+        note_lock::lock_all(&mut loaded);
+    });
+}
+
+#[test]
+fn an_edit_before_locking_again_is_kept() {
+    in_temp_dir(|| {
+        let mut notes = sample();
+        note_lock::lock_note(&mut notes, 0, "pass").unwrap();
+        note_lock::lock_all(&mut notes);
+        note_lock::unlock(&mut notes, "pass").unwrap();
+        notes[0].content = "Edited".to_owned();
+        note_lock::lock_all(&mut notes);
+        Storage::save_notes(&notes).unwrap();
+
+        let mut loaded = Storage::try_load_notes().unwrap();
+        note_lock::unlock(&mut loaded, "pass").unwrap();
+        assert_eq!(loaded[0].content, "Edited");
+        note_lock::lock_all(&mut loaded);
+    });
+}
+
+#[test]
+fn attachments_an_undo_needs_are_kept() {
+    in_temp_dir(|| {
+        let blob = attachments::store_bytes("a.txt", b"kept").unwrap();
+        let mut notes = sample();
+        let mut history = History::default();
+        let fields = NoteFields { attachments: Some(vec![blob.clone()]), ..Default::default() };
+        history.apply(&mut notes, Command::Edit(0, fields)).unwrap();
+        history.undo(&mut notes).unwrap();
+        assert_eq!(history.collect_garbage(&notes), 0);
+        history.clear();
+        assert_eq!(history.collect_garbage(&notes), 1);
+        assert!(!attachments::blob_path(&blob.hash).exists());
+    });
+}
+
+#[test]
+fn locking_again_drops_plain_copies_from_the_history() {
+    in_temp_dir(|| {
+        let mut notes = sample();
+        let mut history = History::default();
+        let fields = NoteFields { content: Some("Secret".to_owned()), ..Default::default() };
+        history.apply(&mut notes, Command::Edit(0, fields)).unwrap();
+        note_lock::lock_note(&mut notes, 0, "pass").unwrap();
+        note_lock::relock(&mut notes, &mut history);
+        assert!(notes[0].is_sealed());
+        assert!(history.undo_label().is_none());
+        note_lock::unlock(&mut notes, "pass").unwrap();
+        assert_eq!(notes[0].content, "Secret");
+        note_lock::lock_all(&mut notes);
+    });
+}
+
+#[test]
+fn locking_the_session_wipes_notes_and_history() {
+    in_temp_dir(|| {
+        let mut notes = sample();
+        Storage::enable_encryption("pass", &notes).unwrap();
+        let mut history = History::default();
+        history.apply(&mut notes, Command::Tag(0, vec!["home".to_owned()])).unwrap();
+        Storage::lock_session(&mut notes, &mut history);
+        assert!(Storage::is_locked());
+        assert!(notes.is_empty());
+        assert!(history.undo_label().is_none());
+        assert_eq!(Storage::unlock("pass").unwrap().len(), 2);
+        Storage::disable_encryption("pass", &notes).unwrap();
+    });
+}
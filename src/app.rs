@@ -1,18 +1,92 @@
 use eframe::egui;
-use crate::note::Note;
-use crate::storage::Storage;
+use chrono::NaiveDate;
+use crate::attachments::{self, Attachment, AttachmentAction, AttachmentsPanel};
+use crate::board::{BoardAction, BoardView};
+use crate::calendar::{self, CalendarAction, CalendarView, DateField};
+use crate::daily::{self, CalendarPicker};
+use crate::encryption::{EncryptionPanel, UnlockScreen};
+use crate::export::ExportDialog;
+use crate::import::ImportDialog;
+use crate::find_replace::FindReplaceState;
+use crate::graph::GraphView;
+use crate::images;
+use note_core::history::{Command, History};
+use note_core::links;
+use note_core::note::Note;
+use note_core::ops::NoteFields;
+use crate::note_find::{select_in_editor, NoteFindState};
+use crate::note_lock::{self, NoteLockPanel};
+use crate::palette::{Action, CommandPalette, PALETTE_SHORTCUT};
+use crate::properties::{self, Properties, PropertyEditor, PropertyQuery};
+use crate::reminders::{self, ReminderAction, Reminders};
+use crate::render::{show_content, ContentAction};
+use crate::settings::Settings;
+use note_core::storage::Storage;
+use crate::tasks::{self, TasksAction, TasksView};
+use crate::templates::{self, TemplateDialog};
 use crate::theme::set_theme;
 use std::fs;
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+/// Shown on buttons that would leave the editor while it has unsaved changes.
+const UNSAVED_HINT: &str = "Save or cancel the note being edited first";
+
+/// What the central panel shows.
+#[derive(PartialEq)]
+pub enum View {
+    Notes,
+    Graph,
+    Calendar,
+    Tasks,
+    Board,
+}
 
 pub struct NoteApp {
     pub notes: Vec<Note>,
     pub filter: String,
+    /// Restricts the sidebar to notes created/edited on one day, set from the calendar view.
+    pub day_filter: Option<(NaiveDate, DateField)>,
     pub selected: Option<usize>,
     pub editor_title: String,
     pub editor_content: String,
+    pub editor_tags: String,
+    /// Due date as typed in the editor, `YYYY-MM-DD [HH:MM]`.
+    pub editor_due: String,
+    pub editor_status: Option<String>,
+    pub editor_properties: Properties,
+    pub property_editor: PropertyEditor,
+    pub editor_attachments: Vec<Attachment>,
+    /// Sidebar sort and filter by property.
+    pub property_query: PropertyQuery,
+    pub attachments_panel: AttachmentsPanel,
     pub is_editing: bool,
     pub dark_mode: bool,
     pub changelogs: Option<String>,
+    pub find_replace: FindReplaceState,
+    /// Commands that changed the notes, for undo and redo.
+    pub history: History,
+    /// Why the last save failed, shown at the top until a save succeeds.
+    pub save_error: Option<String>,
+    pub note_find: NoteFindState,
+    pub view: View,
+    pub graph: GraphView,
+    pub template_dialog: TemplateDialog,
+    pub settings: Settings,
+    pub settings_open: bool,
+    pub calendar_picker: CalendarPicker,
+    pub calendar: CalendarView,
+    pub tasks_view: TasksView,
+    pub reminders: Reminders,
+    pub board: BoardView,
+    pub unlock_screen: UnlockScreen,
+    pub encryption: EncryptionPanel,
+    pub note_lock: NoteLockPanel,
+    pub export_dialog: ExportDialog,
+    pub import_dialog: ImportDialog,
+    pub palette: CommandPalette,
+    /// Last keyboard or pointer input, for locking an encrypted store when idle.
+    pub last_activity: Instant,
 }
 
 impl Default for NoteApp {
@@ -22,12 +96,302 @@ impl Default for NoteApp {
         Self {
             notes,
             filter: String::new(),
+            day_filter: None,
             selected: None,
             editor_title: String::new(),
             editor_content: String::new(),
+            editor_tags: String::new(),
+            editor_due: String::new(),
+            editor_status: None,
+            editor_properties: Properties::new(),
+            property_editor: PropertyEditor::default(),
+            editor_attachments: Vec::new(),
+            property_query: PropertyQuery::default(),
+            attachments_panel: AttachmentsPanel::default(),
             is_editing: false,
             dark_mode: true,
             changelogs,
+            find_replace: FindReplaceState::default(),
+            history: History::default(),
+            save_error: None,
+            note_find: NoteFindState::default(),
+            view: View::Notes,
+            graph: GraphView::default(),
+            template_dialog: TemplateDialog::default(),
+            settings: Settings::load(),
+            settings_open: false,
+            calendar_picker: CalendarPicker::default(),
+            calendar: CalendarView::default(),
+            tasks_view: TasksView::default(),
+            reminders: Reminders::load(),
+            board: BoardView::default(),
+            unlock_screen: UnlockScreen::default(),
+            encryption: EncryptionPanel::default(),
+            note_lock: NoteLockPanel::default(),
+            export_dialog: ExportDialog::default(),
+            import_dialog: ImportDialog::default(),
+            palette: CommandPalette::default(),
+            last_activity: Instant::now(),
+        }
+    }
+}
+
+impl NoteApp {
+    /// Show the note at `idx` in the viewer.
+    pub fn open_note(&mut self, idx: usize) {
+        self.load_note(idx);
+        self.view = View::Notes;
+    }
+
+    /// Select the note at `idx` and copy it into the editor fields, without changing the view.
+    fn load_note(&mut self, idx: usize) {
+        self.selected = Some(idx);
+        self.editor_title = self.notes[idx].title.clone();
+        self.editor_content = self.notes[idx].content.clone();
+        self.editor_tags = self.notes[idx].tags.join(", ");
+        self.editor_due = self.notes[idx].due.map(reminders::format_due).unwrap_or_default();
+        self.editor_status = self.notes[idx].status.clone();
+        self.editor_properties = self.notes[idx].properties.clone();
+        self.editor_attachments = self.notes[idx].attachments.clone();
+        self.is_editing = false;
+    }
+
+    /// Apply `command` through the undo history and save. Returns the index of the note it
+    /// created or changed.
+    pub fn dispatch(&mut self, command: Command) -> Option<usize> {
+        match self.history.apply(&mut self.notes, command) {
+            Ok(idx) => {
+                self.save();
+                self.find_replace.invalidate_preview();
+                idx
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                None
+            }
+        }
+    }
+
+    fn save(&mut self) {
+        self.save_error = Storage::save_notes(&self.notes).err().map(|e| format!("Error saving notes: {e}"));
+        if let Some(e) = &self.save_error {
+            eprintln!("{e}");
+        }
+    }
+
+    /// Delete files no note refers to, keeping those of notes that undo can bring back.
+    fn collect_garbage(&self) {
+        self.history.collect_garbage(&self.notes);
+    }
+
+    pub fn undo(&mut self) {
+        let selected = self.selected.map(|i| self.notes[i].id);
+        if let Some(entry) = self.history.undo(&mut self.notes) {
+            let note = entry.note;
+            self.after_history(selected, note);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        let selected = self.selected.map(|i| self.notes[i].id);
+        if let Some(entry) = self.history.redo(&mut self.notes) {
+            let note = entry.note;
+            self.after_history(selected, note);
+        }
+    }
+
+    /// Save after undo or redo and keep the viewer on the selected note, which may have moved.
+    /// With nothing selected, the note the command was about is shown.
+    fn after_history(&mut self, selected: Option<u64>, note: Option<u64>) {
+        self.save();
+        self.find_replace.invalidate_preview();
+        let position = |id: u64| self.notes.iter().position(|n| n.id == id);
+        let shown = match selected {
+            Some(id) => position(id),
+            None if self.view == View::Notes => note.and_then(position),
+            None => None,
+        };
+        match shown {
+            Some(idx) => self.load_note(idx),
+            None => {
+                self.selected = None;
+                self.is_editing = false;
+            }
+        }
+    }
+
+    /// Whether `note` passes the sidebar search (title, content or tag), day and property filters.
+    pub fn matches_filters(&self, note: &Note) -> bool {
+        if self
+            .day_filter
+            .is_some_and(|(day, field)| !calendar::on_day(note, field, day))
+            || !self.property_query.matches(note)
+        {
+            return false;
+        }
+        note.matches_text(&self.filter)
+    }
+
+    /// Check or uncheck the task item on `line` of the note at `idx` and save.
+    pub fn toggle_task(&mut self, idx: usize, line: usize) {
+        if let Some(content) = tasks::toggle(&self.notes[idx].content, line) {
+            let fields = NoteFields { content: Some(content), ..Default::default() };
+            if self.dispatch(Command::Edit(idx, fields)).is_some() && self.selected == Some(idx) {
+                self.editor_content = self.notes[idx].content.clone();
+            }
+        }
+    }
+
+    /// Add or remove an attachment of the note at `idx` and save. Removed files are
+    /// deleted from the store once no note refers to them.
+    pub fn apply_attachment_action(&mut self, idx: usize, action: AttachmentAction) {
+        let mut list = self.notes[idx].attachments.clone();
+        match action {
+            AttachmentAction::Add(attachment) => {
+                if list.contains(&attachment) {
+                    return;
+                }
+                images::make_thumbnail(&attachment);
+                list.push(attachment);
+            }
+            AttachmentAction::Remove(i) => {
+                list.remove(i);
+            }
+        }
+        self.dispatch(Command::Edit(idx, NoteFields { attachments: Some(list), ..Default::default() }));
+        self.collect_garbage();
+    }
+
+    /// Lock the encrypted store: forget the key and wipe the notes held in memory.
+    pub fn lock(&mut self) {
+        Storage::lock_session(&mut self.notes, &mut self.history);
+        self.editor_title.zeroize();
+        self.editor_content.zeroize();
+        self.editor_tags.zeroize();
+        self.editor_properties.clear();
+        self.editor_attachments.clear();
+        self.selected = None;
+        self.is_editing = false;
+        self.view = View::Notes;
+        self.day_filter = None;
+        self.find_replace.invalidate_preview();
+    }
+
+    /// Lock every locked note again, dropping any copy of their content in the editor or undo history.
+    pub fn lock_notes(&mut self) {
+        if self.selected.is_some_and(|i| self.notes[i].locked) {
+            self.is_editing = false;
+            self.editor_content.zeroize();
+        }
+        note_lock::relock(&mut self.notes, &mut self.history);
+        self.find_replace.invalidate_preview();
+    }
+
+    /// Save after a note was locked or its lock removed. Locking isn't a command: undo would
+    /// need a plain copy of the content, so commands holding one are dropped instead. Undoing
+    /// an older edit after the lock was removed would bring the lock back, so those go too.
+    fn after_lock_change(&mut self, id: u64) {
+        self.save();
+        self.find_replace.invalidate_preview();
+        self.history.forget(id);
+    }
+
+    /// Whether `action` can run now. The palette only lists actions that can.
+    pub fn can_run(&self, action: Action) -> bool {
+        let selected = self.selected.map(|i| &self.notes[i]);
+        match action {
+            Action::EditNote => !self.is_editing && selected.is_some_and(|n| !n.is_sealed()),
+            // These leave the editor, so they wait until its changes are saved or cancelled.
+            Action::NewNote | Action::Home | Action::Today | Action::PickDay => !self.has_unsaved_changes(),
+            Action::DeleteNote => selected.is_some() && !self.has_unsaved_changes(),
+            Action::FindInNote => self.is_editing || selected.is_some(),
+            Action::Undo => !self.is_editing && self.history.undo_label().is_some(),
+            Action::Redo => !self.is_editing && self.history.redo_label().is_some(),
+            Action::LockStore => Storage::is_encrypted(),
+            Action::LockNotes => note_lock::is_unlocked(),
+            _ => true,
+        }
+    }
+
+    /// Whether the editor holds changes that haven't been saved to the note yet.
+    fn has_unsaved_changes(&self) -> bool {
+        if !self.is_editing {
+            return false;
+        }
+        let original = self.selected.map_or_else(|| Note::new(0, ""), |i| self.notes[i].clone());
+        self.editor_title.trim() != original.title.trim()
+            || self.editor_content != original.content
+            || Note::parse_tags(&self.editor_tags) != original.tags
+            || self.editor_due.trim() != original.due.map(reminders::format_due).unwrap_or_default()
+            || self.editor_status != original.status
+            || self.editor_properties != original.properties
+            || self.editor_attachments != original.attachments
+    }
+
+    /// Run `action` for a button, a keyboard shortcut or the command palette.
+    pub fn run_action(&mut self, action: Action) {
+        if !self.can_run(action) {
+            return;
+        }
+        let toggle_view = |current: &View, view: View| if *current == view { View::Notes } else { view };
+        match action {
+            Action::NewNote | Action::Home => {
+                self.editor_title.clear();
+                self.editor_content.clear();
+                self.editor_tags.clear();
+                self.editor_due.clear();
+                self.editor_status = None;
+                self.editor_properties.clear();
+                self.editor_attachments.clear();
+                self.is_editing = action == Action::NewNote;
+                self.selected = None;
+                self.view = View::Notes;
+            }
+            Action::EditNote => {
+                if let Some(idx) = self.selected {
+                    self.open_note(idx);
+                    self.is_editing = true;
+                }
+            }
+            Action::DeleteNote => {
+                if let Some(idx) = self.selected {
+                    self.dispatch(Command::Delete(idx));
+                    self.collect_garbage();
+                    self.selected = None;
+                    self.is_editing = false;
+                }
+            }
+            Action::Today => self.open_daily(daily::today()),
+            Action::PickDay => self.calendar_picker.open = true,
+            Action::Graph => self.view = toggle_view(&self.view, View::Graph),
+            Action::Calendar => self.view = toggle_view(&self.view, View::Calendar),
+            Action::Tasks => self.view = toggle_view(&self.view, View::Tasks),
+            Action::Board => self.view = toggle_view(&self.view, View::Board),
+            Action::FindReplace => self.find_replace.open = true,
+            Action::FindInNote => self.note_find.open(),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::Import => self.import_dialog.open = true,
+            Action::Export => {
+                if !self.export_dialog.open {
+                    self.export_dialog.toggle(self.dark_mode);
+                }
+            }
+            Action::Settings => self.settings_open = true,
+            Action::ToggleTheme => self.dark_mode = !self.dark_mode,
+            Action::LockStore => self.lock(),
+            Action::LockNotes => self.lock_notes(),
+        }
+    }
+
+    /// Show the daily note for `date`, creating it first if it does not exist yet.
+    pub fn open_daily(&mut self, date: NaiveDate) {
+        let idx = match daily::find(&self.notes, date, &self.settings) {
+            Some(idx) => Some(idx),
+            None => self.dispatch(Command::Create(daily::draft(&self.notes, date, &self.settings))),
+        };
+        if let Some(idx) = idx {
+            self.open_note(idx);
         }
     }
 }
@@ -36,12 +400,48 @@ impl eframe::App for NoteApp {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         set_theme(ctx, self.dark_mode);
 
+        if Storage::is_locked() {
+            if let Some(notes) = self.unlock_screen.show(ctx) {
+                self.notes = notes;
+                self.last_activity = Instant::now();
+                ctx.request_repaint();
+            }
+            return;
+        }
+        if ctx.input(|i| !i.events.is_empty() || i.pointer.is_moving()) {
+            self.last_activity = Instant::now();
+        }
+        let auto_lock = Duration::from_secs(u64::from(self.settings.auto_lock_minutes) * 60);
+        if Storage::is_encrypted() && !auto_lock.is_zero() && self.last_activity.elapsed() >= auto_lock {
+            self.lock();
+            ctx.request_repaint();
+            return;
+        }
+        if note_lock::expired(Duration::from_secs(u64::from(self.settings.note_unlock_minutes) * 60)) {
+            self.lock_notes();
+        }
+
+        if PALETTE_SHORTCUT.pressed(ctx) {
+            self.palette.toggle();
+        }
+        for action in Action::ALL {
+            // Text fields have their own undo, so the notes history only takes the keys outside them.
+            // A new note from the keyboard would also throw away what is being typed.
+            if matches!(action, Action::Undo | Action::Redo | Action::NewNote) && ctx.wants_keyboard_input() {
+                continue;
+            }
+            if action.shortcuts().iter().any(|s| s.pressed(ctx)) {
+                self.run_action(action);
+            }
+        }
+
         // Top Panel: Search and Theme Toggle
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.horizontal_centered(|ui| {
                 // "Home" button, always visible on the top left
                 if ui
-                    .add(
+                    .add_enabled(
+                        self.can_run(Action::Home),
                         egui::Button::new(
                             egui::RichText::new("🏠 Home")
                                 .font(egui::FontId::proportional(18.0))
@@ -50,12 +450,10 @@ impl eframe::App for NoteApp {
                             .min_size([70.0, 32.0].into()),
                     )
                     .on_hover_text("Return to Home Page")
+                    .on_disabled_hover_text(UNSAVED_HINT)
                     .clicked()
                 {
-                    self.selected = None;
-                    self.is_editing = false;
-                    self.editor_title.clear();
-                    self.editor_content.clear();
+                    self.run_action(Action::Home);
                 }
 
                 ui.add_space(8.0);
@@ -64,6 +462,10 @@ impl eframe::App for NoteApp {
                         .font(egui::FontId::proportional(26.0))
                         .strong(),
                 );
+                if let Some(error) = &self.save_error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 80, 80), "⚠ Not saved")
+                        .on_hover_text(format!("{error}\nChanges are kept in memory until a save succeeds."));
+                }
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     // Theme Toggle
                     if ui
@@ -78,7 +480,113 @@ impl eframe::App for NoteApp {
                         .on_hover_text("Toggle theme")
                         .clicked()
                     {
-                        self.dark_mode = !self.dark_mode;
+                        self.run_action(Action::ToggleTheme);
+                    }
+                    if Storage::is_encrypted()
+                        && ui
+                            .button(egui::RichText::new("🔒 Lock").size(15.0))
+                            .on_hover_text("Lock the encrypted notes now")
+                            .clicked()
+                    {
+                        self.run_action(Action::LockStore);
+                    }
+                    if ui
+                        .button(egui::RichText::new("🔁 Replace").size(15.0))
+                        .on_hover_text(format!("Find and replace across all notes ({})", Action::FindReplace.shortcuts()[0].label()))
+                        .clicked()
+                    {
+                        self.find_replace.open = !self.find_replace.open;
+                    }
+                    if ui
+                        .button(egui::RichText::new("☰").size(15.0))
+                        .on_hover_text(format!("Command palette ({})", PALETTE_SHORTCUT.label()))
+                        .clicked()
+                    {
+                        self.palette.toggle();
+                    }
+                    let label = self.history.redo_label();
+                    let hint = label.map_or_else(|| "Nothing to redo".to_owned(), |label| format!("Redo {label} ({})", Action::Redo.shortcuts()[0].label()));
+                    if ui
+                        .add_enabled(!self.is_editing && label.is_some(), egui::Button::new(egui::RichText::new("↪").size(15.0)))
+                        .on_hover_text(&hint)
+                        .on_disabled_hover_text(&hint)
+                        .clicked()
+                    {
+                        self.run_action(Action::Redo);
+                    }
+                    let label = self.history.undo_label();
+                    let hint = label.map_or_else(|| "Nothing to undo".to_owned(), |label| format!("Undo {label} ({})", Action::Undo.shortcuts()[0].label()));
+                    if ui
+                        .add_enabled(!self.is_editing && label.is_some(), egui::Button::new(egui::RichText::new("↩").size(15.0)))
+                        .on_hover_text(&hint)
+                        .on_disabled_hover_text(&hint)
+                        .clicked()
+                    {
+                        self.run_action(Action::Undo);
+                    }
+                    if ui
+                        .button(egui::RichText::new("📤 Export").size(15.0))
+                        .on_hover_text("Export notes to files")
+                        .clicked()
+                    {
+                        self.export_dialog.toggle(self.dark_mode);
+                    }
+                    if ui
+                        .button(egui::RichText::new("📥 Import").size(15.0))
+                        .on_hover_text("Import Markdown files or another app's export")
+                        .clicked()
+                    {
+                        self.import_dialog.open = !self.import_dialog.open;
+                    }
+                    if ui
+                        .button(egui::RichText::new("⚙").size(15.0))
+                        .on_hover_text(format!("Settings ({})", Action::Settings.shortcuts()[0].label()))
+                        .clicked()
+                    {
+                        self.settings_open = !self.settings_open;
+                    }
+                    if ui
+                        .button(egui::RichText::new("🗓").size(15.0))
+                        .on_hover_text("Jump to any day's note")
+                        .clicked()
+                    {
+                        self.calendar_picker.open = !self.calendar_picker.open;
+                    }
+                    if ui
+                        .add_enabled(self.can_run(Action::Today), egui::Button::new(egui::RichText::new("📅 Today").size(15.0)))
+                        .on_hover_text("Open today's daily note")
+                        .on_disabled_hover_text(UNSAVED_HINT)
+                        .clicked()
+                    {
+                        self.run_action(Action::Today);
+                    }
+                    if ui
+                        .selectable_label(self.view == View::Board, egui::RichText::new("📋 Board").size(15.0))
+                        .on_hover_text("Kanban board of notes by status")
+                        .clicked()
+                    {
+                        self.run_action(Action::Board);
+                    }
+                    if ui
+                        .selectable_label(self.view == View::Tasks, egui::RichText::new("☑ Tasks").size(15.0))
+                        .on_hover_text("Task items from every note")
+                        .clicked()
+                    {
+                        self.run_action(Action::Tasks);
+                    }
+                    if ui
+                        .selectable_label(self.view == View::Calendar, egui::RichText::new("📆 Calendar").size(15.0))
+                        .on_hover_text("Browse notes by the day they were created or edited")
+                        .clicked()
+                    {
+                        self.run_action(Action::Calendar);
+                    }
+                    if ui
+                        .selectable_label(self.view == View::Graph, egui::RichText::new("🕸 Graph").size(15.0))
+                        .on_hover_text("Show how notes link to each other")
+                        .clicked()
+                    {
+                        self.run_action(Action::Graph);
                     }
                 });
                 ui.add_space(8.0);
@@ -110,7 +618,8 @@ impl eframe::App for NoteApp {
                     );
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         if ui
-                            .add(
+                            .add_enabled(
+                                self.can_run(Action::NewNote),
                                 egui::Button::new(
                                     egui::RichText::new("＋").color(egui::Color32::WHITE),
                                 )
@@ -119,14 +628,44 @@ impl eframe::App for NoteApp {
                                     .rounding(egui::Rounding::same(16.0))
                                     .min_size([32.0, 32.0].into()),
                             )
-                            .on_hover_text("New Note")
+                            .on_hover_text(format!("New Note ({})", Action::NewNote.shortcuts()[0].label()))
+                            .on_disabled_hover_text(UNSAVED_HINT)
                             .clicked()
                         {
-                            self.editor_title.clear();
-                            self.editor_content.clear();
-                            self.is_editing = true;
-                            self.selected = None;
+                            self.run_action(Action::NewNote);
+                        }
+
+                        let template_button = ui
+                            .add(
+                                egui::Button::new("📄")
+                                    .rounding(egui::Rounding::same(16.0))
+                                    .min_size([32.0, 32.0].into()),
+                            )
+                            .on_hover_text("New from template");
+                        let popup_id = ui.make_persistent_id("template_popup");
+                        if template_button.clicked() {
+                            ui.memory_mut(|m| m.toggle_popup(popup_id));
                         }
+                        egui::popup::popup_below_widget(ui, popup_id, &template_button, |ui| {
+                            ui.set_min_width(180.0);
+                            let available = templates::templates(&self.notes);
+                            if available.is_empty() {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "No templates yet.\nTag a note \"{}\" to use it as one.",
+                                        templates::TEMPLATE_TAG
+                                    ))
+                                        .italics()
+                                        .weak(),
+                                );
+                            }
+                            for idx in available {
+                                if ui.selectable_label(false, &self.notes[idx].title).clicked() {
+                                    self.template_dialog.open(&self.notes, idx);
+                                    ui.memory_mut(|m| m.close_popup());
+                                }
+                            }
+                        });
                     });
                 });
                 ui.add_space(8.0);
@@ -142,22 +681,40 @@ impl eframe::App for NoteApp {
                 if search_changed {
                     self.selected = None;
                 }
+                self.property_query.controls(ui, &self.notes);
+                if let Some((day, field)) = self.day_filter {
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(format!("📆 {} on {}", field.label(), day.format("%Y-%m-%d")))
+                                .size(13.0)
+                                .color(egui::Color32::from_rgb(60, 155, 255)),
+                        );
+                        if ui.small_button("✖").on_hover_text("Clear day filter").clicked() {
+                            self.day_filter = None;
+                        }
+                    });
+                }
                 ui.add_space(8.0);
 
                 // Notes List
-                let filter = self.filter.to_lowercase();
                 let mut did_select = false;
+                let mut order: Vec<usize> = (0..self.notes.len()).collect();
+                self.property_query.sort(&self.notes, &mut order);
                 let mut filtered_count = 0;
-                for (i, note) in self.notes.iter().enumerate() {
-                    if filter.is_empty()
-                        || note.title.to_lowercase().contains(&filter)
-                        || note.content.to_lowercase().contains(&filter)
-                    {
+                for i in order {
+                    let note = &self.notes[i];
+                    if self.matches_filters(note) {
                         filtered_count += 1;
                         let selected = Some(i) == self.selected;
                         let label = egui::SelectableLabel::new(
                             selected,
-                            egui::RichText::new(&note.title).color(
+                            egui::RichText::new(match (note.locked, note.unsealed) {
+                                (true, false) => format!("🔒 {}", note.title),
+                                (true, true) => format!("🔓 {}", note.title),
+                                _ => note.title.clone(),
+                            })
+                            .color(
                                 if selected {
                                     egui::Color32::from_rgb(60, 155, 255)
                                 } else {
@@ -180,7 +737,13 @@ impl eframe::App for NoteApp {
                             self.selected = Some(i);
                             self.editor_title = note.title.clone();
                             self.editor_content = note.content.clone();
+                            self.editor_tags = note.tags.join(", ");
+                            self.editor_due = note.due.map(reminders::format_due).unwrap_or_default();
+                            self.editor_status = note.status.clone();
+                            self.editor_properties = note.properties.clone();
+                            self.editor_attachments = note.attachments.clone();
                             self.is_editing = false;
+                            self.view = View::Notes;
                             did_select = true;
                         }
                     }
@@ -203,6 +766,97 @@ impl eframe::App for NoteApp {
                 })
                 .inner_margin(egui::Margin::same(24.0)),
         ).show(ctx, |ui| {
+            if self.view == View::Graph {
+                ui.heading(
+                    egui::RichText::new("🕸 Note Graph")
+                        .font(egui::FontId::proportional(28.0))
+                        .strong(),
+                );
+                ui.label(
+                    egui::RichText::new("Drag to pan, scroll to zoom, click a note to open it.")
+                        .size(13.0)
+                        .weak(),
+                );
+                ui.add_space(8.0);
+                if let Some(idx) = self.graph.show(ui, &self.notes) {
+                    self.open_note(idx);
+                }
+                return;
+            }
+            if self.view == View::Calendar {
+                ui.heading(
+                    egui::RichText::new("📆 Calendar")
+                        .font(egui::FontId::proportional(28.0))
+                        .strong(),
+                );
+                ui.label(
+                    egui::RichText::new("Click a day to list its notes in the sidebar, or a title to open it.")
+                        .size(13.0)
+                        .weak(),
+                );
+                ui.add_space(8.0);
+                let selected_day = self
+                    .day_filter
+                    .filter(|&(_, field)| field == self.calendar.field)
+                    .map(|(day, _)| day);
+                match self.calendar.show(ui, &self.notes, selected_day) {
+                    Some(CalendarAction::SelectDay(day)) => {
+                        self.day_filter = if selected_day == Some(day) {
+                            None
+                        } else {
+                            Some((day, self.calendar.field))
+                        };
+                        self.selected = None;
+                    }
+                    Some(CalendarAction::OpenNote(idx)) => self.open_note(idx),
+                    None => {}
+                }
+                return;
+            }
+            if self.view == View::Board {
+                ui.heading(
+                    egui::RichText::new("📋 Board")
+                        .font(egui::FontId::proportional(28.0))
+                        .strong(),
+                );
+                ui.label(
+                    egui::RichText::new("Drag cards between columns to change their status. The sidebar search applies here too.")
+                        .size(13.0)
+                        .weak(),
+                );
+                ui.add_space(8.0);
+                let visible: Vec<usize> = (0..self.notes.len())
+                    .filter(|&i| self.matches_filters(&self.notes[i]))
+                    .collect();
+                match self.board.show(ui, &self.notes, &visible, &self.settings.board_columns) {
+                    Some(BoardAction::OpenNote(idx)) => self.open_note(idx),
+                    Some(BoardAction::SetStatus { note, status }) => {
+                        self.dispatch(Command::Move(note, status));
+                    }
+                    None => {}
+                }
+                return;
+            }
+            if self.view == View::Tasks {
+                ui.heading(
+                    egui::RichText::new("☑ Tasks")
+                        .font(egui::FontId::proportional(28.0))
+                        .strong(),
+                );
+                ui.label(
+                    egui::RichText::new("Every \"- [ ]\" item across your notes.")
+                        .size(13.0)
+                        .weak(),
+                );
+                ui.add_space(8.0);
+                match self.tasks_view.show(ui, &self.notes) {
+                    Some(TasksAction::OpenNote(idx)) => self.open_note(idx),
+                    Some(TasksAction::Toggle { note, line }) => self.toggle_task(note, line),
+                    None => {}
+                }
+                return;
+            }
+
             ui.set_width(ui.available_width().min(680.0));
 
             if self.is_editing {
@@ -229,21 +883,147 @@ impl eframe::App for NoteApp {
                 );
                 ui.add_space(14.0);
                 ui.label(
-                    egui::RichText::new("Content")
+                    egui::RichText::new("Tags")
                         .font(egui::FontId::proportional(22.0))
                         .strong(),
                 );
-                let available_height = ui.available_height() - 80.0;
-                ui.add_sized(
-                    [ui.available_width(), available_height.max(200.0)],
-                    egui::TextEdit::multiline(&mut self.editor_content)
-                        .hint_text("Type your note here...")
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.editor_tags)
+                        .hint_text("Comma-separated, e.g. work, meeting")
                         .font(egui::FontId::proportional(17.0)),
                 );
+                ui.add_space(14.0);
+                ui.label(
+                    egui::RichText::new("Due")
+                        .font(egui::FontId::proportional(22.0))
+                        .strong(),
+                );
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.editor_due)
+                            .hint_text("YYYY-MM-DD or YYYY-MM-DD HH:MM")
+                            .font(egui::FontId::proportional(17.0)),
+                    );
+                    if !self.editor_due.trim().is_empty() && reminders::parse_due(&self.editor_due).is_none() {
+                        ui.colored_label(egui::Color32::from_rgb(255, 80, 80), "Invalid date");
+                    }
+                });
+                ui.add_space(14.0);
+                ui.label(
+                    egui::RichText::new("Status")
+                        .font(egui::FontId::proportional(22.0))
+                        .strong(),
+                );
+                egui::ComboBox::from_id_source("editor_status")
+                    .selected_text(self.editor_status.as_deref().unwrap_or("None"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.editor_status, None, "None");
+                        for column in &self.settings.board_columns {
+                            ui.selectable_value(&mut self.editor_status, Some(column.clone()), column);
+                        }
+                    });
+                ui.add_space(14.0);
+                ui.label(
+                    egui::RichText::new("Properties")
+                        .font(egui::FontId::proportional(22.0))
+                        .strong(),
+                );
+                self.property_editor.show(ui, &mut self.editor_properties, &self.notes);
+                ui.add_space(14.0);
+                ui.label(
+                    egui::RichText::new("Content")
+                        .font(egui::FontId::proportional(22.0))
+                        .strong(),
+                );
+                let find_output = self.note_find.show_bar(ui, &mut self.editor_content, true);
+                let editor_id = egui::Id::new("editor_content");
+                if let Some(range) = find_output.jump_to {
+                    select_in_editor(ctx, editor_id, &self.editor_content, range);
+                }
+                let note_find = &self.note_find;
+                let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+                    let mut job = note_find.highlight(ui, text, egui::FontId::proportional(17.0));
+                    job.wrap.max_width = wrap_width;
+                    ui.fonts(|f| f.layout_job(job))
+                };
+                let available_height = ui.available_height() - 80.0;
+                let mut content_edit = egui::TextEdit::multiline(&mut self.editor_content)
+                    .id(editor_id)
+                    .hint_text("Type your note here... Link notes with [[Note Title]].")
+                    .font(egui::FontId::proportional(17.0))
+                    .desired_width(f32::INFINITY)
+                    .min_size([ui.available_width(), available_height.max(200.0)].into());
+                if note_find.open {
+                    content_edit = content_edit.layouter(&mut layouter);
+                }
+                let output = content_edit.show(ui);
+
+                // Suggest note titles while a [[link is being typed. Pressing on the popup takes
+                // focus from the editor, so keep it open while the pointer is over it.
+                let popup_id = egui::Id::new("link_suggestions");
+                let popup_hovered = ctx.data(|d| d.get_temp::<bool>(popup_id)).unwrap_or(false);
+                let mut popup_hovered_now = false;
+                if let Some(cursor) = output
+                    .cursor_range
+                    .filter(|_| output.response.has_focus() || popup_hovered)
+                {
+                    let char_idx = cursor.primary.ccursor.index;
+                    let byte_idx = self
+                        .editor_content
+                        .char_indices()
+                        .nth(char_idx)
+                        .map(|(b, _)| b)
+                        .unwrap_or(self.editor_content.len());
+                    if let Some((start, partial)) = links::pending_link(&self.editor_content, byte_idx) {
+                        let suggestions = links::suggestions(&self.notes, partial, 8);
+                        if !suggestions.is_empty() {
+                            let cursor_rect = output.galley.pos_from_cursor(&cursor.primary);
+                            let mut picked = None;
+                            let area = egui::Area::new(popup_id)
+                                .order(egui::Order::Foreground)
+                                .fixed_pos(output.galley_pos + cursor_rect.left_bottom().to_vec2())
+                                .show(ctx, |ui| {
+                                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                                        ui.label(egui::RichText::new("Link to…").weak().size(12.0));
+                                        for note in suggestions {
+                                            if ui.selectable_label(false, &note.title).clicked() {
+                                                picked = Some(links::link_to(note));
+                                            }
+                                        }
+                                    });
+                                });
+                            popup_hovered_now = area.response.contains_pointer();
+                            if let Some(link) = picked {
+                                self.editor_content.replace_range(start..byte_idx, &link);
+                                let end = start + link.len();
+                                select_in_editor(ctx, editor_id, &self.editor_content, end..end);
+                                output.response.request_focus();
+                                popup_hovered_now = false;
+                            }
+                        }
+                    }
+                }
+                ctx.data_mut(|d| d.insert_temp(popup_id, popup_hovered_now));
+
+                // Files dropped on the window are attached to the note; images, and images pasted
+                // with Ctrl+V, are also embedded at the cursor.
+                let mut added: Vec<Attachment> = Vec::new();
+                for file in ctx.input(|i| i.raw.dropped_files.clone()) {
+                    if let Some(path) = file.path {
+                        match attachments::store_file(&path) {
+                            Ok(attachment) => added.push(attachment),
+                            Err(e) => eprintln!("Error attaching {}: {e}", path.display()),
+                        }
+                    }
+                }
+                let mut paste_image = output.response.has_focus()
+                    && ctx.input(|i| i.modifiers.command && i.key_pressed(egui::Key::V));
+                let checked = NoteFields::from_editor(&self.editor_title, &self.editor_tags, &self.editor_due);
                 ui.add_space(16.0);
                 ui.horizontal(|ui| {
                     if ui
-                        .add(
+                        .add_enabled(
+                            checked.is_ok(),
                             egui::Button::new(
                                 egui::RichText::new("💾 Save").color(egui::Color32::WHITE),
                             )
@@ -251,29 +1031,24 @@ impl eframe::App for NoteApp {
                                 .rounding(egui::Rounding::same(12.0))
                                 .min_size([80.0, 36.0].into()),
                         )
+                        .on_disabled_hover_text(checked.as_ref().err().map_or("", String::as_str))
                         .clicked()
                     {
-                        if !self.editor_title.trim().is_empty() {
-                            match self.selected {
-                                Some(idx) => {
-                                    let note = &mut self.notes[idx];
-                                    note.title = self.editor_title.clone();
-                                    note.content = self.editor_content.clone();
-                                    note.edited = Note::now_ts();
-                                }
-                                None => {
-                                    self.notes.push(Note {
-                                        title: self.editor_title.clone(),
-                                        content: self.editor_content.clone(),
-                                        created: Note::now_ts(),
-                                        edited: Note::now_ts(),
-                                    });
-                                    self.selected = Some(self.notes.len() - 1);
-                                }
-                            }
-                            Storage::save_notes(&self.notes);
-                            self.is_editing = false;
+                        let fields = NoteFields {
+                            content: Some(self.editor_content.clone()),
+                            status: Some(self.editor_status.clone()),
+                            properties: Some(self.editor_properties.clone()),
+                            attachments: Some(self.editor_attachments.clone()),
+                            ..checked.unwrap_or_default()
+                        };
+                        let command = match self.selected {
+                            Some(idx) => Command::Edit(idx, fields),
+                            None => Command::Create(fields),
+                        };
+                        if let Some(idx) = self.dispatch(command) {
+                            self.selected = Some(idx);
                         }
+                        self.is_editing = false;
                     }
                     if ui
                         .add(
@@ -287,9 +1062,69 @@ impl eframe::App for NoteApp {
                     {
                         self.is_editing = false;
                     }
+                    if ui
+                        .add(
+                            egui::Button::new("🖼 Paste image")
+                                .rounding(egui::Rounding::same(12.0))
+                                .min_size([80.0, 36.0].into()),
+                        )
+                        .on_hover_text("Embed the image on the clipboard (or press Ctrl+V in the editor)")
+                        .clicked()
+                    {
+                        paste_image = true;
+                    }
                 });
+                if paste_image {
+                    // Without an image on the clipboard, Ctrl+V is an ordinary text paste.
+                    if let Ok(attachment) = images::paste_image() {
+                        added.push(attachment);
+                    }
+                }
+                let mut cursor = output
+                    .cursor_range
+                    .map(|c| c.primary.ccursor.index)
+                    .and_then(|char_idx| self.editor_content.char_indices().nth(char_idx).map(|(b, _)| b))
+                    .unwrap_or(self.editor_content.len());
+                for attachment in added {
+                    images::make_thumbnail(&attachment);
+                    if images::is_image(&attachment.name) {
+                        let at_line_start = cursor == 0 || self.editor_content[..cursor].ends_with('\n');
+                        let markdown = format!(
+                            "{}{}\n",
+                            if at_line_start { "" } else { "\n" },
+                            images::markdown_for(&attachment)
+                        );
+                        self.editor_content.insert_str(cursor, &markdown);
+                        cursor += markdown.len();
+                        select_in_editor(ctx, editor_id, &self.editor_content, cursor..cursor);
+                    }
+                    if !self.editor_attachments.contains(&attachment) {
+                        self.editor_attachments.push(attachment);
+                    }
+                }
             } else if let Some(idx) = self.selected {
-                let note = self.notes[idx].clone();
+                let mut note = self.notes[idx].clone();
+                if let Some(date) = daily::date_of(&note, &self.settings) {
+                    ui.horizontal(|ui| {
+                        let prev = daily::adjacent(&self.notes, date, &self.settings, false);
+                        let next = daily::adjacent(&self.notes, date, &self.settings, true);
+                        let prev_button = ui.add_enabled(prev.is_some(), egui::Button::new("◀ Previous day"));
+                        if let Some((_, prev_date)) = prev {
+                            if prev_button.on_hover_text(daily::title_for(prev_date, &self.settings)).clicked() {
+                                self.open_daily(prev_date);
+                            }
+                        }
+                        if date != daily::today() && ui.button("📅 Today").clicked() {
+                            self.open_daily(daily::today());
+                        }
+                        let next_button = ui.add_enabled(next.is_some(), egui::Button::new("Next day ▶"));
+                        if let Some((_, next_date)) = next {
+                            if next_button.on_hover_text(daily::title_for(next_date, &self.settings)).clicked() {
+                                self.open_daily(next_date);
+                            }
+                        }
+                    });
+                }
                 ui.add_space(8.0);
                 ui.heading(
                     egui::RichText::new(&note.title)
@@ -307,16 +1142,75 @@ impl eframe::App for NoteApp {
                         .italics()
                         .weak(),
                 );
+                if !note.tags.is_empty() {
+                    let mut untag = None;
+                    ui.horizontal_wrapped(|ui| {
+                        for (i, tag) in note.tags.iter().enumerate() {
+                            ui.label(
+                                egui::RichText::new(format!("#{tag}"))
+                                    .size(13.0)
+                                    .color(egui::Color32::from_rgb(60, 155, 255)),
+                            );
+                            if ui.small_button("✕").on_hover_text(format!("Remove #{tag}")).clicked() {
+                                untag = Some(i);
+                            }
+                        }
+                    });
+                    if let Some(i) = untag {
+                        let mut tags = note.tags.clone();
+                        tags.remove(i);
+                        self.dispatch(Command::Tag(idx, tags));
+                        self.editor_tags = self.notes[idx].tags.join(", ");
+                    }
+                }
+                if let Some(status) = &note.status {
+                    ui.label(egui::RichText::new(format!("📋 Status: {status}")).size(13.0));
+                }
+                if let Some(due) = note.due {
+                    let overdue = due <= Note::now_ts();
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "⏰ Due: {}{}",
+                            reminders::format_due(due),
+                            if overdue { " (overdue)" } else { "" }
+                        ))
+                            .size(13.0)
+                            .color(if overdue {
+                                egui::Color32::from_rgb(255, 80, 80)
+                            } else {
+                                ui.visuals().text_color()
+                            }),
+                    );
+                }
+                properties::show_properties(ui, &note.properties);
                 ui.separator();
                 ui.add_space(12.0);
-                ui.label(
-                    egui::RichText::new(&note.content)
-                        .font(egui::FontId::proportional(17.0)),
-                );
+                if note.is_sealed() {
+                    if self.note_lock.show_unlock(ui, &mut self.notes) {
+                        self.open_note(idx);
+                    }
+                } else {
+                    self.note_find.show_bar(ui, &mut note.content, false);
+                    if self.note_find.open {
+                        ui.label(self.note_find.highlight(ui, &note.content, egui::FontId::proportional(17.0)));
+                    } else {
+                        match show_content(ui, &note.content, &self.notes) {
+                            Some(ContentAction::OpenNote(target)) => self.open_note(target),
+                            Some(ContentAction::ToggleTask(line)) => self.toggle_task(idx, line),
+                            Some(ContentAction::OpenAttachment(attachment)) => {
+                                if let Err(e) = attachments::open(&attachment) {
+                                    eprintln!("Error opening {}: {e}", attachment.name);
+                                }
+                            }
+                            None => {}
+                        }
+                    }
+                }
                 ui.add_space(24.0);
                 ui.horizontal(|ui| {
                     if ui
-                        .add(
+                        .add_enabled(
+                            !note.is_sealed(),
                             egui::Button::new(
                                 egui::RichText::new("✏️ Edit"),
                             )
@@ -326,9 +1220,7 @@ impl eframe::App for NoteApp {
                         .on_hover_text("Edit this note")
                         .clicked()
                     {
-                        self.is_editing = true;
-                        self.editor_title = note.title.clone();
-                        self.editor_content = note.content.clone();
+                        self.run_action(Action::EditNote);
                     }
                     if ui
                         .add(
@@ -342,12 +1234,97 @@ impl eframe::App for NoteApp {
                         .on_hover_text("Delete this note")
                         .clicked()
                     {
-                        self.notes.remove(idx);
-                        Storage::save_notes(&self.notes);
-                        self.selected = None;
-                        self.is_editing = false;
+                        self.run_action(Action::DeleteNote);
+                    }
+                    if self.selected == Some(idx) && !note.locked {
+                        if ui
+                            .add(
+                                egui::Button::new("🔒 Lock")
+                                    .rounding(egui::Rounding::same(12.0))
+                                    .min_size([70.0, 36.0].into()),
+                            )
+                            .on_hover_text("Encrypt this note's content with the locked-notes passphrase")
+                            .clicked()
+                        {
+                            if note_lock::is_unlocked() {
+                                // The key is already unlocked, so no passphrase is needed.
+                                if note_lock::lock_note(&mut self.notes, idx, "").is_ok() {
+                                    self.after_lock_change(note.id);
+                                }
+                            } else {
+                                self.note_lock.locking = Some(note.id);
+                            }
+                        }
+                    } else if self.selected == Some(idx) && note.unsealed {
+                        if ui
+                            .add(
+                                egui::Button::new("🔒 Lock now")
+                                    .rounding(egui::Rounding::same(12.0))
+                                    .min_size([70.0, 36.0].into()),
+                            )
+                            .on_hover_text("Lock all unlocked notes again")
+                            .clicked()
+                        {
+                            self.lock_notes();
+                        }
+                        if ui
+                            .add(
+                                egui::Button::new("🔓 Remove lock")
+                                    .rounding(egui::Rounding::same(12.0))
+                                    .min_size([70.0, 36.0].into()),
+                            )
+                            .on_hover_text("Store this note's content unencrypted again")
+                            .clicked()
+                        {
+                            note_lock::remove_lock(&mut self.notes[idx]);
+                            self.after_lock_change(note.id);
+                        }
                     }
                 });
+                if self.selected == Some(idx) && self.note_lock.locking == Some(note.id) {
+                    ui.add_space(8.0);
+                    if self.note_lock.show_lock(ui, &mut self.notes, idx) {
+                        self.after_lock_change(note.id);
+                    }
+                }
+
+                if self.selected == Some(idx) {
+                    ui.add_space(24.0);
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        let mut actions = Vec::new();
+                        // Files dropped on the window while a note is open are attached to it.
+                        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+                        for file in dropped {
+                            if let Some(path) = file.path {
+                                actions.extend(self.attachments_panel.attach(&path));
+                            }
+                        }
+                        actions.extend(self.attachments_panel.show(ui, &note));
+                        for action in actions {
+                            self.apply_attachment_action(idx, action);
+                        }
+                    });
+                }
+
+                let backlinks = links::backlinks(&self.notes, note.id);
+                if self.selected.is_some() && !backlinks.is_empty() {
+                    ui.add_space(24.0);
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.label(
+                            egui::RichText::new(format!("🔗 Linked from ({})", backlinks.len()))
+                                .size(16.0)
+                                .strong(),
+                        );
+                        ui.add_space(4.0);
+                        for source in backlinks {
+                            if ui.link(&self.notes[source].title).clicked() {
+                                self.open_note(source);
+                            }
+                        }
+                    });
+                }
             } else {
                 // Home Page: Welcome, Instructions, and Changelogs
                 ui.add_space(32.0);
@@ -371,7 +1348,25 @@ impl eframe::App for NoteApp {
                             r#"• To create a note, click the ＋ button on the left sidebar or 'New Note' at the top.
 • Click a note in the sidebar to view it.
 • Use the ✏️ Edit and 🗑️ Delete buttons to modify or remove notes.
+• Give a note a due date in the editor, or a task "@due(YYYY-MM-DD HH:MM)"; due items show below and pop up as reminders.
+• Add typed properties (text, number, date, checkbox, select) in the editor, and sort or filter the sidebar by them.
+• Give notes a status and use 📋 Board to drag them between columns (configured in ⚙ Settings).
+• Write "- [ ] something" for a task; tick it in the viewer, and see open tasks from every note under ☑ Tasks.
+• 📆 Calendar shows notes by the day they were created or edited; clicking a day filters the sidebar.
+• 📅 Today opens (or creates) today's daily note; 🗓 jumps to any day and ⚙ sets the title format and template.
+• Tag a note "template" to reuse it: 📄 next to ＋ creates a note from it, filling {{date}}, {{time}}, {{title}} and any custom {{fields}}.
+• Add comma-separated tags in the editor; the 🕸 Graph button shows notes, their links and shared tags.
+• Link to another note by typing [[ and picking its title; notes that link here are listed under "Linked from".
+• Drop files on the window while viewing a note (or type a path) to attach them; open or export them from the viewer.
+• Drop an image on the editor or paste one with Ctrl+V to embed it; images show inline in the viewer.
+• Encrypt your notes with a passphrase in ⚙ Settings; they lock after a period of inactivity or with 🔒 Lock.
+• 🔒 Lock a note to encrypt its content with a separate passphrase; locked notes show 🔒 in the sidebar until unlocked.
+• 📥 Import brings in a folder of Markdown or text files, such as an Obsidian vault, an Evernote, Joplin or Google Keep export, or JSON Lines/CSV records, after a preview.
+• 📤 Export saves the selected note, the notes in the sidebar, a tag or all notes as Markdown, an HTML site, a PDF, JSON Lines or CSV.
 • Search notes by typing in the search bar at the top.
+• Undo a change with Ctrl+Z or ↩ at the top, and redo it with Ctrl+Shift+Z or ↪.
+• Press Ctrl+Shift+P for the command palette: type part of any action's name and press Enter.
+• Run the app with a command such as list, show or create to use your notes from a terminal; help lists them all.
 • Switch between dark and light mode with the icon at the top right.
 • Click the 🏠 Home button any time to return to this page.
 "#,
@@ -380,6 +1375,47 @@ impl eframe::App for NoteApp {
                     );
                 });
 
+                // Due Soon Section: overdue items and anything due in the next week
+                let now = Note::now_ts();
+                let due_soon: Vec<reminders::DueItem> = reminders::due_items(&self.notes)
+                    .into_iter()
+                    .filter(|item| item.due <= now + reminders::UPCOMING_WINDOW)
+                    .collect();
+                if !due_soon.is_empty() {
+                    ui.add_space(18.0);
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.heading(
+                            egui::RichText::new("⏰ Due Soon")
+                                .size(22.0)
+                                .strong(),
+                        );
+                        ui.add_space(6.0);
+                        egui::ScrollArea::vertical()
+                            .id_source("due_soon")
+                            .max_height(160.0)
+                            .show(ui, |ui| {
+                                for item in &due_soon {
+                                    ui.horizontal(|ui| {
+                                        let overdue = item.due <= now;
+                                        ui.label(
+                                            egui::RichText::new(reminders::format_due(item.due))
+                                                .size(14.0)
+                                                .color(if overdue {
+                                                    egui::Color32::from_rgb(255, 80, 80)
+                                                } else {
+                                                    ui.visuals().weak_text_color()
+                                                }),
+                                        );
+                                        if ui.link(egui::RichText::new(&item.label).size(15.0)).clicked() {
+                                            self.open_note(item.note_idx);
+                                        }
+                                    });
+                                }
+                            });
+                    });
+                }
+
                 ui.add_space(18.0);
 
                 // Changelogs Section - fixed size, expands entire screen width, and scrollable
@@ -428,6 +1464,54 @@ impl eframe::App for NoteApp {
                 ui.add_space(10.0);
             }
         });
+
+        // Skipped right after locking: with no notes loaded every reminder would be pruned.
+        if !Storage::is_locked() {
+            if let Some(ReminderAction::OpenNote(idx)) = self.reminders.show(ctx, &self.notes) {
+                self.open_note(idx);
+            }
+        }
+
+        if let Some(date) = self.calendar_picker.show(ctx, &self.notes, &self.settings) {
+            self.open_daily(date);
+        }
+        let mut settings_open = self.settings_open;
+        self.settings.show_window(ctx, &mut settings_open, &self.notes, &self.history, &mut self.encryption);
+        self.settings_open = settings_open;
+
+        if self.export_dialog.open {
+            let visible: Vec<usize> = (0..self.notes.len())
+                .filter(|&i| self.matches_filters(&self.notes[i]))
+                .collect();
+            self.export_dialog.show(ctx, &self.notes, self.selected, &visible);
+        }
+        if self.import_dialog.open {
+            if let Some(command) = self.import_dialog.show(ctx, &self.notes) {
+                self.dispatch(command);
+            }
+        }
+
+        if let Some(draft) = self.template_dialog.show(ctx, &self.notes) {
+            self.editor_title = draft.title;
+            self.editor_content = draft.content;
+            self.editor_tags = draft.tags.join(", ");
+            self.editor_due.clear();
+            self.editor_status = None;
+            self.editor_properties.clear();
+            self.editor_attachments.clear();
+            self.is_editing = true;
+            self.selected = None;
+            self.view = View::Notes;
+        }
+
+        if let Some(command) = self.find_replace.show(ctx, &self.notes) {
+            self.dispatch(command);
+        }
+
+        let available: Vec<Action> = Action::ALL.into_iter().filter(|&a| self.can_run(a)).collect();
+        if let Some(action) = self.palette.show(ctx, &available) {
+            self.run_action(action);
+        }
     }
 }
 
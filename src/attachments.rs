@@ -0,0 +1,104 @@
+pub use note_core::attachments::*;
+
+use note_core::note::Note;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+pub enum AttachmentAction {
+    Add(Attachment),
+    Remove(usize),
+}
+
+/// The attachment list in the note viewer.
+#[derive(Default)]
+pub struct AttachmentsPanel {
+    attach_path: String,
+    /// Attachment being exported and the destination typed for it.
+    export: Option<(usize, String)>,
+    error: Option<String>,
+}
+
+impl AttachmentsPanel {
+    /// Store a file dropped on the window or typed into the path field.
+    pub fn attach(&mut self, path: &Path) -> Option<AttachmentAction> {
+        match store_file(path) {
+            Ok(attachment) => {
+                self.error = None;
+                Some(AttachmentAction::Add(attachment))
+            }
+            Err(e) => {
+                self.error = Some(format!("Could not attach {}: {e}", path.display()));
+                None
+            }
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, note: &Note) -> Option<AttachmentAction> {
+        let mut action = None;
+        ui.label(
+            egui::RichText::new(format!("📎 Attachments ({})", note.attachments.len()))
+                .size(16.0)
+                .strong(),
+        );
+        for (i, attachment) in note.attachments.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(&attachment.name);
+                ui.label(egui::RichText::new(format_size(attachment.size)).size(12.0).weak());
+                if ui.small_button("Open").clicked() {
+                    if let Err(e) = open(attachment) {
+                        self.error = Some(format!("Could not open {}: {e}", attachment.name));
+                    }
+                }
+                if ui.small_button("Export").clicked() {
+                    self.export = Some((i, attachment.name.clone()));
+                }
+                if ui.small_button("✖").on_hover_text("Remove attachment").clicked() {
+                    action = Some(AttachmentAction::Remove(i));
+                }
+            });
+            if let Some((export_idx, dest)) = &mut self.export {
+                if *export_idx != i {
+                    continue;
+                }
+                let mut done = false;
+                ui.horizontal(|ui| {
+                    ui.label("Save to");
+                    ui.text_edit_singleline(dest);
+                    if ui.button("💾 Export").clicked() {
+                        match export(attachment, Path::new(dest.trim())) {
+                            Ok(()) => self.error = None,
+                            Err(e) => self.error = Some(format!("Could not export to {dest}: {e}")),
+                        }
+                        done = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        done = true;
+                    }
+                });
+                if done {
+                    self.export = None;
+                }
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.attach_path)
+                    .hint_text("File path, or drop files on the window"),
+            );
+            if ui
+                .add_enabled(!self.attach_path.trim().is_empty(), egui::Button::new("📎 Attach"))
+                .clicked()
+            {
+                let path = PathBuf::from(self.attach_path.trim());
+                action = self.attach(&path);
+                if action.is_some() {
+                    self.attach_path.clear();
+                }
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::from_rgb(255, 80, 80), error);
+        }
+        action
+    }
+}
@@ -0,0 +1,139 @@
+use note_core::note::Note;
+use eframe::egui;
+
+const COLUMN_WIDTH: f32 = 220.0;
+
+/// Dragged between columns: the index of the note on the card.
+struct CardPayload(usize);
+
+pub enum BoardAction {
+    OpenNote(usize),
+    /// Move the note to another column; `None` clears its status.
+    SetStatus { note: usize, status: Option<String> },
+}
+
+/// Columns to show: the configured ones, then any other status found on a visible note.
+pub fn columns(configured: &[String], notes: &[Note], visible: &[usize]) -> Vec<String> {
+    let mut columns: Vec<String> = configured.to_vec();
+    for &idx in visible {
+        if let Some(status) = &notes[idx].status {
+            if !columns.iter().any(|c| c.eq_ignore_ascii_case(status)) {
+                columns.push(status.clone());
+            }
+        }
+    }
+    columns
+}
+
+fn in_column(note: &Note, column: Option<&str>) -> bool {
+    match (note.status.as_deref(), column) {
+        (Some(status), Some(column)) => status.eq_ignore_ascii_case(column),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Kanban board of notes grouped by status.
+pub struct BoardView {
+    pub show_unassigned: bool,
+}
+
+impl Default for BoardView {
+    fn default() -> Self {
+        Self { show_unassigned: true }
+    }
+}
+
+impl BoardView {
+    /// Draw the board for the notes at `visible`. Returns the card the user opened or moved.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        notes: &[Note],
+        visible: &[usize],
+        configured: &[String],
+    ) -> Option<BoardAction> {
+        let mut action = None;
+        ui.checkbox(&mut self.show_unassigned, "Show notes without a status");
+        ui.add_space(6.0);
+
+        let mut lanes: Vec<Option<String>> = Vec::new();
+        if self.show_unassigned {
+            lanes.push(None);
+        }
+        lanes.extend(columns(configured, notes, visible).into_iter().map(Some));
+
+        egui::ScrollArea::horizontal().auto_shrink([false, false]).show(ui, |ui| {
+            ui.horizontal_top(|ui| {
+                for lane in &lanes {
+                    let cards: Vec<usize> = visible
+                        .iter()
+                        .copied()
+                        .filter(|&i| in_column(&notes[i], lane.as_deref()))
+                        .collect();
+                    let frame = egui::Frame::group(ui.style()).inner_margin(egui::Margin::same(8.0));
+                    let (_, dropped) = ui.dnd_drop_zone::<CardPayload, ()>(frame, |ui| {
+                        ui.set_width(COLUMN_WIDTH);
+                        ui.set_min_height(ui.available_height().max(200.0));
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(lane.as_deref().unwrap_or("No status"))
+                                    .size(16.0)
+                                    .strong(),
+                            );
+                            ui.label(egui::RichText::new(cards.len().to_string()).weak());
+                        });
+                        ui.separator();
+                        egui::ScrollArea::vertical()
+                            .id_source(("board_lane", lane.clone()))
+                            .show(ui, |ui| {
+                                for &idx in &cards {
+                                    let id = egui::Id::new(("board_card", notes[idx].id));
+                                    ui.dnd_drag_source(id, CardPayload(idx), |ui| {
+                                        card(ui, &notes[idx], idx, &mut action);
+                                    });
+                                    ui.add_space(4.0);
+                                }
+                            });
+                    });
+                    if let Some(payload) = dropped {
+                        let note = payload.0;
+                        if !in_column(&notes[note], lane.as_deref()) {
+                            action = Some(BoardAction::SetStatus { note, status: lane.clone() });
+                        }
+                    }
+                }
+            });
+        });
+        action
+    }
+}
+
+fn card(ui: &mut egui::Ui, note: &Note, idx: usize, action: &mut Option<BoardAction>) {
+    egui::Frame::none()
+        .fill(ui.visuals().extreme_bg_color)
+        .rounding(egui::Rounding::same(8.0))
+        .inner_margin(egui::Margin::same(8.0))
+        .show(ui, |ui| {
+            ui.set_width(COLUMN_WIDTH - 16.0);
+            if ui
+                .add(egui::Label::new(egui::RichText::new(&note.title).strong()).sense(egui::Sense::click()))
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .on_hover_text("Click to open, drag to move")
+                .clicked()
+            {
+                *action = Some(BoardAction::OpenNote(idx));
+            }
+            let preview: String = note.content.lines().next().unwrap_or("").chars().take(80).collect();
+            if !preview.is_empty() {
+                ui.label(egui::RichText::new(preview).size(12.0).weak());
+            }
+            if !note.tags.is_empty() {
+                ui.label(
+                    egui::RichText::new(note.tags.iter().map(|t| format!("#{t}")).collect::<Vec<_>>().join(" "))
+                        .size(12.0)
+                        .color(egui::Color32::from_rgb(60, 155, 255)),
+                );
+            }
+        });
+}
@@ -0,0 +1,244 @@
+use crate::daily::{first_of_month, today};
+use note_core::note::Note;
+use chrono::{Datelike, Days, Local, Months, NaiveDate, TimeZone};
+use eframe::egui;
+use std::collections::HashMap;
+
+/// Which timestamp places a note on the calendar.
+#[derive(Clone, Copy, PartialEq)]
+pub enum DateField {
+    Created,
+    Edited,
+}
+
+impl DateField {
+    pub fn of(self, note: &Note) -> u64 {
+        match self {
+            DateField::Created => note.created,
+            DateField::Edited => note.edited,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DateField::Created => "created",
+            DateField::Edited => "edited",
+        }
+    }
+}
+
+/// The local calendar day a Unix timestamp falls on.
+pub fn local_date(ts: u64) -> Option<NaiveDate> {
+    Local.timestamp_opt(ts as i64, 0).single().map(|dt| dt.date_naive())
+}
+
+/// Whether `note` was created/edited on `date`.
+pub fn on_day(note: &Note, field: DateField, date: NaiveDate) -> bool {
+    local_date(field.of(note)) == Some(date)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Span {
+    Month,
+    Week,
+}
+
+pub enum CalendarAction {
+    /// Filter the sidebar to notes on this day.
+    SelectDay(NaiveDate),
+    OpenNote(usize),
+}
+
+/// Month/week calendar of notes by creation or edit date.
+pub struct CalendarView {
+    pub span: Span,
+    pub field: DateField,
+    /// Any day inside the month or week being shown.
+    anchor: NaiveDate,
+}
+
+impl Default for CalendarView {
+    fn default() -> Self {
+        Self {
+            span: Span::Month,
+            field: DateField::Edited,
+            anchor: today(),
+        }
+    }
+}
+
+fn monday_of(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+impl CalendarView {
+    /// The days shown, padded to whole weeks starting on Monday.
+    fn days(&self) -> Vec<NaiveDate> {
+        let (start, end) = match self.span {
+            Span::Week => {
+                let start = monday_of(self.anchor);
+                (start, start + Days::new(6))
+            }
+            Span::Month => {
+                let first = first_of_month(self.anchor);
+                let last = first + Months::new(1) - Days::new(1);
+                (monday_of(first), monday_of(last) + Days::new(6))
+            }
+        };
+        start.iter_days().take_while(|d| *d <= end).collect()
+    }
+
+    fn step(&mut self, forward: bool) {
+        self.anchor = match (self.span, forward) {
+            (Span::Month, true) => self.anchor + Months::new(1),
+            (Span::Month, false) => self.anchor - Months::new(1),
+            (Span::Week, true) => self.anchor + Days::new(7),
+            (Span::Week, false) => self.anchor - Days::new(7),
+        };
+    }
+
+    fn heading(&self) -> String {
+        match self.span {
+            Span::Month => self.anchor.format("%B %Y").to_string(),
+            Span::Week => {
+                let start = monday_of(self.anchor);
+                format!(
+                    "{} – {}",
+                    start.format("%b %d"),
+                    (start + Days::new(6)).format("%b %d, %Y")
+                )
+            }
+        }
+    }
+
+    /// Draw the calendar. `selected_day` is highlighted as the active sidebar filter.
+    pub fn show(&mut self, ui: &mut egui::Ui, notes: &[Note], selected_day: Option<NaiveDate>) -> Option<CalendarAction> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            if ui.button("◀").clicked() {
+                self.step(false);
+            }
+            ui.label(egui::RichText::new(self.heading()).size(18.0).strong());
+            if ui.button("▶").clicked() {
+                self.step(true);
+            }
+            if ui.button("Today").clicked() {
+                self.anchor = today();
+            }
+            ui.separator();
+            ui.selectable_value(&mut self.span, Span::Month, "Month");
+            ui.selectable_value(&mut self.span, Span::Week, "Week");
+            ui.separator();
+            ui.selectable_value(&mut self.field, DateField::Edited, "Edited");
+            ui.selectable_value(&mut self.field, DateField::Created, "Created");
+        });
+        ui.add_space(6.0);
+
+        let days = self.days();
+        let mut per_day: HashMap<NaiveDate, Vec<usize>> = HashMap::new();
+        for (idx, note) in notes.iter().enumerate() {
+            if let Some(date) = local_date(self.field.of(note)) {
+                per_day.entry(date).or_default().push(idx);
+            }
+        }
+        let busiest = days
+            .iter()
+            .map(|d| per_day.get(d).map_or(0, Vec::len))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let spacing = 4.0;
+        let cell_width = ((ui.available_width() - spacing * 6.0) / 7.0).max(40.0);
+        let rows = days.len() / 7;
+        let cell_height = match self.span {
+            Span::Month => ((ui.available_height() - 24.0) / rows as f32 - spacing).clamp(56.0, 120.0),
+            Span::Week => (ui.available_height() - 24.0).max(160.0),
+        };
+        let max_titles = match self.span {
+            Span::Month => ((cell_height - 24.0) / 16.0).floor().max(0.0) as usize,
+            Span::Week => usize::MAX,
+        };
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = spacing;
+            for name in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"] {
+                ui.add_sized([cell_width, 18.0], egui::Label::new(egui::RichText::new(name).weak()));
+            }
+        });
+
+        let origin = ui.cursor().min;
+        let heat = egui::Color32::from_rgb(60, 155, 255);
+        for (i, &day) in days.iter().enumerate() {
+            let col = (i % 7) as f32;
+            let row = (i / 7) as f32;
+            let rect = egui::Rect::from_min_size(
+                origin + egui::vec2(col * (cell_width + spacing), row * (cell_height + spacing)),
+                egui::vec2(cell_width, cell_height),
+            );
+            let day_notes = per_day.get(&day).map(Vec::as_slice).unwrap_or(&[]);
+            let response = ui.interact(rect, ui.id().with(("calendar_day", day)), egui::Sense::click());
+
+            let in_range = self.span == Span::Week || day.month() == self.anchor.month();
+            let mut fill = ui.visuals().faint_bg_color;
+            if !day_notes.is_empty() {
+                let intensity = 0.15 + 0.6 * day_notes.len() as f32 / busiest as f32;
+                fill = heat.gamma_multiply(intensity);
+            }
+            if !in_range {
+                fill = fill.gamma_multiply(0.4);
+            }
+            let stroke = if selected_day == Some(day) {
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 170, 40))
+            } else if response.hovered() {
+                ui.visuals().widgets.hovered.bg_stroke
+            } else {
+                egui::Stroke::NONE
+            };
+            ui.painter().rect(rect, egui::Rounding::same(6.0), fill, stroke);
+
+            let mut cell = ui.child_ui(rect.shrink(4.0), egui::Layout::top_down(egui::Align::Min));
+            cell.set_clip_rect(rect);
+            cell.horizontal(|ui| {
+                let mut number = egui::RichText::new(day.day().to_string()).strong();
+                if day == today() {
+                    number = number.underline();
+                }
+                if !in_range {
+                    number = number.weak();
+                }
+                ui.label(number);
+                if !day_notes.is_empty() {
+                    ui.label(egui::RichText::new(format!("· {}", day_notes.len())).weak().size(12.0));
+                }
+            });
+            for &idx in day_notes.iter().take(max_titles) {
+                let title = egui::RichText::new(&notes[idx].title)
+                    .size(12.0)
+                    .color(cell.visuals().hyperlink_color);
+                let link = cell
+                    .add(egui::Label::new(title).truncate(true).sense(egui::Sense::click()))
+                    .on_hover_cursor(egui::CursorIcon::PointingHand);
+                if link.clicked() {
+                    action = Some(CalendarAction::OpenNote(idx));
+                }
+            }
+            if day_notes.len() > max_titles {
+                cell.label(egui::RichText::new(format!("+{} more", day_notes.len() - max_titles)).weak().size(12.0));
+            }
+
+            if response
+                .on_hover_text(format!("{} note(s) {} on {}", day_notes.len(), self.field.label(), day.format("%Y-%m-%d")))
+                .clicked()
+                && action.is_none()
+            {
+                action = Some(CalendarAction::SelectDay(day));
+            }
+        }
+        ui.allocate_space(egui::vec2(
+            7.0 * cell_width + 6.0 * spacing,
+            rows as f32 * (cell_height + spacing),
+        ));
+        action
+    }
+}
@@ -0,0 +1,387 @@
+use crate::attachments;
+use crate::export::{self, Scope};
+use crate::html::{self, format_date};
+use note_core::links;
+use note_core::note::Note;
+use note_core::ops::{self, NoteFields};
+use crate::pdf;
+use crate::records::{self, ExportRecord, RecordFormat};
+use note_core::storage::Storage;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::process::Command;
+
+/// Environment variable holding the passphrase of an encrypted note store.
+const PASSPHRASE_VAR: &str = "NOTES_PASSPHRASE";
+
+const USAGE: &str = "Usage: advanced_note_app [COMMAND] [OPTIONS]
+
+Without a command, the app window opens. Commands work on the notes in the
+current folder, like the app.
+
+Commands:
+  list                  List notes
+  show <note>           Print a note
+  create <title>        Create a note with content read from stdin
+  append <note>         Append text read from stdin to a note
+  edit <note>           Edit a note's content in $EDITOR
+  delete <note>         Delete a note
+  search <text>         List notes whose title, content or tags contain <text>
+  export [<note>...]    Export notes (all, or the ones given)
+  help                  Show this message
+
+<note> is a note ID or title.
+
+Options:
+  --json                Print notes as JSON, in the JSON Lines export schema
+  --tag <tag>           list, search, export: only notes with this tag
+                        create: comma-separated tags for the new note
+  --format <format>     export: jsonl (default), csv, md, html or pdf
+  -o, --output <path>   export: file, folder or .zip to write; jsonl and csv
+                        go to stdout without it
+  --dir <path>          Use the notes in this folder
+
+An encrypted note store is unlocked with the passphrase in $NOTES_PASSPHRASE.
+Locked notes can be listed and deleted, but not shown or changed.";
+
+/// Write `text` to stdout. A closed pipe, e.g. from `| head`, ends the program quietly.
+fn out(text: &str) {
+    if let Err(e) = io::stdout().write_all(text.as_bytes()) {
+        if e.kind() == io::ErrorKind::BrokenPipe {
+            std::process::exit(0);
+        }
+        eprintln!("Can't write to stdout: {e}");
+        std::process::exit(1);
+    }
+}
+
+#[derive(Default)]
+struct Args {
+    positional: Vec<String>,
+    json: bool,
+    tag: Option<String>,
+    format: Option<String>,
+    output: Option<String>,
+    dir: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| iter.next().cloned().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "--json" => parsed.json = true,
+            "--tag" => parsed.tag = Some(value(arg)?),
+            "--format" => parsed.format = Some(value(arg)?),
+            "-o" | "--output" => parsed.output = Some(value(arg)?),
+            "--dir" => parsed.dir = Some(value(arg)?),
+            // Everything after `--` is positional, e.g. a title starting with a dash.
+            "--" => parsed.positional.extend(iter.by_ref().cloned()),
+            _ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("Unknown option {arg}")),
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Run the command in `args` (without the program name). Returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let Some((command, rest)) = args.split_first() else {
+        out(&format!("{USAGE}\n"));
+        return 0;
+    };
+    if matches!(command.as_str(), "help" | "-h" | "--help") {
+        out(&format!("{USAGE}\n"));
+        return 0;
+    }
+    let args = match parse_args(rest) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}; run with help to see the options");
+            return 2;
+        }
+    };
+    if let Some(dir) = &args.dir {
+        if let Err(e) = env::set_current_dir(dir) {
+            eprintln!("Can't open {dir}: {e}");
+            return 1;
+        }
+    }
+    let result = match command.as_str() {
+        "list" => list(&args),
+        "show" => show(&args),
+        "create" => create(&args),
+        "append" => append(&args),
+        "edit" => edit(&args),
+        "delete" => delete(&args),
+        "search" => search(&args),
+        "export" => export(&args),
+        _ => {
+            eprintln!("Unknown command {command}; run with help to see the commands");
+            return 2;
+        }
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn load() -> Result<Vec<Note>, String> {
+    if Storage::is_encrypted() {
+        let passphrase = env::var(PASSPHRASE_VAR)
+            .map_err(|_| format!("The note store is encrypted; set {PASSPHRASE_VAR} to its passphrase"))?;
+        return Storage::unlock(&passphrase);
+    }
+    Storage::try_load_notes()
+}
+
+fn save(notes: &[Note]) -> Result<(), String> {
+    Storage::save_notes(notes).map_err(|e| format!("Error saving notes: {e}"))
+}
+
+/// The one note named by the positional arguments, which may be a title with spaces.
+fn target(notes: &[Note], args: &Args) -> Result<usize, String> {
+    if args.positional.is_empty() {
+        return Err("Which note? Give its ID or title".to_owned());
+    }
+    ops::find(notes, &args.positional.join(" "))
+}
+
+fn unlocked(notes: &[Note], idx: usize) -> Result<(), String> {
+    if notes[idx].is_sealed() {
+        return Err(format!("\"{}\" is locked; unlock it in the app first", notes[idx].title));
+    }
+    Ok(())
+}
+
+fn read_stdin() -> Result<String, String> {
+    if io::stdin().is_terminal() {
+        eprintln!("Type the text, then press Ctrl-D (Ctrl-Z on Windows) on an empty line.");
+    }
+    let mut text = String::new();
+    io::stdin().read_to_string(&mut text).map_err(|e| format!("Can't read stdin: {e}"))?;
+    Ok(text)
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    out(&format!("{}\n", serde_json::to_string_pretty(value).map_err(|e| e.to_string())?));
+    Ok(())
+}
+
+/// Print notes one per line, or as a JSON array.
+fn print_list(notes: &[Note], indices: &[usize], json: bool) -> Result<(), String> {
+    if json {
+        let records: Vec<ExportRecord> = indices.iter().map(|&i| ExportRecord::new(&notes[i])).collect();
+        return print_json(&records);
+    }
+    for &i in indices {
+        let note = &notes[i];
+        let mut line = format!("{:>5}  {}  {}", note.id, format_date(note.edited), note.title);
+        if note.locked {
+            line.push_str("  🔒");
+        }
+        if !note.tags.is_empty() {
+            line.push_str(&format!("  #{}", note.tags.join(" #")));
+        }
+        out(&format!("{line}\n"));
+    }
+    Ok(())
+}
+
+/// Print one note: its content, or its record with `--json`.
+fn print_note(note: &Note, json: bool) -> Result<(), String> {
+    if json {
+        return print_json(&ExportRecord::new(note));
+    }
+    out(&format!("{}\n", note.title));
+    let mut meta = format!("ID {} · created {} · edited {}", note.id, format_date(note.created), format_date(note.edited));
+    if !note.tags.is_empty() {
+        meta.push_str(&format!(" · #{}", note.tags.join(" #")));
+    }
+    if let Some(due) = note.due {
+        meta.push_str(&format!(" · due {}", format_date(due)));
+    }
+    if let Some(status) = &note.status {
+        meta.push_str(&format!(" · {status}"));
+    }
+    out(&format!("{meta}\n\n"));
+    out(&format!("{}\n", note.content.trim_end()));
+    Ok(())
+}
+
+fn list(args: &Args) -> Result<(), String> {
+    let notes = load()?;
+    print_list(&notes, &ops::search(&notes, "", args.tag.as_deref()), args.json)
+}
+
+fn show(args: &Args) -> Result<(), String> {
+    let notes = load()?;
+    let idx = target(&notes, args)?;
+    unlocked(&notes, idx)?;
+    print_note(&notes[idx], args.json)
+}
+
+fn search(args: &Args) -> Result<(), String> {
+    let notes = load()?;
+    let query = args.positional.join(" ");
+    let indices = ops::search(&notes, &query, args.tag.as_deref());
+    print_list(&notes, &indices, args.json)
+}
+
+fn create(args: &Args) -> Result<(), String> {
+    let mut notes = load()?;
+    let title = args.positional.join(" ");
+    let title = title.trim();
+    if title.is_empty() {
+        return Err("Give the new note a title".to_owned());
+    }
+    if let Some(i) = links::find_by_title(&notes, title) {
+        return Err(format!("A note titled \"{}\" already exists (ID {})", notes[i].title, notes[i].id));
+    }
+    let fields = NoteFields {
+        title: Some(title.to_owned()),
+        content: Some(read_stdin()?),
+        tags: args.tag.as_deref().map(Note::parse_tags),
+        ..Default::default()
+    };
+    let idx = ops::create(&mut notes, fields)?;
+    save(&notes)?;
+    let note = &notes[idx];
+    if args.json {
+        return print_json(&ExportRecord::new(note));
+    }
+    out(&format!("Created note {}: {}\n", note.id, note.title));
+    Ok(())
+}
+
+fn append(args: &Args) -> Result<(), String> {
+    let mut notes = load()?;
+    let idx = target(&notes, args)?;
+    unlocked(&notes, idx)?;
+    ops::append(&mut notes, idx, &read_stdin()?)?;
+    save(&notes)?;
+    if args.json {
+        return print_json(&ExportRecord::new(&notes[idx]));
+    }
+    out(&format!("Appended to note {}: {}\n", notes[idx].id, notes[idx].title));
+    Ok(())
+}
+
+/// The editor command from `$VISUAL` or `$EDITOR`, split into program and arguments.
+fn editor() -> Vec<String> {
+    let command = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| if cfg!(windows) { "notepad" } else { "vi" }.to_owned());
+    command.split_whitespace().map(str::to_owned).collect()
+}
+
+/// Write `content` to a new temporary file only the current user can read.
+fn temp_file(path: &Path, content: &str) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    io::Write::write_all(&mut options.open(path)?, content.as_bytes())
+}
+
+fn edit(args: &Args) -> Result<(), String> {
+    let mut notes = load()?;
+    let idx = target(&notes, args)?;
+    unlocked(&notes, idx)?;
+    let path = env::temp_dir().join(format!("note-{}-{}.md", notes[idx].id, std::process::id()));
+    temp_file(&path, &notes[idx].content).map_err(|e| format!("Can't write {}: {e}", path.display()))?;
+    let editor = editor();
+    let status = Command::new(&editor[0]).args(&editor[1..]).arg(&path).status();
+    let content = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => return Err(format!("{} exited with {status}; the note was not changed", editor[0])),
+        Err(e) => return Err(format!("Can't run {}: {e}", editor[0])),
+    }
+    let content = content.map_err(|e| format!("Can't read the edited note: {e}"))?;
+    if content == notes[idx].content {
+        out("No changes\n");
+        return Ok(());
+    }
+    ops::update(&mut notes, idx, NoteFields { content: Some(content), ..Default::default() })?;
+    save(&notes)?;
+    if args.json {
+        return print_json(&ExportRecord::new(&notes[idx]));
+    }
+    out(&format!("Saved note {}: {}\n", notes[idx].id, notes[idx].title));
+    Ok(())
+}
+
+fn delete(args: &Args) -> Result<(), String> {
+    let mut notes = load()?;
+    let idx = target(&notes, args)?;
+    let note = ops::delete(&mut notes, idx)?;
+    save(&notes)?;
+    attachments::collect_garbage(&notes);
+    if args.json {
+        return print_json(&ExportRecord::new(&note));
+    }
+    out(&format!("Deleted note {}: {}\n", note.id, note.title));
+    Ok(())
+}
+
+fn export(args: &Args) -> Result<(), String> {
+    let notes = load()?;
+    let (indices, skipped) = if args.positional.is_empty() {
+        let scope = if args.tag.is_some() { Scope::Tag } else { Scope::All };
+        export::select(&notes, scope, None, &[], args.tag.as_deref().unwrap_or_default())
+    } else {
+        let indices = args.positional.iter().map(|r| ops::find(&notes, r)).collect::<Result<Vec<_>, _>>()?;
+        export::select(&notes, Scope::Filtered, None, &indices, "")
+    };
+    if skipped > 0 {
+        eprintln!("Skipped {skipped} locked note(s)");
+    }
+    if indices.is_empty() {
+        return Err("No notes to export".to_owned());
+    }
+    let output = args.output.as_deref().map(Path::new);
+    let format = args.format.as_deref().unwrap_or("jsonl");
+    let text = match format {
+        "jsonl" | "json" => Some(records::export(&notes, &indices, RecordFormat::JsonLines)?),
+        "csv" => Some(records::export(&notes, &indices, RecordFormat::Csv)?),
+        "md" | "markdown" | "html" | "pdf" => None,
+        _ => return Err(format!("Unknown format {format}; use jsonl, csv, md, html or pdf")),
+    };
+    let write_error = |e: io::Error| format!("Can't write {}: {e}", args.output.as_deref().unwrap_or_default());
+    match (text, output) {
+        (Some(text), None) => {
+            out(&text);
+            return Ok(());
+        }
+        (Some(text), Some(path)) => export::write_file(path, text).map_err(write_error)?,
+        (None, None) => return Err(format!("Exporting {format} needs --output")),
+        (None, Some(path)) if format == "pdf" => {
+            let title = match indices.as_slice() {
+                [only] => notes[*only].title.as_str(),
+                _ => "Notes",
+            };
+            export::write_file(path, pdf::pdf_document(&notes, &indices, title)?).map_err(write_error)?;
+        }
+        (None, Some(path)) => {
+            let files = if format == "html" {
+                html::html_files(&notes, &indices, "Notes", false, true)
+            } else {
+                export::markdown_files(&notes, &indices, true)
+            }
+            .map_err(|e| format!("Can't export: {e}"))?;
+            let zip = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+            if zip { export::write_zip(path, &files) } else { export::write_folder(path, &files) }.map_err(write_error)?;
+        }
+    }
+    eprintln!("Exported {} note(s) to {}", indices.len(), args.output.as_deref().unwrap_or_default());
+    Ok(())
+}
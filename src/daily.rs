@@ -0,0 +1,217 @@
+use note_core::note::Note;
+use note_core::ops::NoteFields;
+use crate::settings::Settings;
+use crate::templates;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Local, Months, NaiveDate, TimeZone};
+use eframe::egui;
+
+/// Daily notes carry this tag so renamed or unrelated notes with date-like titles are not picked up.
+pub const DAILY_TAG: &str = "daily";
+
+const FALLBACK_FORMAT: &str = "%Y-%m-%d";
+
+/// Whether a title in `fmt` reads back as the date it was made from. One without the year,
+/// or with only the weekday, doesn't, and the day's note would not be found again.
+pub fn is_readable_format(fmt: &str) -> bool {
+    let sample = NaiveDate::from_ymd_opt(2024, 11, 23).unwrap();
+    !fmt.trim().is_empty()
+        && !StrftimeItems::new(fmt).any(|item| matches!(item, Item::Error))
+        && NaiveDate::parse_from_str(sample.format(fmt).to_string().trim(), fmt).ok() == Some(sample)
+}
+
+/// The configured title format, or the default one if titles in it can't be read back.
+fn title_format(settings: &Settings) -> &str {
+    let fmt = settings.daily_title_format.as_str();
+    if is_readable_format(fmt) {
+        fmt
+    } else {
+        FALLBACK_FORMAT
+    }
+}
+
+pub fn title_for(date: NaiveDate, settings: &Settings) -> String {
+    date.format(title_format(settings)).to_string()
+}
+
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// The date a daily note is for, if `note` is one.
+pub fn date_of(note: &Note, settings: &Settings) -> Option<NaiveDate> {
+    if !note.has_tag(DAILY_TAG) {
+        return None;
+    }
+    NaiveDate::parse_from_str(note.title.trim(), title_format(settings)).ok()
+}
+
+/// Index of the daily note for `date`.
+pub fn find(notes: &[Note], date: NaiveDate, settings: &Settings) -> Option<usize> {
+    notes.iter().position(|n| date_of(n, settings) == Some(date))
+}
+
+/// Fields of a new daily note for `date`, using the configured template if there is one.
+pub fn draft(notes: &[Note], date: NaiveDate, settings: &Settings) -> NoteFields {
+    let title = title_for(date, settings);
+    let mut note = Note::new(0, &title);
+    let template = templates::templates(notes)
+        .into_iter()
+        .find(|&i| match settings.daily_template {
+            Some(id) => notes[i].id == id,
+            None => !settings.old_daily_template.is_empty() && notes[i].title == settings.old_daily_template,
+        });
+    if let Some(idx) = template {
+        // Expand date placeholders for the day the note is for, not for today.
+        let now = Local::now();
+        let when = Local
+            .from_local_datetime(&date.and_time(now.time()))
+            .single()
+            .unwrap_or(now);
+        let draft = templates::instantiate(&notes[idx], &title, &[], when);
+        note.content = draft.content;
+        note.tags = draft.tags;
+    }
+    if !note.has_tag(DAILY_TAG) {
+        note.tags.push(DAILY_TAG.to_owned());
+    }
+    NoteFields {
+        title: Some(note.title),
+        content: Some(note.content),
+        tags: Some(note.tags),
+        ..Default::default()
+    }
+}
+
+/// The closest existing daily note before (or after, if `forward`) `date`.
+pub fn adjacent(notes: &[Note], date: NaiveDate, settings: &Settings, forward: bool) -> Option<(usize, NaiveDate)> {
+    notes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, n)| date_of(n, settings).map(|d| (i, d)))
+        .filter(|&(_, d)| if forward { d > date } else { d < date })
+        .min_by_key(|&(_, d)| (d - date).num_days().abs())
+}
+
+pub fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap()
+}
+
+/// Popup month calendar for jumping to any day's note.
+pub struct CalendarPicker {
+    pub open: bool,
+    month: NaiveDate,
+}
+
+impl Default for CalendarPicker {
+    fn default() -> Self {
+        Self {
+            open: false,
+            month: first_of_month(today()),
+        }
+    }
+}
+
+impl CalendarPicker {
+    /// Draw the picker window. Returns the day the user clicked.
+    pub fn show(&mut self, ctx: &egui::Context, notes: &[Note], settings: &Settings) -> Option<NaiveDate> {
+        if !self.open {
+            return None;
+        }
+        let mut picked = None;
+        let mut open = self.open;
+        egui::Window::new("🗓 Daily Notes")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("◀").clicked() {
+                        self.month = self.month - Months::new(1);
+                    }
+                    ui.label(egui::RichText::new(self.month.format("%B %Y").to_string()).strong());
+                    if ui.button("▶").clicked() {
+                        self.month = self.month + Months::new(1);
+                    }
+                    if ui.button("Today").clicked() {
+                        self.month = first_of_month(today());
+                    }
+                });
+                ui.add_space(4.0);
+                egui::Grid::new("daily_calendar").spacing([4.0, 4.0]).show(ui, |ui| {
+                    for name in ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"] {
+                        ui.label(egui::RichText::new(name).weak());
+                    }
+                    ui.end_row();
+                    let offset = self.month.weekday().num_days_from_monday();
+                    for _ in 0..offset {
+                        ui.label("");
+                    }
+                    let mut day = self.month;
+                    while day.month() == self.month.month() {
+                        let has_note = find(notes, day, settings).is_some();
+                        let mut text = egui::RichText::new(day.day().to_string());
+                        if has_note {
+                            text = text.strong().color(egui::Color32::from_rgb(60, 155, 255));
+                        }
+                        if day == today() {
+                            text = text.underline();
+                        }
+                        let hover = if has_note { "Open this day's note" } else { "Create a note for this day" };
+                        if ui
+                            .add(egui::Button::new(text).min_size([28.0, 24.0].into()))
+                            .on_hover_text(hover)
+                            .clicked()
+                        {
+                            picked = Some(day);
+                        }
+                        if day.weekday().num_days_from_monday() == 6 {
+                            ui.end_row();
+                        }
+                        day = day.succ_opt().unwrap();
+                    }
+                });
+            });
+        self.open = open && picked.is_none();
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(format: &str) -> Settings {
+        Settings { daily_title_format: format.to_owned(), ..Default::default() }
+    }
+
+    #[test]
+    fn only_formats_that_read_back_are_used() {
+        assert!(is_readable_format("%Y-%m-%d"));
+        assert!(is_readable_format("%A, %B %d %Y"));
+        assert!(!is_readable_format("%B %d"));
+        assert!(!is_readable_format("%A"));
+        assert!(!is_readable_format("%Q"));
+        assert!(!is_readable_format(" "));
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        assert_eq!(title_for(date, &settings("%B %d")), "2025-03-07");
+        assert_eq!(title_for(date, &settings("%d.%m.%Y")), "07.03.2025");
+    }
+
+    #[test]
+    fn daily_notes_are_found_by_their_title_and_tag() {
+        let settings = settings("%A, %B %d %Y");
+        let date = NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        let draft = draft(&[], date, &settings);
+        let mut note = Note::new(1, draft.title.as_deref().unwrap());
+        note.tags = draft.tags.unwrap();
+        let mut untagged = note.clone();
+        untagged.tags.clear();
+
+        let notes = vec![untagged, note];
+        assert_eq!(find(&notes, date, &settings), Some(1));
+        assert_eq!(find(&notes, date.succ_opt().unwrap(), &settings), None);
+        assert_eq!(adjacent(&notes, date.succ_opt().unwrap(), &settings, false), Some((1, date)));
+    }
+}
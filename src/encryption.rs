@@ -0,0 +1,152 @@
+use note_core::note::Note;
+use note_core::storage::Storage;
+use eframe::egui;
+use zeroize::Zeroize;
+
+/// Passphrases shorter than this are refused when setting one.
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Shown instead of the app while the encrypted store is locked.
+#[derive(Default)]
+pub struct UnlockScreen {
+    passphrase: String,
+    error: Option<String>,
+}
+
+impl UnlockScreen {
+    /// Draw the unlock screen. Returns the decrypted notes once the right passphrase is entered.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Vec<Note>> {
+        let mut notes = None;
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 3.0);
+                ui.heading(egui::RichText::new("🔒 Notes are locked").size(28.0).strong());
+                ui.add_space(12.0);
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.passphrase)
+                        .password(true)
+                        .hint_text("Passphrase")
+                        .desired_width(260.0),
+                );
+                response.request_focus();
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.add_space(8.0);
+                if ui.button("🔓 Unlock").clicked() || submitted {
+                    match Storage::unlock(&self.passphrase) {
+                        Ok(loaded) => {
+                            self.error = None;
+                            notes = Some(loaded);
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                    self.passphrase.zeroize();
+                }
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 80, 80), error);
+                }
+            });
+        });
+        notes
+    }
+}
+
+/// Encryption section of the settings window.
+#[derive(Default)]
+pub struct EncryptionPanel {
+    current: String,
+    new: String,
+    confirm: String,
+    status: Option<Result<String, String>>,
+}
+
+impl EncryptionPanel {
+    fn check_new(&self) -> Result<(), String> {
+        if self.new.chars().count() < MIN_PASSPHRASE_LEN {
+            Err(format!("Use at least {MIN_PASSPHRASE_LEN} characters"))
+        } else if self.new != self.confirm {
+            Err("Passphrases don't match".to_owned())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn clear(&mut self) {
+        self.current.zeroize();
+        self.new.zeroize();
+        self.confirm.zeroize();
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, notes: &[Note], auto_lock_minutes: &mut u32) {
+        let encrypted = Storage::is_encrypted();
+        let password = |ui: &mut egui::Ui, value: &mut String, hint: &str| {
+            ui.add(egui::TextEdit::singleline(value).password(true).hint_text(hint));
+        };
+        if encrypted {
+            ui.label("Notes are encrypted with a passphrase.");
+            ui.horizontal(|ui| {
+                ui.label("Lock after idle for");
+                ui.add(egui::DragValue::new(auto_lock_minutes).clamp_range(0..=1440).suffix(" min"));
+            })
+            .response
+            .on_hover_text("0 never locks automatically");
+            egui::Grid::new("settings_encryption").num_columns(2).show(ui, |ui| {
+                ui.label("Current passphrase");
+                password(ui, &mut self.current, "");
+                ui.end_row();
+                ui.label("New passphrase");
+                password(ui, &mut self.new, "");
+                ui.end_row();
+                ui.label("Confirm");
+                password(ui, &mut self.confirm, "");
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Change passphrase").clicked() {
+                    self.status = Some(
+                        self.check_new()
+                            .and_then(|_| Storage::change_passphrase(&self.current, &self.new, notes))
+                            .map(|_| "Passphrase changed".to_owned()),
+                    );
+                    self.clear();
+                }
+                if ui
+                    .button("Turn off encryption")
+                    .on_hover_text("Needs the current passphrase; notes are saved as plain JSON again")
+                    .clicked()
+                {
+                    self.status = Some(
+                        Storage::disable_encryption(&self.current, notes).map(|_| "Encryption turned off".to_owned()),
+                    );
+                    self.clear();
+                }
+            });
+        } else {
+            ui.label("Encrypt notes.json with a passphrase. There is no way to recover a forgotten passphrase.");
+            egui::Grid::new("settings_encryption").num_columns(2).show(ui, |ui| {
+                ui.label("Passphrase");
+                password(ui, &mut self.new, "");
+                ui.end_row();
+                ui.label("Confirm");
+                password(ui, &mut self.confirm, "");
+                ui.end_row();
+            });
+            if ui.button("🔒 Encrypt notes").clicked() {
+                self.status = Some(
+                    self.check_new()
+                        .and_then(|_| Storage::enable_encryption(&self.new, notes))
+                        .map(|_| "Notes are now encrypted".to_owned()),
+                );
+                self.clear();
+            }
+        }
+        match &self.status {
+            Some(Ok(message)) => {
+                ui.label(egui::RichText::new(message).size(13.0).weak());
+            }
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::from_rgb(255, 80, 80), error);
+            }
+            None => {}
+        }
+    }
+}
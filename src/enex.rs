@@ -0,0 +1,699 @@
+use crate::attachments::{self, Attachment};
+use crate::images::{self, ATTACHMENT_SCHEME};
+use crate::import::{self, Blob, Candidate, Resolution};
+use note_core::note::Note;
+use crate::properties::PropertyValue;
+use base64::Engine;
+use chrono::NaiveDateTime;
+use md5::{Digest, Md5};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Named HTML entities that turn up in notes. XML only defines five.
+fn resolve_entity(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "nbsp" => "\u{a0}",
+        "amp" => "&",
+        "lt" => "<",
+        "gt" => ">",
+        "quot" => "\"",
+        "apos" => "'",
+        "mdash" => "—",
+        "ndash" => "–",
+        "hellip" => "…",
+        "lsquo" => "‘",
+        "rsquo" => "’",
+        "ldquo" => "“",
+        "rdquo" => "”",
+        "laquo" => "«",
+        "raquo" => "»",
+        "bull" => "•",
+        "middot" => "·",
+        "copy" => "©",
+        "reg" => "®",
+        "trade" => "™",
+        "deg" => "°",
+        "times" => "×",
+        "divide" => "÷",
+        "euro" => "€",
+        "pound" => "£",
+        "yen" => "¥",
+        "cent" => "¢",
+        _ => return None,
+    })
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    let attr = element.try_get_attribute(name).ok().flatten()?;
+    Some(match attr.unescape_value() {
+        Ok(value) => value.into_owned(),
+        Err(_) => String::from_utf8_lossy(&attr.value).into_owned(),
+    })
+}
+
+fn text_of(text: &quick_xml::events::BytesText) -> String {
+    match text.unescape_with(resolve_entity) {
+        Ok(s) => s.into_owned(),
+        Err(_) => String::from_utf8_lossy(text).into_owned(),
+    }
+}
+
+/// What an open element did, so its end tag can undo it.
+enum Open {
+    Inline(&'static str),
+    Link(String),
+    Block,
+    Paragraph,
+    Heading,
+    Pre,
+    List,
+    Item,
+    Quote,
+    Table,
+    Row,
+    Cell,
+    /// Content that isn't converted, such as scripts or encrypted text.
+    Hidden,
+    Other,
+}
+
+/// Builds Markdown from HTML elements as they are read.
+struct Markdown<'a> {
+    out: String,
+    stack: Vec<Open>,
+    /// Written at the start of each line: quote markers and list indentation.
+    prefixes: Vec<String>,
+    /// Open lists, with the next number for ordered ones.
+    lists: Vec<Option<u32>>,
+    /// Rows of the table being read; cell text is collected here instead of `out`.
+    table: Vec<Vec<String>>,
+    pre: usize,
+    hidden: usize,
+    /// Evernote's own to-do list style.
+    todo_list: bool,
+    resources: &'a HashMap<String, Attachment>,
+    warnings: Vec<String>,
+}
+
+impl<'a> Markdown<'a> {
+    fn new(resources: &'a HashMap<String, Attachment>) -> Self {
+        Self {
+            out: String::new(),
+            stack: Vec::new(),
+            prefixes: Vec::new(),
+            lists: Vec::new(),
+            table: Vec::new(),
+            pre: 0,
+            hidden: 0,
+            todo_list: false,
+            resources,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn warn(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    fn in_cell(&self) -> bool {
+        matches!(self.stack.iter().rev().find(|o| matches!(o, Open::Cell | Open::Table)), Some(Open::Cell))
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    /// Write `text` as is, starting a new line with the current prefixes if needed.
+    fn write(&mut self, text: &str) {
+        if self.in_cell() {
+            if let Some(cell) = self.table.last_mut().and_then(|row| row.last_mut()) {
+                cell.push_str(text);
+            }
+            return;
+        }
+        if self.at_line_start() && !text.is_empty() {
+            let prefix: String = self.prefixes.concat();
+            self.out.push_str(&prefix);
+        }
+        self.out.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        if self.in_cell() {
+            self.write(" ");
+            return;
+        }
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.at_line_start() {
+            self.out.push('\n');
+        }
+    }
+
+    fn break_line(&mut self) {
+        if self.in_cell() {
+            self.write(" ");
+            return;
+        }
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        self.out.push('\n');
+    }
+
+    /// End the current line and leave an empty one, unless inside a list item.
+    fn blank_line(&mut self) {
+        self.newline();
+        if self.out.is_empty() || self.out.ends_with("\n\n") || self.in_cell() {
+            return;
+        }
+        if self.stack.iter().any(|o| matches!(o, Open::Item)) {
+            return;
+        }
+        let prefix: String = self.prefixes.concat();
+        self.out.push_str(prefix.trim_end());
+        self.out.push('\n');
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.hidden > 0 {
+            return;
+        }
+        if self.pre > 0 {
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    self.break_line();
+                }
+                self.write(line);
+            }
+            return;
+        }
+        // Outside preformatted text, runs of whitespace are a single space.
+        let mut collapsed = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c.is_whitespace() && c != '\u{a0}' {
+                if !collapsed.ends_with(' ') {
+                    collapsed.push(' ');
+                }
+            } else {
+                collapsed.push(c);
+            }
+        }
+        let ends_with_space = self.out.ends_with(' ') || self.at_line_start();
+        let collapsed = if ends_with_space && !self.in_cell() {
+            collapsed.trim_start()
+        } else {
+            &collapsed
+        };
+        // Non-breaking spaces keep their width but are stored as plain spaces, so search finds them.
+        self.write(&collapsed.replace('\u{a0}', " "));
+    }
+
+    fn start(&mut self, element: &BytesStart, empty: bool) {
+        let name = String::from_utf8_lossy(element.local_name().as_ref()).to_lowercase();
+        let style = attribute(element, "style").unwrap_or_default().replace(' ', "");
+        let open = match name.as_str() {
+            "br" => {
+                self.break_line();
+                Open::Other
+            }
+            "hr" => {
+                self.blank_line();
+                self.write("---");
+                self.blank_line();
+                Open::Other
+            }
+            "p" => {
+                self.blank_line();
+                Open::Paragraph
+            }
+            "div" if style.contains("-en-codeblock:true") => {
+                self.blank_line();
+                self.write("```");
+                self.newline();
+                self.pre += 1;
+                Open::Pre
+            }
+            "div" | "section" | "article" | "header" | "footer" | "center" | "dl" | "dt" | "dd" | "figure" => {
+                self.newline();
+                Open::Block
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.blank_line();
+                let level = name[1..].parse().unwrap_or(1);
+                self.write(&format!("{} ", "#".repeat(level)));
+                Open::Heading
+            }
+            "pre" => {
+                self.blank_line();
+                self.write("```");
+                self.newline();
+                self.pre += 1;
+                Open::Pre
+            }
+            "blockquote" => {
+                self.blank_line();
+                self.prefixes.push("> ".to_owned());
+                Open::Quote
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.blank_line();
+                } else {
+                    self.newline();
+                }
+                self.todo_list = style.contains("--en-todo:true");
+                let start = attribute(element, "start").and_then(|s| s.parse().ok()).unwrap_or(1);
+                self.lists.push((name == "ol").then_some(start));
+                Open::List
+            }
+            "li" => {
+                self.newline();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "- ".to_owned(),
+                };
+                let checkbox = if self.todo_list {
+                    if style.contains("--en-checked:true") { "[x] " } else { "[ ] " }
+                } else {
+                    ""
+                };
+                self.write(&format!("{marker}{checkbox}"));
+                self.prefixes.push(" ".repeat(marker.len()));
+                Open::Item
+            }
+            "table" => {
+                if self.in_cell() {
+                    self.warn("A table inside a table was flattened".to_owned());
+                }
+                self.blank_line();
+                self.table.clear();
+                Open::Table
+            }
+            "tr" => {
+                self.table.push(Vec::new());
+                Open::Row
+            }
+            "td" | "th" => {
+                if let Some(row) = self.table.last_mut() {
+                    row.push(String::new());
+                }
+                Open::Cell
+            }
+            "b" | "strong" => self.inline("**"),
+            "i" | "em" => self.inline("*"),
+            "s" | "strike" | "del" => self.inline("~~"),
+            "code" if self.pre == 0 => self.inline("`"),
+            "a" => {
+                let href = attribute(element, "href").unwrap_or_default();
+                self.write("[");
+                Open::Link(href)
+            }
+            "img" => {
+                let src = attribute(element, "src").unwrap_or_default();
+                let alt = attribute(element, "alt").unwrap_or_default();
+                if src.starts_with("http://") || src.starts_with("https://") {
+                    self.warn(format!("Remote image {src} was kept as a link"));
+                    self.write(&format!("[{}]({src})", if alt.is_empty() { "image" } else { &alt }));
+                } else if !src.is_empty() {
+                    self.warn("An embedded image without a file couldn't be converted".to_owned());
+                }
+                Open::Other
+            }
+            "en-media" => {
+                let hash = attribute(element, "hash").unwrap_or_default().to_lowercase();
+                match self.resources.get(&hash) {
+                    Some(a) if images::is_image(&a.name) => {
+                        self.write(&format!("![{}]({ATTACHMENT_SCHEME}{})", a.name, a.hash));
+                    }
+                    Some(a) => {
+                        let name = a.name.clone();
+                        self.write(&format!("📎 {name}"));
+                    }
+                    None => self.warn(format!("Missing attachment {hash}")),
+                }
+                Open::Other
+            }
+            "en-todo" => {
+                let checked = attribute(element, "checked").is_some_and(|c| c == "true");
+                let checkbox = if checked { "[x] " } else { "[ ] " };
+                if self.stack.iter().any(|o| matches!(o, Open::Item)) {
+                    self.write(checkbox);
+                } else {
+                    self.newline();
+                    self.write(&format!("- {checkbox}"));
+                }
+                Open::Other
+            }
+            "en-crypt" => {
+                self.warn("Encrypted text can't be imported; it was left out".to_owned());
+                self.write("🔒 [encrypted text]");
+                self.hidden += 1;
+                Open::Hidden
+            }
+            "script" | "style" | "head" | "title" | "object" | "embed" | "iframe" => {
+                self.hidden += 1;
+                Open::Hidden
+            }
+            _ => Open::Other,
+        };
+        if empty {
+            self.finish(open);
+        } else {
+            self.stack.push(open);
+        }
+    }
+
+    fn inline(&mut self, mark: &'static str) -> Open {
+        self.write(mark);
+        Open::Inline(mark)
+    }
+
+    fn end(&mut self) {
+        if let Some(open) = self.stack.pop() {
+            self.finish(open);
+        }
+    }
+
+    fn finish(&mut self, open: Open) {
+        match open {
+            Open::Inline(mark) => {
+                // `**text **` doesn't render, so the mark goes before trailing spaces.
+                let spaces = self.out.len() - self.out.trim_end_matches(' ').len();
+                if spaces > 0 && !self.in_cell() {
+                    self.out.truncate(self.out.len() - spaces);
+                    self.write(mark);
+                    self.write(&" ".repeat(spaces));
+                } else {
+                    self.write(mark);
+                }
+                if self.out.ends_with(&format!("{mark}{mark}")) {
+                    let len = self.out.len() - 2 * mark.len();
+                    self.out.truncate(len);
+                }
+            }
+            Open::Link(href) => {
+                if href.starts_with("evernote:") {
+                    // Links between Evernote notes become links by title.
+                    let start = self.out.rfind('[').unwrap_or(self.out.len());
+                    let text = self.out[start + 1..].to_owned();
+                    self.out.truncate(start);
+                    self.write(&format!("[[{text}]]"));
+                } else {
+                    self.write(&format!("]({href})"));
+                }
+            }
+            Open::Block => self.newline(),
+            Open::Paragraph | Open::Heading => self.blank_line(),
+            Open::Pre => {
+                self.pre -= 1;
+                self.newline();
+                self.write("```");
+                self.blank_line();
+            }
+            Open::Quote => {
+                self.newline();
+                self.prefixes.pop();
+                self.blank_line();
+            }
+            Open::List => {
+                self.lists.pop();
+                self.todo_list = false;
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            Open::Item => {
+                self.newline();
+                self.prefixes.pop();
+            }
+            Open::Table => {
+                let rows = std::mem::take(&mut self.table);
+                let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+                for (i, row) in rows.iter().enumerate() {
+                    let mut cells: Vec<String> = row.iter().map(|c| c.trim().replace('|', "\\|")).collect();
+                    cells.resize(columns, String::new());
+                    self.write(&format!("| {} |", cells.join(" | ")));
+                    self.newline();
+                    if i == 0 {
+                        self.write(&format!("|{}", " --- |".repeat(columns)));
+                        self.newline();
+                    }
+                }
+                self.blank_line();
+            }
+            Open::Hidden => self.hidden -= 1,
+            Open::Row | Open::Cell | Open::Other => {}
+        }
+    }
+
+    fn finish_document(mut self) -> (String, Vec<String>) {
+        while let Some(open) = self.stack.pop() {
+            self.finish(open);
+        }
+        let mut out = self.out.trim().to_owned();
+        // Collapse the empty lines that nested blocks leave behind.
+        while out.contains("\n\n\n") {
+            out = out.replace("\n\n\n", "\n\n");
+        }
+        (out, self.warnings)
+    }
+}
+
+/// Convert Evernote's ENML, or other XHTML, to Markdown. `resources` maps the MD5 hash that
+/// `<en-media>` uses to the attachment holding that data.
+pub fn html_to_markdown(html: &str, resources: &HashMap<String, Attachment>) -> (String, Vec<String>) {
+    let mut reader = Reader::from_str(html);
+    let config = reader.config_mut();
+    config.check_end_names = false;
+    config.allow_unmatched_ends = true;
+    let mut markdown = Markdown::new(resources);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => markdown.start(&e, false),
+            Ok(Event::Empty(e)) => markdown.start(&e, true),
+            Ok(Event::End(_)) => markdown.end(),
+            Ok(Event::Text(t)) => markdown.text(&text_of(&t)),
+            Ok(Event::CData(t)) => markdown.text(&String::from_utf8_lossy(&t)),
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => {
+                markdown.warn(format!("Content after position {} couldn't be read: {e}", reader.buffer_position()));
+                break;
+            }
+        }
+    }
+    markdown.finish_document()
+}
+
+/// Evernote's compact timestamps, e.g. `20230102T100000Z`.
+fn parse_enex_time(value: &str) -> Option<u64> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ")
+        .ok()
+        .and_then(|dt| u64::try_from(dt.and_utc().timestamp()).ok())
+}
+
+/// File extension for a MIME type, for attachments that come without a file name.
+pub fn extension_for(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/bmp" => "bmp",
+        "application/pdf" => "pdf",
+        "audio/mpeg" => "mp3",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+#[derive(Default)]
+struct Resource {
+    data: String,
+    mime: String,
+    file_name: String,
+}
+
+#[derive(Default)]
+struct EnexNote {
+    title: String,
+    content: String,
+    created: String,
+    updated: String,
+    tags: Vec<String>,
+    source_url: String,
+    author: String,
+    reminder: String,
+    resources: Vec<Resource>,
+}
+
+impl EnexNote {
+    fn into_candidate(self, number: usize) -> Candidate {
+        let mut note = Note::new(0, if self.title.trim().is_empty() { "Untitled" } else { self.title.trim() });
+        let mut warnings = Vec::new();
+        if let Some(ts) = parse_enex_time(&self.created) {
+            note.created = ts;
+            note.edited = ts;
+        }
+        if let Some(ts) = parse_enex_time(&self.updated) {
+            note.edited = ts;
+        }
+        note.due = parse_enex_time(&self.reminder);
+        note.tags = Note::parse_tags(&self.tags.join(","));
+        if !self.source_url.is_empty() {
+            note.properties.insert("Source".to_owned(), PropertyValue::Text(self.source_url));
+        }
+        if !self.author.is_empty() {
+            note.properties.insert("Author".to_owned(), PropertyValue::Text(self.author));
+        }
+
+        let mut resources = HashMap::new();
+        let mut files = Vec::new();
+        for (i, resource) in self.resources.into_iter().enumerate() {
+            let cleaned: String = resource.data.chars().filter(|c| !c.is_whitespace()).collect();
+            let bytes = match base64::engine::general_purpose::STANDARD.decode(cleaned) {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    warnings.push(format!("Attachment {} couldn't be decoded", resource.file_name));
+                    continue;
+                }
+            };
+            let name = if resource.file_name.is_empty() {
+                format!("attachment-{}.{}", i + 1, extension_for(&resource.mime))
+            } else {
+                resource.file_name
+            };
+            let md5: String = Md5::digest(&bytes).iter().map(|b| format!("{b:02x}")).collect();
+            let attachment = Attachment {
+                name,
+                hash: attachments::hash_bytes(&bytes),
+                size: bytes.len() as u64,
+            };
+            resources.insert(md5, attachment.clone());
+            if !note.attachments.contains(&attachment) {
+                note.attachments.push(attachment.clone());
+                files.push((attachment, Blob::Bytes(bytes)));
+            }
+        }
+
+        let (content, content_warnings) = html_to_markdown(&self.content, &resources);
+        note.content = content;
+        warnings.extend(content_warnings);
+        Candidate {
+            source: format!("Note {number}"),
+            note,
+            files,
+            warnings,
+            include: true,
+            resolution: Resolution::Skip,
+        }
+    }
+}
+
+/// Read the notes of an Evernote `.enex` export.
+pub fn scan_enex(path: &Path) -> io::Result<Vec<Candidate>> {
+    let xml = fs::read_to_string(path)?;
+    let mut reader = Reader::from_str(&xml);
+    let mut candidates = Vec::new();
+    let mut note: Option<EnexNote> = None;
+    let mut resource: Option<Resource> = None;
+    // Element the text being read belongs to.
+    let mut element = String::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let text = match event {
+            Event::Start(e) => {
+                element = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                match element.as_str() {
+                    "note" => note = Some(EnexNote::default()),
+                    "resource" => resource = Some(Resource::default()),
+                    _ => {}
+                }
+                continue;
+            }
+            Event::End(e) => {
+                match e.local_name().as_ref() {
+                    b"note" => {
+                        if let Some(finished) = note.take() {
+                            candidates.push(finished.into_candidate(candidates.len() + 1));
+                        }
+                    }
+                    b"resource" => {
+                        if let (Some(n), Some(r)) = (note.as_mut(), resource.take()) {
+                            n.resources.push(r);
+                        }
+                    }
+                    _ => {}
+                }
+                element.clear();
+                continue;
+            }
+            Event::Text(t) => text_of(&t),
+            Event::CData(t) => String::from_utf8_lossy(&t).into_owned(),
+            Event::Eof => break,
+            _ => continue,
+        };
+        let Some(n) = note.as_mut() else {
+            continue;
+        };
+        match (element.as_str(), resource.as_mut()) {
+            ("data", Some(r)) => r.data.push_str(&text),
+            ("mime", Some(r)) => r.mime.push_str(&text),
+            ("file-name", Some(r)) => r.file_name.push_str(&text),
+            (_, Some(_)) => {}
+            ("title", None) => n.title.push_str(&text),
+            ("content", None) => n.content.push_str(&text),
+            ("created", None) => n.created.push_str(&text),
+            ("updated", None) => n.updated.push_str(&text),
+            ("tag", None) => n.tags.push(text),
+            ("source-url", None) => n.source_url.push_str(&text),
+            ("author", None) => n.author.push_str(&text),
+            ("reminder-time", None) => n.reminder.push_str(&text),
+            _ => {}
+        }
+    }
+    import::find_duplicates(&mut candidates);
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::Resolution;
+    use tempfile::TempDir;
+
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<en-export>
+<note><title></title><content><![CDATA[<en-note><div>first</div></en-note>]]></content><tag>home</tag></note>
+<note><title>  </title><content><![CDATA[<en-note><div>second</div></en-note>]]></content></note>
+<note><title>Plans</title><content><![CDATA[<en-note><div>third</div></en-note>]]></content></note>
+</en-export>"#;
+
+    #[test]
+    fn untitled_notes_are_numbered_with_a_warning() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("export.enex");
+        fs::write(&path, EXPORT).unwrap();
+        let candidates = scan_enex(&path).unwrap();
+        let titles: Vec<&str> = candidates.iter().map(|c| c.note.title.as_str()).collect();
+        assert_eq!(titles, vec!["Untitled", "Untitled (2)", "Plans"]);
+        assert!(candidates[0].warnings.is_empty());
+        assert_eq!(candidates[1].warnings, vec![import::renamed_warning("Untitled")]);
+        assert!(candidates[1].resolution == Resolution::KeepBoth);
+        assert!(candidates[0].resolution == Resolution::Skip);
+        assert_eq!(candidates[0].note.tags, vec!["home".to_owned()]);
+        assert!(candidates[1].note.content.contains("second"));
+    }
+}
@@ -0,0 +1,550 @@
+use crate::attachments;
+use crate::html;
+use crate::images;
+use crate::pdf;
+use crate::records::{self, RecordFormat};
+use note_core::links;
+use note_core::note::{rfc3339, Note};
+use crate::properties::PropertyValue;
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Folder that attachments are written to inside an export.
+const ATTACHMENTS_FOLDER: &str = "attachments";
+/// Property naming a note's notebook, e.g. "Work / Projects" from a Joplin import. Exports
+/// put the note in a matching folder.
+pub const NOTEBOOK_PROPERTY: &str = "Notebook";
+
+/// Which notes to export.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Scope {
+    Selected,
+    /// The notes currently shown in the sidebar.
+    Filtered,
+    /// Every note with a tag, e.g. to publish a handbook.
+    Tag,
+    All,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Markdown,
+    /// One page per note plus an index page, i.e. a static site.
+    Html,
+    /// A single document with each note starting on a new page.
+    Pdf,
+    /// One JSON object per note, per line, for scripts.
+    JsonLines,
+    Csv,
+}
+
+impl Format {
+    /// Formats written as one file rather than a folder or zip archive.
+    fn single_file(self) -> bool {
+        matches!(self, Format::Pdf | Format::JsonLines | Format::Csv)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Destination {
+    Folder,
+    Zip,
+}
+
+/// One file of an export, relative to the export root.
+pub struct ExportFile {
+    pub path: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Indices of the notes in `scope`, and how many were left out because they are locked.
+pub fn select(
+    notes: &[Note],
+    scope: Scope,
+    selected: Option<usize>,
+    visible: &[usize],
+    tag: &str,
+) -> (Vec<usize>, usize) {
+    let candidates: Vec<usize> = match scope {
+        Scope::Selected => selected.into_iter().collect(),
+        Scope::Filtered => visible.to_vec(),
+        Scope::Tag => (0..notes.len()).filter(|&i| notes[i].has_tag(tag)).collect(),
+        Scope::All => (0..notes.len()).collect(),
+    };
+    let (sealed, open): (Vec<usize>, Vec<usize>) = candidates.into_iter().partition(|&i| notes[i].is_sealed());
+    (open, sealed.len())
+}
+
+/// `text` with only characters that are safe in a file name on every platform. May be empty.
+fn safe_stem(text: &str) -> String {
+    let mut stem: String = text
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    while stem.contains("--") {
+        stem = stem.replace("--", "-");
+    }
+    stem.trim_matches('-').to_owned()
+}
+
+/// A file name for `title` that is safe on every platform and not in `taken`.
+pub fn unique_file_name(title: &str, extension: &str, taken: &mut HashSet<String>) -> String {
+    let stem = safe_stem(title);
+    let stem = if stem.is_empty() { "note" } else { stem.as_str() };
+    let mut name = format!("{stem}.{extension}");
+    let mut n = 2;
+    while !taken.insert(name.to_lowercase()) {
+        name = format!("{stem}-{n}.{extension}");
+        n += 1;
+    }
+    name
+}
+
+/// Folder of the note's notebook inside an export, e.g. `Work/Projects/`, or "" for none.
+pub fn notebook_folder(note: &Note) -> String {
+    match note.properties.get(NOTEBOOK_PROPERTY) {
+        Some(PropertyValue::Text(notebook) | PropertyValue::Select(notebook)) => notebook
+            .split('/')
+            .map(safe_stem)
+            .filter(|part| !part.is_empty())
+            .map(|part| format!("{part}/"))
+            .collect(),
+        _ => String::new(),
+    }
+}
+
+/// The way back to the export root from `folder`, e.g. `../../` for `Work/Projects/`.
+pub fn to_root(folder: &str) -> String {
+    "../".repeat(folder.matches('/').count())
+}
+
+/// File names for the attachments of `indices`, keyed by hash. Same-named files get a suffix.
+pub fn attachment_names(notes: &[Note], indices: &[usize]) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    let mut taken = HashSet::new();
+    for &i in indices {
+        for attachment in &notes[i].attachments {
+            if names.contains_key(&attachment.hash) {
+                continue;
+            }
+            let path = Path::new(&attachment.name);
+            let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let extension = path.extension().map(|e| e.to_string_lossy().into_owned()).unwrap_or_default();
+            let name = unique_file_name(&stem, &extension, &mut taken);
+            names.insert(attachment.hash.clone(), name.trim_end_matches('.').to_owned());
+        }
+    }
+    names
+}
+
+/// Attachment files for `names`, read from the store.
+pub fn attachment_files(names: &HashMap<String, String>) -> io::Result<Vec<ExportFile>> {
+    names
+        .iter()
+        .map(|(hash, name)| {
+            Ok(ExportFile {
+                path: format!("{ATTACHMENTS_FOLDER}/{name}"),
+                bytes: fs::read(attachments::blob_path(hash))?,
+            })
+        })
+        .collect()
+}
+
+/// Path an exported file uses to refer to the attachment with `hash`.
+pub fn attachment_path(names: &HashMap<String, String>, hash: &str) -> Option<String> {
+    names.get(hash).map(|name| format!("{ATTACHMENTS_FOLDER}/{name}"))
+}
+
+/// A YAML scalar for a string. JSON string syntax is valid YAML.
+fn yaml_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// YAML front matter with the note's timestamps and metadata. Attachment paths start with
+/// `root`, the way back to the export root.
+pub fn front_matter(note: &Note, attachment_names: &HashMap<String, String>, root: &str) -> String {
+    let mut out = String::from("---\n");
+    out.push_str(&format!("title: {}\n", yaml_string(&note.title)));
+    out.push_str(&format!("id: {}\n", note.id));
+    out.push_str(&format!("created: {}\n", rfc3339(note.created)));
+    out.push_str(&format!("edited: {}\n", rfc3339(note.edited)));
+    if !note.tags.is_empty() {
+        let tags: Vec<String> = note.tags.iter().map(|t| yaml_string(t)).collect();
+        out.push_str(&format!("tags: [{}]\n", tags.join(", ")));
+    }
+    if let Some(due) = note.due {
+        out.push_str(&format!("due: {}\n", rfc3339(due)));
+    }
+    if let Some(status) = &note.status {
+        out.push_str(&format!("status: {}\n", yaml_string(status)));
+    }
+    if !note.properties.is_empty() {
+        out.push_str("properties:\n");
+        for (name, value) in &note.properties {
+            let value = match value {
+                PropertyValue::Text(s) | PropertyValue::Select(s) => yaml_string(s),
+                PropertyValue::Number(n) => n.to_string(),
+                PropertyValue::Date(d) => d.format("%Y-%m-%d").to_string(),
+                PropertyValue::Checkbox(b) => b.to_string(),
+            };
+            out.push_str(&format!("  {}: {value}\n", yaml_string(name)));
+        }
+    }
+    if !note.attachments.is_empty() {
+        out.push_str("attachments:\n");
+        for attachment in &note.attachments {
+            let path = attachment_path(attachment_names, &attachment.hash)
+                .map_or_else(|| attachment.name.clone(), |path| format!("{root}{path}"));
+            out.push_str(&format!("  - {}\n", yaml_string(&path)));
+        }
+    }
+    out.push_str("---\n\n");
+    out
+}
+
+/// Note content as portable Markdown: links lose their IDs and images point into the export's
+/// attachments folder, by way of `root`.
+pub fn portable_content(content: &str, attachment_names: &HashMap<String, String>, root: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in links::parse_links(content) {
+        out.push_str(&content[last..link.range.start]);
+        out.push_str(&format!("[[{}]]", link.title));
+        last = link.range.end;
+    }
+    out.push_str(&content[last..]);
+
+    let content = out;
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for image in images::parse_images(&content) {
+        let Some(path) = images::attachment_hash(&image.target).and_then(|h| attachment_path(attachment_names, h)) else {
+            continue;
+        };
+        out.push_str(&content[last..image.range.start]);
+        out.push_str(&format!("![{}]({root}{path})", image.alt));
+        last = image.range.end;
+    }
+    out.push_str(&content[last..]);
+    out
+}
+
+/// Markdown files (and optionally attachments) for the notes at `indices`, in notebook folders.
+pub fn markdown_files(notes: &[Note], indices: &[usize], include_attachments: bool) -> io::Result<Vec<ExportFile>> {
+    // Without the files, references are left as they are rather than pointing at missing files.
+    let names = if include_attachments { attachment_names(notes, indices) } else { HashMap::new() };
+    let mut taken = HashSet::new();
+    let mut files = Vec::new();
+    for &i in indices {
+        let note = &notes[i];
+        let folder = notebook_folder(note);
+        let root = to_root(&folder);
+        let mut text = front_matter(note, &names, &root);
+        text.push_str(&portable_content(&note.content, &names, &root));
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        files.push(ExportFile {
+            path: format!("{folder}{}", unique_file_name(&note.title, "md", &mut taken)),
+            bytes: text.into_bytes(),
+        });
+    }
+    if include_attachments {
+        files.extend(attachment_files(&names)?);
+    }
+    Ok(files)
+}
+
+pub fn write_folder(dest: &Path, files: &[ExportFile]) -> io::Result<()> {
+    for file in files {
+        let path = dest.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &file.bytes)?;
+    }
+    Ok(())
+}
+
+fn create_parent(dest: &Path) -> io::Result<()> {
+    match dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(parent) => fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
+
+/// Write a single-file export, creating its folder if needed.
+pub fn write_file(dest: &Path, bytes: impl AsRef<[u8]>) -> io::Result<()> {
+    create_parent(dest)?;
+    fs::write(dest, bytes)
+}
+
+pub fn write_zip(dest: &Path, files: &[ExportFile]) -> io::Result<()> {
+    create_parent(dest)?;
+    let mut zip = zip::ZipWriter::new(File::create(dest)?);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for file in files {
+        zip.start_file(file.path.as_str(), options).map_err(io::Error::other)?;
+        zip.write_all(&file.bytes)?;
+    }
+    zip.finish().map_err(io::Error::other)?;
+    Ok(())
+}
+
+/// The export window.
+pub struct ExportDialog {
+    pub open: bool,
+    scope: Scope,
+    tag: String,
+    format: Format,
+    /// Dark or light page style for HTML.
+    dark: bool,
+    /// Heading of the HTML index page.
+    site_title: String,
+    destination: Destination,
+    include_attachments: bool,
+    path: String,
+    status: Option<Result<String, String>>,
+}
+
+impl Default for ExportDialog {
+    fn default() -> Self {
+        Self {
+            open: false,
+            scope: Scope::All,
+            tag: String::new(),
+            format: Format::Markdown,
+            dark: false,
+            site_title: "Notes".to_owned(),
+            destination: Destination::Folder,
+            include_attachments: true,
+            path: "export".to_owned(),
+            status: None,
+        }
+    }
+}
+
+impl ExportDialog {
+    fn export(&self, notes: &[Note], indices: &[usize]) -> io::Result<()> {
+        let dest = Path::new(self.path.trim());
+        if self.format == Format::Pdf {
+            let title = match indices {
+                [only] => notes[*only].title.as_str(),
+                _ => "Notes",
+            };
+            let bytes = pdf::pdf_document(notes, indices, title).map_err(io::Error::other)?;
+            return write_file(dest, bytes);
+        }
+        if let Some(format) = match self.format {
+            Format::JsonLines => Some(RecordFormat::JsonLines),
+            Format::Csv => Some(RecordFormat::Csv),
+            _ => None,
+        } {
+            return write_file(dest, records::export(notes, indices, format).map_err(io::Error::other)?);
+        }
+        let files = if self.format == Format::Html {
+            html::html_files(notes, indices, &self.site_title, self.dark, self.include_attachments)?
+        } else {
+            markdown_files(notes, indices, self.include_attachments)?
+        };
+        match self.destination {
+            Destination::Folder => write_folder(dest, &files),
+            Destination::Zip => write_zip(dest, &files),
+        }
+    }
+
+    /// Make the path end in `.pdf`, `.jsonl`, `.csv`, `.zip` or nothing to match the chosen output.
+    fn set_extension(&mut self) {
+        let mut stem = self.path.as_str();
+        for extension in [".pdf", ".jsonl", ".csv", ".zip"] {
+            stem = stem.trim_end_matches(extension);
+        }
+        let extension = match (self.format, self.destination) {
+            (Format::Pdf, _) => ".pdf",
+            (Format::JsonLines, _) => ".jsonl",
+            (Format::Csv, _) => ".csv",
+            (_, Destination::Zip) => ".zip",
+            (_, Destination::Folder) => "",
+        };
+        self.path = format!("{stem}{extension}");
+    }
+
+    /// Open the window with the page style following the app theme.
+    pub fn toggle(&mut self, dark_mode: bool) {
+        self.open = !self.open;
+        self.dark = dark_mode;
+    }
+
+    /// Draw the export window. `visible` are the notes shown in the sidebar.
+    pub fn show(&mut self, ctx: &egui::Context, notes: &[Note], selected: Option<usize>, visible: &[usize]) {
+        let mut open = self.open;
+        egui::Window::new("📤 Export")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("export_options").num_columns(2).show(ui, |ui| {
+                    ui.label("Notes");
+                    ui.horizontal(|ui| {
+                        ui.add_enabled_ui(selected.is_some(), |ui| {
+                            ui.radio_value(&mut self.scope, Scope::Selected, "Selected note");
+                        });
+                        ui.radio_value(&mut self.scope, Scope::Filtered, format!("Sidebar ({})", visible.len()));
+                        ui.radio_value(&mut self.scope, Scope::Tag, "Tag");
+                        ui.radio_value(&mut self.scope, Scope::All, format!("All ({})", notes.len()));
+                    });
+                    ui.end_row();
+                    if self.scope == Scope::Tag {
+                        ui.label("Tag");
+                        let mut tags: Vec<&String> = notes.iter().flat_map(|n| &n.tags).collect();
+                        tags.sort_by_key(|t| t.to_lowercase());
+                        tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+                        egui::ComboBox::from_id_source("export_tag")
+                            .selected_text(if self.tag.is_empty() { "Choose a tag" } else { self.tag.as_str() })
+                            .show_ui(ui, |ui| {
+                                for tag in tags {
+                                    ui.selectable_value(&mut self.tag, tag.clone(), format!("#{tag}"));
+                                }
+                            });
+                        ui.end_row();
+                    }
+                    ui.label("Format");
+                    ui.horizontal(|ui| {
+                        let before = self.format;
+                        ui.radio_value(&mut self.format, Format::Markdown, "Markdown");
+                        ui.radio_value(&mut self.format, Format::Html, "HTML site");
+                        ui.radio_value(&mut self.format, Format::Pdf, "PDF");
+                        ui.radio_value(&mut self.format, Format::JsonLines, "JSON Lines");
+                        ui.radio_value(&mut self.format, Format::Csv, "CSV");
+                        if self.format != before {
+                            self.set_extension();
+                        }
+                    });
+                    ui.end_row();
+                    if self.format == Format::Html {
+                        ui.label("Site title");
+                        ui.text_edit_singleline(&mut self.site_title);
+                        ui.end_row();
+                        ui.label("Style");
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut self.dark, false, "Light");
+                            ui.radio_value(&mut self.dark, true, "Dark");
+                        });
+                        ui.end_row();
+                    }
+                    if !self.format.single_file() {
+                        ui.label("Save as");
+                        ui.horizontal(|ui| {
+                            let before = self.destination;
+                            ui.radio_value(&mut self.destination, Destination::Folder, "Folder");
+                            ui.radio_value(&mut self.destination, Destination::Zip, "Zip archive");
+                            if self.destination != before {
+                                self.set_extension();
+                            }
+                        });
+                        ui.end_row();
+                    }
+                    ui.label("Path");
+                    ui.text_edit_singleline(&mut self.path);
+                    ui.end_row();
+                });
+                if !self.format.single_file() {
+                    ui.checkbox(&mut self.include_attachments, "Include attachments");
+                }
+                ui.label(
+                    egui::RichText::new(match self.format {
+                        Format::Markdown => "Each note becomes a Markdown file with its timestamps and metadata in YAML front matter.",
+                        Format::Html => "Each note becomes a self-contained page, linked from index.html. Open index.html to browse the site.",
+                        Format::Pdf => "One PDF with each note starting on a new page, its dates in the page header and images embedded.",
+                        Format::JsonLines | Format::Csv => {
+                            "One record per note with its ID, title, content, timestamps, tags, due date and status, \
+                             for scripts. The schema is described in the README."
+                        }
+                    })
+                    .size(12.0)
+                    .weak(),
+                );
+                ui.add_space(6.0);
+                let (indices, skipped) = select(notes, self.scope, selected, visible, &self.tag);
+                let empty = match self.scope {
+                    Scope::Selected if selected.is_none() => "No note is selected",
+                    _ if skipped > 0 => "Every note in scope is locked",
+                    _ => "No notes in scope",
+                };
+                if ui
+                    .add_enabled(!indices.is_empty() && !self.path.trim().is_empty(), egui::Button::new("📤 Export"))
+                    .on_disabled_hover_text(if indices.is_empty() { empty } else { "Enter a path" })
+                    .clicked()
+                {
+                    self.status = Some(match self.export(notes, &indices) {
+                        Ok(()) => {
+                            let mut message = format!("Exported {} note(s) to {}", indices.len(), self.path.trim());
+                            if skipped > 0 {
+                                message.push_str(&format!("; skipped {skipped} locked note(s)"));
+                            }
+                            Ok(message)
+                        }
+                        Err(e) => Err(format!("Export failed: {e}")),
+                    });
+                }
+                if indices.is_empty() {
+                    ui.label(egui::RichText::new(empty).size(13.0).weak());
+                }
+                match &self.status {
+                    Some(Ok(message)) => {
+                        ui.label(egui::RichText::new(message).size(13.0).weak());
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(egui::Color32::from_rgb(255, 80, 80), error);
+                    }
+                    None => {}
+                }
+            });
+        self.open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes() -> Vec<Note> {
+        let mut plans = Note::new(1, "Plans: 2025/26");
+        plans.tags = vec!["work".to_owned()];
+        plans.properties.insert(NOTEBOOK_PROPERTY.to_owned(), PropertyValue::Text("Work / Projects".to_owned()));
+        let mut locked = Note::new(2, "Diary");
+        locked.locked = true;
+        locked.tags = vec!["work".to_owned()];
+        vec![plans, locked, Note::new(3, "plans: 2025/26")]
+    }
+
+    #[test]
+    fn selected_scope_without_a_selection_exports_nothing() {
+        let notes = notes();
+        assert_eq!(select(&notes, Scope::Selected, None, &[], ""), (vec![], 0));
+        assert_eq!(select(&notes, Scope::Selected, Some(2), &[], ""), (vec![2], 0));
+        assert_eq!(select(&notes, Scope::Tag, None, &[], "Work"), (vec![0], 1));
+        assert_eq!(select(&notes, Scope::All, None, &[], ""), (vec![0, 2], 1));
+    }
+
+    #[test]
+    fn file_names_are_safe_and_unique() {
+        let mut taken = HashSet::new();
+        assert_eq!(unique_file_name("Plans: 2025/26", "md", &mut taken), "Plans-2025-26.md");
+        assert_eq!(unique_file_name("plans: 2025/26", "md", &mut taken), "plans-2025-26-2.md");
+        assert_eq!(unique_file_name("../..", "md", &mut taken), "note.md");
+    }
+
+    #[test]
+    fn notes_go_into_their_notebook_folder() {
+        let notes = notes();
+        assert_eq!(notebook_folder(&notes[0]), "Work/Projects/");
+        assert_eq!(to_root("Work/Projects/"), "../../");
+        let files = markdown_files(&notes, &[0, 2], false).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["Work/Projects/Plans-2025-26.md", "plans-2025-26-2.md"]);
+    }
+}
@@ -0,0 +1,343 @@
+use note_core::history::Command;
+use note_core::note::Note;
+use note_core::ops::NoteFields;
+use eframe::egui;
+use regex::{Captures, Regex, RegexBuilder};
+
+/// Options controlling how the search term is interpreted.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct FindOptions {
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+}
+
+/// Build the regex used for both matching and replacing.
+/// Plain text searches are escaped so they match literally.
+pub fn build_regex(pattern: &str, options: FindOptions) -> Result<Regex, regex::Error> {
+    let mut source = if options.regex {
+        pattern.to_owned()
+    } else {
+        regex::escape(pattern)
+    };
+    if options.whole_word {
+        source = format!(r"\b(?:{source})\b");
+    }
+    RegexBuilder::new(&source)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Field {
+    Title,
+    Content,
+}
+
+/// A single match, with the line it occurs on and what it would become.
+pub struct MatchPreview {
+    pub field: Field,
+    pub line_number: usize,
+    pub before: String,
+    pub matched: String,
+    pub replacement: String,
+    pub after: String,
+}
+
+/// All matches within one note.
+pub struct NoteMatches {
+    pub note_idx: usize,
+    pub title: String,
+    pub matches: Vec<MatchPreview>,
+}
+
+pub fn expand(caps: &Captures, replacement: &str, options: FindOptions) -> String {
+    if options.regex {
+        let mut out = String::new();
+        caps.expand(replacement, &mut out);
+        out
+    } else {
+        replacement.to_owned()
+    }
+}
+
+/// The matches that are shown and replaced. Empty ones, e.g. of `^` or `x*`, are neither.
+fn matches<'a>(re: &'a Regex, text: &'a str) -> impl Iterator<Item = Captures<'a>> + 'a {
+    re.captures_iter(text).filter(|caps| !caps[0].is_empty())
+}
+
+fn preview_field(
+    re: &Regex,
+    text: &str,
+    field: Field,
+    replacement: &str,
+    options: FindOptions,
+    out: &mut Vec<MatchPreview>,
+) {
+    for caps in matches(re, text) {
+        let m = caps.get(0).unwrap();
+        let line_start = text[..m.start()].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = text[m.end()..]
+            .find('\n')
+            .map(|i| m.end() + i)
+            .unwrap_or(text.len());
+        out.push(MatchPreview {
+            field,
+            line_number: text[..m.start()].matches('\n').count() + 1,
+            before: text[line_start..m.start()].to_owned(),
+            matched: m.as_str().to_owned(),
+            replacement: expand(&caps, replacement, options),
+            after: text[m.end()..line_end].to_owned(),
+        });
+    }
+}
+
+/// Collect every match across all notes, grouped by note.
+pub fn preview(notes: &[Note], re: &Regex, replacement: &str, options: FindOptions) -> Vec<NoteMatches> {
+    let mut result = Vec::new();
+    for (note_idx, note) in notes.iter().enumerate() {
+        let mut matches = Vec::new();
+        preview_field(re, &note.title, Field::Title, replacement, options, &mut matches);
+        preview_field(re, &note.content, Field::Content, replacement, options, &mut matches);
+        if !matches.is_empty() {
+            result.push(NoteMatches {
+                note_idx,
+                title: note.title.clone(),
+                matches,
+            });
+        }
+    }
+    result
+}
+
+/// Replace the same matches the preview lists.
+pub fn replace_text(re: &Regex, text: &str, replacement: &str, options: FindOptions) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in matches(re, text) {
+        let m = caps.get(0).unwrap();
+        out.push_str(&text[last..m.start()]);
+        out.push_str(&expand(&caps, replacement, options));
+        last = m.end();
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// The edits that replace every match in every note's title and content.
+pub fn edits(notes: &[Note], re: &Regex, replacement: &str, options: FindOptions) -> Vec<(usize, NoteFields)> {
+    let mut edits = Vec::new();
+    for (idx, note) in notes.iter().enumerate() {
+        let title = replace_text(re, &note.title, replacement, options);
+        let content = replace_text(re, &note.content, replacement, options);
+        if title != note.title || content != note.content {
+            edits.push((
+                idx,
+                NoteFields {
+                    title: (title != note.title).then_some(title),
+                    content: (content != note.content).then_some(content),
+                    ..Default::default()
+                },
+            ));
+        }
+    }
+    edits
+}
+
+/// State of the global find/replace window.
+#[derive(Default)]
+pub struct FindReplaceState {
+    pub open: bool,
+    pub find: String,
+    pub replace: String,
+    pub options: FindOptions,
+    pub error: Option<String>,
+    pub preview: Option<Vec<NoteMatches>>,
+}
+
+impl FindReplaceState {
+    /// Any change to the notes makes the preview stale.
+    pub fn invalidate_preview(&mut self) {
+        self.preview = None;
+    }
+
+    /// Draw the window. Returns the command for Replace All once it is clicked.
+    pub fn show(&mut self, ctx: &egui::Context, notes: &[Note]) -> Option<Command> {
+        if !self.open {
+            return None;
+        }
+        let mut command = None;
+        let mut open = self.open;
+        egui::Window::new("🔁 Find & Replace")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let mut inputs_changed = false;
+                egui::Grid::new("find_replace_grid").num_columns(2).show(ui, |ui| {
+                    ui.label("Find");
+                    inputs_changed |= ui
+                        .add(egui::TextEdit::singleline(&mut self.find).hint_text("Search term or pattern..."))
+                        .changed();
+                    ui.end_row();
+                    ui.label("Replace");
+                    inputs_changed |= ui
+                        .add(egui::TextEdit::singleline(&mut self.replace).hint_text(if self.options.regex {
+                            "Replacement ($1, ${name} for groups)..."
+                        } else {
+                            "Replacement..."
+                        }))
+                        .changed();
+                    ui.end_row();
+                });
+                ui.horizontal(|ui| {
+                    inputs_changed |= ui.checkbox(&mut self.options.regex, "Regex").changed();
+                    inputs_changed |= ui.checkbox(&mut self.options.case_sensitive, "Match case").changed();
+                    inputs_changed |= ui.checkbox(&mut self.options.whole_word, "Whole word").changed();
+                });
+                if inputs_changed {
+                    self.preview = None;
+                    self.error = None;
+                }
+
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("🔍 Preview").clicked() && !self.find.is_empty() {
+                        match build_regex(&self.find, self.options) {
+                            Ok(re) => {
+                                self.preview = Some(preview(notes, &re, &self.replace, self.options));
+                                self.error = None;
+                            }
+                            Err(e) => {
+                                self.preview = None;
+                                self.error = Some(e.to_string());
+                            }
+                        }
+                    }
+                    let can_apply = self.preview.as_ref().is_some_and(|p| !p.is_empty());
+                    if ui
+                        .add_enabled(can_apply, egui::Button::new("✅ Replace All"))
+                        .on_hover_text("Apply the previewed replacements to every listed note (Ctrl+Z undoes them)")
+                        .clicked()
+                    {
+                        if let Ok(re) = build_regex(&self.find, self.options) {
+                            let edits = edits(notes, &re, &self.replace, self.options);
+                            if !edits.is_empty() {
+                                command = Some(Command::EditMany(edits));
+                            }
+                        }
+                        self.preview = None;
+                    }
+                });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 80, 80), format!("Invalid pattern: {error}"));
+                }
+
+                if let Some(preview) = &self.preview {
+                    ui.separator();
+                    let total: usize = preview.iter().map(|n| n.matches.len()).sum();
+                    ui.label(
+                        egui::RichText::new(format!("{total} match(es) in {} note(s)", preview.len()))
+                            .strong(),
+                    );
+                    egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                        for note_matches in preview {
+                            egui::CollapsingHeader::new(&note_matches.title)
+                                .id_source(("find_replace_note", note_matches.note_idx))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    for m in &note_matches.matches {
+                                        ui.label(match_job(ui, m));
+                                    }
+                                });
+                        }
+                    });
+                }
+            });
+        self.open = open;
+        command
+    }
+}
+
+fn match_job(ui: &egui::Ui, m: &MatchPreview) -> egui::text::LayoutJob {
+    use egui::text::{LayoutJob, TextFormat};
+    let font = egui::FontId::proportional(14.0);
+    let normal = TextFormat::simple(font.clone(), ui.visuals().text_color());
+    let location = match m.field {
+        Field::Title => "title: ".to_owned(),
+        Field::Content => format!("line {}: ", m.line_number),
+    };
+    let mut job = LayoutJob::default();
+    job.append(&location, 0.0, TextFormat::simple(font.clone(), ui.visuals().weak_text_color()));
+    job.append(m.before.trim_start(), 0.0, normal.clone());
+    job.append(
+        &m.matched,
+        0.0,
+        TextFormat {
+            strikethrough: egui::Stroke::new(1.0, egui::Color32::from_rgb(255, 80, 80)),
+            background: egui::Color32::from_rgb(255, 80, 80).gamma_multiply(0.25),
+            ..normal.clone()
+        },
+    );
+    job.append(
+        &m.replacement,
+        0.0,
+        TextFormat {
+            background: egui::Color32::from_rgb(60, 155, 255).gamma_multiply(0.35),
+            ..normal.clone()
+        },
+    );
+    job.append(m.after.trim_end(), 0.0, normal);
+    job
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(title: &str, content: &str) -> Note {
+        let mut note = Note::new(1, title);
+        note.content = content.to_owned();
+        note
+    }
+
+    #[test]
+    fn empty_matches_are_neither_previewed_nor_replaced() {
+        let options = FindOptions { regex: true, ..Default::default() };
+        let re = build_regex("x*", options).unwrap();
+        let notes = vec![note("Plain", "axxb\nxc")];
+
+        let found = preview(&notes, &re, "-", options);
+        let matched: Vec<&str> = found[0].matches.iter().map(|m| m.matched.as_str()).collect();
+        assert_eq!(matched, vec!["xx", "x"]);
+        let changes = edits(&notes, &re, "-", options);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].1.content.as_deref(), Some("a-b\n-c"));
+        assert_eq!(changes[0].1.title, None);
+
+        let re = build_regex("^", options).unwrap();
+        assert!(preview(&notes, &re, "> ", options).is_empty());
+        assert!(edits(&notes, &re, "> ", options).is_empty());
+    }
+
+    #[test]
+    fn replacements_expand_groups_only_in_regex_mode() {
+        let regex = FindOptions { regex: true, ..Default::default() };
+        let re = build_regex(r"(\w+)@example", regex).unwrap();
+        assert_eq!(replace_text(&re, "mail bob@example now", "<$1>", regex), "mail <bob> now");
+
+        let plain = FindOptions::default();
+        let re = build_regex("a.b", plain).unwrap();
+        assert_eq!(replace_text(&re, "a.b axb A.B", "$1", plain), "$1 axb $1");
+    }
+
+    #[test]
+    fn whole_words_and_case_are_respected() {
+        let options = FindOptions { whole_word: true, case_sensitive: true, ..Default::default() };
+        let re = build_regex("cat", options).unwrap();
+        assert_eq!(replace_text(&re, "cat Cat concat cat.", "dog", options), "dog Cat concat dog.");
+        let found = preview(&[note("cat", "")], &re, "dog", options);
+        assert!(found[0].matches[0].field == Field::Title);
+    }
+}
@@ -0,0 +1,287 @@
+use note_core::links;
+use note_core::note::Note;
+use eframe::egui;
+use std::collections::{BTreeSet, HashMap};
+
+const NODE_RADIUS: f32 = 9.0;
+/// Ideal distance between connected nodes, in graph units.
+const SPRING_LENGTH: f32 = 90.0;
+/// Strength of the pull toward the origin that keeps the layout compact.
+const GRAVITY: f32 = 1.0;
+const STEPS_PER_FRAME: usize = 4;
+
+#[derive(Clone, Copy, PartialEq)]
+enum EdgeKind {
+    Link,
+    SharedTag,
+}
+
+struct Edge {
+    from: usize,
+    to: usize,
+    kind: EdgeKind,
+}
+
+/// State of the interactive note graph: layout positions, camera and filters.
+pub struct GraphView {
+    positions: HashMap<u64, egui::Vec2>,
+    /// How far nodes may still move per step; cooled down until the layout settles.
+    temperature: f32,
+    node_ids: Vec<u64>,
+    pan: egui::Vec2,
+    zoom: f32,
+    pub tag_filter: Option<String>,
+    pub show_tag_edges: bool,
+    pub highlight_orphans: bool,
+}
+
+impl Default for GraphView {
+    fn default() -> Self {
+        Self {
+            positions: HashMap::new(),
+            temperature: SPRING_LENGTH,
+            node_ids: Vec::new(),
+            pan: egui::Vec2::ZERO,
+            zoom: 1.0,
+            tag_filter: None,
+            show_tag_edges: true,
+            highlight_orphans: true,
+        }
+    }
+}
+
+/// Deterministic starting position on a sunflower spiral so the layout is stable between runs.
+fn initial_position(id: u64) -> egui::Vec2 {
+    let angle = id as f32 * 2.399_963;
+    let radius = SPRING_LENGTH * 0.6 * (id as f32).sqrt();
+    egui::vec2(angle.cos(), angle.sin()) * radius
+}
+
+fn build_edges(notes: &[Note], visible: &[usize], show_tag_edges: bool) -> Vec<Edge> {
+    let slot: HashMap<u64, usize> = visible
+        .iter()
+        .enumerate()
+        .map(|(slot, &idx)| (notes[idx].id, slot))
+        .collect();
+    let mut edges = Vec::new();
+    let mut seen = BTreeSet::new();
+    for (from, &idx) in visible.iter().enumerate() {
+        for target in links::outgoing(&notes[idx], notes) {
+            if let Some(&to) = slot.get(&target) {
+                if seen.insert((from.min(to), from.max(to))) {
+                    edges.push(Edge { from, to, kind: EdgeKind::Link });
+                }
+            }
+        }
+    }
+    if show_tag_edges {
+        for (from, &a) in visible.iter().enumerate() {
+            for (to, &b) in visible.iter().enumerate().skip(from + 1) {
+                let shares_tag = notes[a].tags.iter().any(|t| notes[b].has_tag(t));
+                if shares_tag && seen.insert((from, to)) {
+                    edges.push(Edge { from, to, kind: EdgeKind::SharedTag });
+                }
+            }
+        }
+    }
+    edges
+}
+
+impl GraphView {
+    /// Reset the layout so it is recomputed from scratch.
+    pub fn relayout(&mut self) {
+        self.positions.clear();
+        self.node_ids.clear();
+        self.temperature = SPRING_LENGTH;
+    }
+
+    /// One Fruchterman–Reingold step: all nodes repel, edges attract, and a pull
+    /// toward the origin keeps disconnected parts from drifting away.
+    fn step(&mut self, edges: &[Edge]) {
+        let ids = &self.node_ids;
+        let pos: Vec<egui::Vec2> = ids.iter().map(|id| self.positions[id]).collect();
+        let mut disp = vec![egui::Vec2::ZERO; pos.len()];
+        let k = SPRING_LENGTH;
+        for i in 0..pos.len() {
+            for j in (i + 1)..pos.len() {
+                let mut delta = pos[i] - pos[j];
+                if delta.length_sq() < 0.01 {
+                    delta = egui::vec2(0.1 * (i as f32 + 1.0), 0.1);
+                }
+                let dist = delta.length();
+                let force = delta / dist * (k * k / dist);
+                disp[i] += force;
+                disp[j] -= force;
+            }
+        }
+        for edge in edges {
+            let delta = pos[edge.from] - pos[edge.to];
+            let dist = delta.length().max(0.1);
+            let strength = if edge.kind == EdgeKind::Link { 1.0 } else { 0.5 };
+            let force = delta / dist * (dist * dist / k) * strength;
+            disp[edge.from] -= force;
+            disp[edge.to] += force;
+        }
+        for (i, id) in ids.iter().enumerate() {
+            let gravity = -pos[i] * GRAVITY;
+            let d = disp[i] + gravity;
+            let len = d.length();
+            if len > 0.0 {
+                let moved = d / len * len.min(self.temperature);
+                *self.positions.get_mut(id).unwrap() += moved;
+            }
+        }
+        self.temperature = (self.temperature * 0.97).max(0.0);
+    }
+
+    fn to_screen(&self, rect: egui::Rect, p: egui::Vec2) -> egui::Pos2 {
+        rect.center() + (p + self.pan) * self.zoom
+    }
+
+    fn controls(&mut self, ui: &mut egui::Ui, notes: &[Note]) {
+        let all_tags: BTreeSet<&str> = notes.iter().flat_map(|n| n.tags.iter().map(String::as_str)).collect();
+        ui.horizontal(|ui| {
+            let before = self.tag_filter.clone();
+            egui::ComboBox::from_label("Tag")
+                .selected_text(self.tag_filter.as_deref().unwrap_or("All notes"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.tag_filter, None, "All notes");
+                    for tag in &all_tags {
+                        ui.selectable_value(&mut self.tag_filter, Some((*tag).to_owned()), *tag);
+                    }
+                });
+            if before != self.tag_filter {
+                self.temperature = SPRING_LENGTH;
+            }
+            if ui.checkbox(&mut self.show_tag_edges, "Shared-tag edges").changed() {
+                self.temperature = SPRING_LENGTH;
+            }
+            ui.checkbox(&mut self.highlight_orphans, "Highlight orphans")
+                .on_hover_text("Notes with no links in or out are drawn in red");
+            if ui.button("⟲ Re-layout").clicked() {
+                self.relayout();
+            }
+            if ui.button("⛶ Reset view").clicked() {
+                self.pan = egui::Vec2::ZERO;
+                self.zoom = 1.0;
+            }
+        });
+    }
+
+    /// Draw the graph. Returns the index of a note the user clicked to open.
+    pub fn show(&mut self, ui: &mut egui::Ui, notes: &[Note]) -> Option<usize> {
+        self.controls(ui, notes);
+        ui.add_space(6.0);
+
+        let visible: Vec<usize> = (0..notes.len())
+            .filter(|&i| self.tag_filter.as_ref().is_none_or(|t| notes[i].has_tag(t)))
+            .collect();
+        let ids: Vec<u64> = visible.iter().map(|&i| notes[i].id).collect();
+        if ids != self.node_ids {
+            for &id in &ids {
+                self.positions.entry(id).or_insert_with(|| initial_position(id));
+            }
+            self.node_ids = ids;
+            self.temperature = self.temperature.max(SPRING_LENGTH * 0.5);
+        }
+        let edges = build_edges(notes, &visible, self.show_tag_edges);
+        // Orphans have no links in either direction, regardless of the current filter.
+        let mut linked = BTreeSet::new();
+        for note in notes {
+            let out = links::outgoing(note, notes);
+            if !out.is_empty() {
+                linked.insert(note.id);
+                linked.extend(out);
+            }
+        }
+
+        if self.temperature > 0.5 {
+            for _ in 0..STEPS_PER_FRAME {
+                self.step(&edges);
+            }
+            ui.ctx().request_repaint();
+        }
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::click_and_drag());
+        let rect = response.rect;
+        painter.rect_filled(rect, egui::Rounding::same(8.0), ui.visuals().extreme_bg_color);
+
+        if response.dragged() {
+            self.pan += response.drag_delta() / self.zoom;
+        }
+        if response.hovered() {
+            let (scroll, zoom_delta) = ui.input(|i| (i.raw_scroll_delta.y, i.zoom_delta()));
+            let factor = zoom_delta * (scroll * 0.002).exp();
+            if factor != 1.0 {
+                // Zoom around the pointer so the point under it stays put.
+                if let Some(pointer) = response.hover_pos() {
+                    let anchor = (pointer - rect.center()) / self.zoom - self.pan;
+                    self.zoom = (self.zoom * factor).clamp(0.1, 5.0);
+                    self.pan = (pointer - rect.center()) / self.zoom - anchor;
+                }
+            }
+        }
+
+        let painter = painter.with_clip_rect(rect);
+        let link_color = ui.visuals().text_color().gamma_multiply(0.5);
+        let tag_color = egui::Color32::from_rgb(60, 155, 255).gamma_multiply(0.35);
+        for edge in &edges {
+            let a = self.to_screen(rect, self.positions[&self.node_ids[edge.from]]);
+            let b = self.to_screen(rect, self.positions[&self.node_ids[edge.to]]);
+            match edge.kind {
+                EdgeKind::Link => {
+                    painter.line_segment([a, b], egui::Stroke::new(1.5, link_color));
+                }
+                EdgeKind::SharedTag => {
+                    painter.add(egui::Shape::dashed_line(&[a, b], egui::Stroke::new(1.0, tag_color), 6.0, 4.0));
+                }
+            }
+        }
+
+        let pointer = response.hover_pos();
+        let mut hovered = None;
+        for (slot, &idx) in visible.iter().enumerate() {
+            let center = self.to_screen(rect, self.positions[&self.node_ids[slot]]);
+            let radius = NODE_RADIUS * self.zoom.sqrt();
+            if pointer.is_some_and(|p| p.distance(center) <= radius + 3.0) {
+                hovered = Some(idx);
+            }
+            let orphan = self.highlight_orphans && !linked.contains(&notes[idx].id);
+            let fill = if hovered == Some(idx) {
+                egui::Color32::from_rgb(255, 170, 40)
+            } else if orphan {
+                egui::Color32::from_rgb(255, 80, 80)
+            } else {
+                egui::Color32::from_rgb(60, 155, 255)
+            };
+            painter.circle_filled(center, radius, fill);
+            if self.zoom >= 0.6 || hovered == Some(idx) {
+                painter.text(
+                    center + egui::vec2(0.0, radius + 4.0),
+                    egui::Align2::CENTER_TOP,
+                    &notes[idx].title,
+                    egui::FontId::proportional(13.0),
+                    ui.visuals().text_color(),
+                );
+            }
+        }
+
+        if visible.is_empty() {
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "No notes to show.",
+                egui::FontId::proportional(16.0),
+                ui.visuals().weak_text_color(),
+            );
+        }
+
+        if let Some(idx) = hovered {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+            if response.clicked() {
+                return Some(idx);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,245 @@
+use crate::attachments;
+use crate::export::{self, ExportFile};
+use crate::images;
+use note_core::links;
+use note_core::note::Note;
+use base64::Engine;
+use chrono::{TimeZone, Utc};
+use pulldown_cmark::{html, Event, Options, Parser};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const INDEX_FILE: &str = "index.html";
+
+/// Colours follow the app's light and dark themes.
+const LIGHT_CSS: &str = "
+:root { --bg: #ffffff; --panel: #eef1f5; --text: #1e1e1e; --weak: #6b7280; --accent: #3c9bff; }";
+const DARK_CSS: &str = "
+:root { --bg: #282c34; --panel: #20242a; --text: #dcdcdc; --weak: #9aa0a6; --accent: #3c9bff; }";
+const BASE_CSS: &str = "
+body { background: var(--bg); color: var(--text); font: 17px/1.6 system-ui, sans-serif; margin: 0; }
+nav { background: var(--panel); padding: 12px 24px; }
+main { max-width: 720px; margin: 0 auto; padding: 24px; }
+a { color: var(--accent); }
+h1 { margin-bottom: 4px; }
+.meta, .tags { color: var(--weak); font-size: 14px; margin: 2px 0; }
+.tags span { color: var(--accent); margin-right: 8px; }
+pre, code { background: var(--panel); border-radius: 4px; }
+pre { padding: 12px; overflow-x: auto; }
+code { padding: 1px 4px; }
+img { max-width: 100%; border-radius: 4px; }
+blockquote { border-left: 3px solid var(--accent); margin-left: 0; padding-left: 12px; color: var(--weak); }
+table { border-collapse: collapse; }
+td, th { border: 1px solid var(--weak); padding: 4px 8px; }
+ul.index { list-style: none; padding: 0; }
+ul.index li { padding: 6px 0; border-bottom: 1px solid var(--panel); }
+section { background: var(--panel); border-radius: 8px; padding: 8px 16px; margin-top: 24px; }
+";
+
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn format_date(ts: u64) -> String {
+    Utc.timestamp_opt(ts as i64, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_owned())
+}
+
+fn mime_type(name: &str) -> &'static str {
+    let extension = Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
+
+pub fn markdown_options() -> Options {
+    Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH
+}
+
+/// Markdown to HTML with tables, task lists and strikethrough. Raw HTML in a note is shown
+/// as text rather than passed through, so published pages can't run scripts from notes.
+pub fn render_markdown(markdown: &str) -> String {
+    let events = Parser::new_ext(markdown, markdown_options()).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        other => other,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Rewrite wiki links to page links from `root` (plain text if the target isn't exported) and
+/// embed attachment images as data URIs so each page stands alone.
+fn prepare_content(content: &str, notes: &[Note], pages: &HashMap<u64, String>, root: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in links::parse_links(content) {
+        out.push_str(&content[last..link.range.start]);
+        let target = links::resolve(&link, notes).and_then(|i| pages.get(&notes[i].id));
+        match target {
+            Some(page) => out.push_str(&format!("[{}]({root}{page})", link.title)),
+            None => out.push_str(&link.title),
+        }
+        last = link.range.end;
+    }
+    out.push_str(&content[last..]);
+
+    let content = out;
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for image in images::parse_images(&content) {
+        let Some(hash) = images::attachment_hash(&image.target) else {
+            continue;
+        };
+        let name = notes
+            .iter()
+            .flat_map(|n| &n.attachments)
+            .find(|a| a.hash == hash)
+            .map(|a| a.name.as_str())
+            .unwrap_or("");
+        let bytes = match fs::read(attachments::blob_path(hash)) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Error reading attachment {hash}: {e}");
+                continue;
+            }
+        };
+        out.push_str(&content[last..image.range.start]);
+        out.push_str(&format!(
+            "![{}](data:{};base64,{})",
+            image.alt,
+            mime_type(name),
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ));
+        last = image.range.end;
+    }
+    out.push_str(&content[last..]);
+    out
+}
+
+/// A whole HTML page. `nav` is the way back to the index, for a link to it.
+fn page(title: &str, dark: bool, nav: Option<&str>, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<style>{}{}</style>\n</head>\n<body>\n{}<main>\n{body}</main>\n</body>\n</html>\n",
+        escape(title),
+        if dark { DARK_CSS } else { LIGHT_CSS },
+        BASE_CSS,
+        nav.map(|root| format!("<nav><a href=\"{root}{INDEX_FILE}\">← All notes</a></nav>\n")).unwrap_or_default(),
+    )
+}
+
+/// A page for each note at `indices`, in notebook folders, plus an `index.html` listing them.
+pub fn html_files(
+    notes: &[Note],
+    indices: &[usize],
+    title: &str,
+    dark: bool,
+    include_attachments: bool,
+) -> io::Result<Vec<ExportFile>> {
+    let mut taken = HashSet::from([INDEX_FILE.to_owned()]);
+    let pages: HashMap<u64, String> = indices
+        .iter()
+        .map(|&i| {
+            let name = export::unique_file_name(&notes[i].title, "html", &mut taken);
+            (notes[i].id, format!("{}{name}", export::notebook_folder(&notes[i])))
+        })
+        .collect();
+    let names = export::attachment_names(notes, indices);
+    let mut files = Vec::new();
+
+    for &i in indices {
+        let note = &notes[i];
+        let root = export::to_root(&pages[&note.id]);
+        let mut body = format!("<article>\n<h1>{}</h1>\n", escape(&note.title));
+        body.push_str(&format!(
+            "<p class=\"meta\">Created {} · Edited {}</p>\n",
+            format_date(note.created),
+            format_date(note.edited)
+        ));
+        if !note.tags.is_empty() {
+            let tags: Vec<String> = note.tags.iter().map(|t| format!("<span>#{}</span>", escape(t))).collect();
+            body.push_str(&format!("<p class=\"tags\">{}</p>\n", tags.join("")));
+        }
+        body.push_str(&render_markdown(&prepare_content(&note.content, notes, &pages, &root)));
+        body.push_str("</article>\n");
+
+        let files_section: Vec<String> = note
+            .attachments
+            .iter()
+            .filter(|a| include_attachments && !images::is_image(&a.name))
+            .filter_map(|a| export::attachment_path(&names, &a.hash).map(|path| (a, path)))
+            .map(|(a, path)| format!("<li><a href=\"{}\">{}</a></li>", escape(&format!("{root}{path}")), escape(&a.name)))
+            .collect();
+        if !files_section.is_empty() {
+            body.push_str(&format!("<section>\n<h3>Attachments</h3>\n<ul>{}</ul>\n</section>\n", files_section.join("")));
+        }
+        let backlinks: Vec<String> = links::backlinks(notes, note.id)
+            .into_iter()
+            .filter_map(|source| pages.get(&notes[source].id).map(|page| (source, page)))
+            .map(|(source, page)| format!("<li><a href=\"{root}{page}\">{}</a></li>", escape(&notes[source].title)))
+            .collect();
+        if !backlinks.is_empty() {
+            body.push_str(&format!("<section>\n<h3>Linked from</h3>\n<ul>{}</ul>\n</section>\n", backlinks.join("")));
+        }
+        files.push(ExportFile {
+            path: pages[&note.id].clone(),
+            bytes: page(&note.title, dark, Some(&root), &body).into_bytes(),
+        });
+    }
+
+    let mut sorted = indices.to_vec();
+    sorted.sort_by_key(|&i| notes[i].title.to_lowercase());
+    let mut index = format!("<h1>{}</h1>\n<ul class=\"index\">\n", escape(title));
+    for i in sorted {
+        let note = &notes[i];
+        index.push_str(&format!(
+            "<li><a href=\"{}\">{}</a> <span class=\"meta\">{}{}</span></li>\n",
+            pages[&note.id],
+            escape(&note.title),
+            format_date(note.edited),
+            note.tags.iter().map(|t| format!(" #{}", escape(t))).collect::<String>(),
+        ));
+    }
+    index.push_str("</ul>\n");
+    files.push(ExportFile {
+        path: INDEX_FILE.to_owned(),
+        bytes: page(title, dark, None, &index).into_bytes(),
+    });
+
+    if include_attachments {
+        // Images are already embedded in the pages.
+        let others: HashMap<String, String> = names
+            .into_iter()
+            .filter(|(_, name)| !images::is_image(name))
+            .collect();
+        files.extend(export::attachment_files(&others)?);
+    }
+    Ok(files)
+}
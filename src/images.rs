@@ -0,0 +1,127 @@
+use crate::attachments::{self, Attachment, ATTACHMENTS_DIR};
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use regex::Regex;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Images referencing an attachment are written `![alt](attachment:<hash>)`.
+pub const ATTACHMENT_SCHEME: &str = "attachment:";
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico"];
+/// Images wider or taller than this are shown from a downscaled copy.
+const THUMBNAIL_SIZE: u32 = 1200;
+const THUMBNAILS_DIR: &str = "thumbnails";
+
+/// A Markdown image, `![alt](target)`.
+pub struct ImageRef {
+    pub range: Range<usize>,
+    pub alt: String,
+    pub target: String,
+}
+
+fn image_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"!\[([^\]\n]*)\]\(([^)\n]+)\)").unwrap())
+}
+
+/// Find every image in `line`, in order.
+pub fn parse_images(line: &str) -> Vec<ImageRef> {
+    image_regex()
+        .captures_iter(line)
+        .map(|caps| ImageRef {
+            range: caps.get(0).unwrap().range(),
+            alt: caps[1].to_owned(),
+            target: caps[2].trim().to_owned(),
+        })
+        .collect()
+}
+
+pub fn is_image(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Markdown that shows the attachment as an image.
+pub fn markdown_for(attachment: &Attachment) -> String {
+    format!("![{}]({ATTACHMENT_SCHEME}{})", attachment.name, attachment.hash)
+}
+
+/// The attachment hash an image target refers to, if it is an attachment reference.
+pub fn attachment_hash(target: &str) -> Option<&str> {
+    target.strip_prefix(ATTACHMENT_SCHEME)
+}
+
+pub fn thumbnail_path(hash: &str) -> PathBuf {
+    Path::new(ATTACHMENTS_DIR).join(THUMBNAILS_DIR).join(format!("{hash}.png"))
+}
+
+/// URI egui should load for an image target: the thumbnail of a large attachment,
+/// the attachment itself, or a local file path.
+pub fn uri_for(target: &str) -> String {
+    let path = match attachment_hash(target) {
+        Some(hash) => {
+            let thumbnail = thumbnail_path(hash);
+            if thumbnail.exists() {
+                thumbnail
+            } else {
+                attachments::blob_path(hash)
+            }
+        }
+        None => PathBuf::from(target.strip_prefix("file://").unwrap_or(target)),
+    };
+    let path = path.canonicalize().unwrap_or(path);
+    format!("file://{}", path.display())
+}
+
+/// Write a downscaled copy of a large image attachment. Small images and files that
+/// are not images are left alone.
+pub fn make_thumbnail(attachment: &Attachment) {
+    if !is_image(&attachment.name) {
+        return;
+    }
+    let path = thumbnail_path(&attachment.hash);
+    if path.exists() {
+        return;
+    }
+    // Blobs have no extension, so the format is sniffed from the content.
+    let image = match image::io::Reader::open(attachments::blob_path(&attachment.hash))
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(image::ImageError::IoError)
+        .and_then(|reader| reader.decode())
+    {
+        Ok(image) => image,
+        Err(e) => {
+            eprintln!("Error reading image {}: {e}", attachment.name);
+            return;
+        }
+    };
+    if image.width() <= THUMBNAIL_SIZE && image.height() <= THUMBNAIL_SIZE {
+        return;
+    }
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(image::ImageError::IoError)
+        .and_then(|_| thumbnail.save_with_format(&path, ImageFormat::Png));
+    if let Err(e) = result {
+        eprintln!("Error saving thumbnail for {}: {e}", attachment.name);
+    }
+}
+
+/// Store the image on the clipboard as a PNG attachment.
+pub fn paste_image() -> io::Result<Attachment> {
+    let mut clipboard = arboard::Clipboard::new().map_err(io::Error::other)?;
+    let data = clipboard.get_image().map_err(io::Error::other)?;
+    let rgba = RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned())
+        .ok_or_else(|| io::Error::other("clipboard image has an unexpected size"))?;
+    let mut png = Vec::new();
+    DynamicImage::ImageRgba8(rgba)
+        .write_to(&mut io::Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(io::Error::other)?;
+    let name = format!("pasted-{}.png", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    attachments::store_bytes(&name, &png)
+}
@@ -0,0 +1,1088 @@
+use crate::attachments::{self, Attachment};
+use crate::enex;
+use crate::html::format_date;
+use crate::images::{self, ATTACHMENT_SCHEME};
+use crate::joplin;
+use crate::keep;
+use note_core::history::Command;
+use note_core::links;
+use note_core::note::Note;
+use crate::properties::{Properties, PropertyValue};
+use crate::records::RecordsImport;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use eframe::egui;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+/// Front matter keys that map onto note fields rather than properties.
+const CREATED_KEYS: [&str; 4] = ["created", "date", "created_at", "creation date"];
+const EDITED_KEYS: [&str; 5] = ["edited", "updated", "modified", "updated_at", "last_modified"];
+const IGNORED_KEYS: [&str; 6] = ["id", "aliases", "alias", "attachments", "cssclasses", "cssclass"];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TitleSource {
+    FileName,
+    /// The first `# Heading`, which is then dropped from the content.
+    Heading,
+}
+
+/// What to do with an imported note whose title is already taken.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Skip,
+    /// Import it under a numbered title, e.g. "Ideas (2)".
+    KeepBoth,
+    /// Overwrite the existing note, keeping its ID so links to it still work.
+    Replace,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution::Skip, Resolution::KeepBoth, Resolution::Replace];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Skip => "Skip",
+            Resolution::KeepBoth => "Keep both",
+            Resolution::Replace => "Replace",
+        }
+    }
+}
+
+/// Where an imported attachment's bytes come from.
+pub enum Blob {
+    File(PathBuf),
+    /// Decoded from the export itself, e.g. base64 in an ENEX file.
+    Bytes(Vec<u8>),
+}
+
+/// A note read from a file, not yet added.
+pub struct Candidate {
+    /// Where it came from, shown in the preview.
+    pub source: String,
+    /// Content is final: links are `[[Title]]` and images refer to attachment hashes.
+    pub note: Note,
+    /// Files to copy into the attachment store when the note is imported.
+    pub files: Vec<(Attachment, Blob)>,
+    /// Anything that couldn't be converted faithfully, shown before and after importing.
+    pub warnings: Vec<String>,
+    pub include: bool,
+    /// What to do if a note has the same title. Notes can change while the preview is open,
+    /// so that note is looked up by title when drawn and again when imported.
+    pub resolution: Resolution,
+}
+
+#[derive(Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+    pub attachments: usize,
+}
+
+impl ImportSummary {
+    pub fn message(&self) -> String {
+        let mut message = format!("Imported {} note(s)", self.added);
+        if self.replaced > 0 {
+            message.push_str(&format!(", replaced {}", self.replaced));
+        }
+        if self.skipped > 0 {
+            message.push_str(&format!(", skipped {}", self.skipped));
+        }
+        if self.attachments > 0 {
+            message.push_str(&format!(", with {} attachment(s)", self.attachments));
+        }
+        message
+    }
+}
+
+/// A value in YAML front matter. Only the subset notes commonly use is understood.
+#[derive(Debug, PartialEq)]
+pub enum FrontValue {
+    Scalar(String),
+    List(Vec<String>),
+    Map(Vec<(String, String)>),
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.starts_with('"') {
+        if let Ok(s) = serde_json::from_str::<String>(value) {
+            return s;
+        }
+    }
+    match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        Some(inner) => inner.replace("''", "'"),
+        None => value.to_owned(),
+    }
+}
+
+/// Split a `[a, "b, c"]` flow list, respecting quotes.
+fn flow_list(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    for c in inner.chars() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (q, Some(open)) if q == open => quote = None,
+            (',', None) => {
+                items.push(unquote(&std::mem::take(&mut current)));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    items.push(unquote(&current));
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Split `text` into its front matter and the rest. Text without front matter has none.
+pub fn parse_front_matter(text: &str) -> (Vec<(String, FrontValue)>, &str) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return (Vec::new(), text);
+    };
+    let mut end = None;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if matches!(line.trim_end(), "---" | "...") {
+            end = Some((offset, offset + line.len()));
+            break;
+        }
+        offset += line.len();
+    }
+    let Some((yaml_end, body_start)) = end else {
+        return (Vec::new(), text);
+    };
+
+    let mut fields: Vec<(String, FrontValue)> = Vec::new();
+    for line in rest[..yaml_end].lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        let line = line.trim();
+        if indented {
+            // Continues the block list or map of the key above.
+            let Some((_, value)) = fields.last_mut() else {
+                continue;
+            };
+            if let Some(item) = line.strip_prefix("- ").or_else(|| (line == "-").then_some("")) {
+                match value {
+                    FrontValue::List(items) => items.push(unquote(item)),
+                    FrontValue::Scalar(s) if s.is_empty() => *value = FrontValue::List(vec![unquote(item)]),
+                    _ => {}
+                }
+            } else if let Some((key, item)) = line.split_once(':') {
+                match value {
+                    FrontValue::Map(entries) => entries.push((unquote(key), unquote(item))),
+                    FrontValue::Scalar(s) if s.is_empty() => {
+                        *value = FrontValue::Map(vec![(unquote(key), unquote(item))])
+                    }
+                    _ => {}
+                }
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            Some(inner) => FrontValue::List(flow_list(inner)),
+            None => FrontValue::Scalar(unquote(value)),
+        };
+        fields.push((unquote(key).to_lowercase(), value));
+    }
+    (fields, &rest[body_start..])
+}
+
+/// A timestamp in any of the usual front matter formats. Times without a zone are local.
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(ts) = value.parse::<u64>() {
+        return Some(ts);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return u64::try_from(dt.timestamp()).ok();
+    }
+    let naive = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .and_then(|dt| u64::try_from(dt.timestamp()).ok())
+}
+
+fn property_value(value: &str) -> PropertyValue {
+    if let Ok(n) = value.parse::<f64>() {
+        return PropertyValue::Number(n);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return PropertyValue::Date(date);
+    }
+    match value {
+        "true" => PropertyValue::Checkbox(true),
+        "false" => PropertyValue::Checkbox(false),
+        _ => PropertyValue::Text(value.to_owned()),
+    }
+}
+
+/// Fill in the note's fields from its front matter. Returns the title it names, if any.
+pub fn apply_front_matter(note: &mut Note, fields: Vec<(String, FrontValue)>) -> Option<String> {
+    let mut title = None;
+    let mut properties = Properties::new();
+    for (key, value) in fields {
+        let key_str = key.as_str();
+        match (key_str, value) {
+            ("title", FrontValue::Scalar(s)) if !s.is_empty() => title = Some(s),
+            ("tags" | "tag" | "keywords", FrontValue::List(items)) => {
+                note.tags = Note::parse_tags(&items.join(","));
+            }
+            // Obsidian also accepts a space-separated tag string.
+            ("tags" | "tag" | "keywords", FrontValue::Scalar(s)) => {
+                note.tags = Note::parse_tags(&s.replace(' ', ","));
+            }
+            (k, FrontValue::Scalar(s)) if CREATED_KEYS.contains(&k) => {
+                if let Some(ts) = parse_timestamp(&s) {
+                    note.created = ts;
+                }
+            }
+            (k, FrontValue::Scalar(s)) if EDITED_KEYS.contains(&k) => {
+                if let Some(ts) = parse_timestamp(&s) {
+                    note.edited = ts;
+                }
+            }
+            ("due", FrontValue::Scalar(s)) => note.due = parse_timestamp(&s),
+            ("status", FrontValue::Scalar(s)) if !s.is_empty() => note.status = Some(s),
+            ("properties", FrontValue::Map(entries)) => {
+                for (name, value) in entries {
+                    properties.insert(name, property_value(&value));
+                }
+            }
+            (k, _) if IGNORED_KEYS.contains(&k) => {}
+            (_, FrontValue::Scalar(s)) if !s.is_empty() => {
+                properties.insert(key, property_value(&s));
+            }
+            (_, FrontValue::List(items)) if !items.is_empty() => {
+                properties.insert(key, PropertyValue::Text(items.join(", ")));
+            }
+            _ => {}
+        }
+    }
+    // Explicit `properties:` entries win over loose keys of the same name.
+    for (name, value) in properties {
+        note.properties.entry(name).or_insert(value);
+    }
+    title
+}
+
+/// Created and modified times of a file, as Unix timestamps.
+fn file_times(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let secs = |t: std::time::SystemTime| t.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs());
+    let modified = metadata.modified().ok().and_then(secs)?;
+    let created = metadata.created().ok().and_then(secs).unwrap_or(modified);
+    Some((created.min(modified), modified))
+}
+
+fn decode_percent(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%' && i + 2 < bytes.len())
+            .then(|| std::str::from_utf8(&bytes[i + 1..i + 3]).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(b) = escaped {
+            out.push(b);
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn is_note_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e.to_lowercase().as_str(), "md" | "markdown" | "txt"))
+}
+
+fn lowercase_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+/// Every file under `dir`, skipping hidden files and folders such as `.obsidian` and `.trash`.
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        if path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')) {
+            continue;
+        }
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn wiki_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(!?)\[\[([^\[\]\n]+?)\]\]").unwrap())
+}
+
+fn markdown_link_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(!?)\[([^\]\n]*)\]\(([^)\n]+)\)").unwrap())
+}
+
+/// The files of a folder being imported, for resolving links and embeds between them.
+struct Vault {
+    /// Canonical path of the folder being imported.
+    root: PathBuf,
+    /// Lower-case file name to path, for Obsidian's shortest-path references.
+    by_name: HashMap<String, PathBuf>,
+    /// Canonical path of each note file to the title that note is imported under.
+    titles: HashMap<PathBuf, String>,
+}
+
+impl Vault {
+    /// Resolve a reference to a file, relative to `dir`, the vault root, or by name alone.
+    /// Returns the canonical path, and only for files inside the vault, so an absolute path or
+    /// `..` in an imported note can't attach files from elsewhere.
+    fn find(&self, dir: &Path, target: &str) -> Option<PathBuf> {
+        let target = target.trim().trim_start_matches("./");
+        if target.is_empty() || target.contains("://") || target.starts_with("data:") || target.starts_with('#') {
+            return None;
+        }
+        let inside = |path: PathBuf| {
+            let path = path.canonicalize().ok()?;
+            (path.is_file() && path.starts_with(&self.root)).then_some(path)
+        };
+        [dir.join(target), self.root.join(target)]
+            .into_iter()
+            .find_map(inside)
+            .or_else(|| self.by_name.get(&lowercase_name(Path::new(target))).cloned().and_then(inside))
+            .or_else(|| {
+                // Obsidian leaves out the `.md` of note references.
+                self.by_name.get(&format!("{}.md", target.to_lowercase())).cloned().and_then(inside)
+            })
+            .or_else(|| {
+                let name = Path::new(target).file_name()?.to_string_lossy().to_lowercase();
+                self.by_name.get(&format!("{name}.md")).cloned().and_then(inside)
+            })
+    }
+
+    fn title_for(&self, path: &Path) -> Option<&String> {
+        is_note_file(path).then(|| self.titles.get(path)).flatten()
+    }
+
+    /// Hash the file and add it to the note, unless it is attached already.
+    fn attach(&self, path: &Path, note: &mut Note, files: &mut Vec<(Attachment, Blob)>) -> Option<Attachment> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("Error reading {}: {e}", path.display());
+                return None;
+            }
+        };
+        let name = path.file_name()?.to_string_lossy().into_owned();
+        let attachment = Attachment {
+            hash: attachments::hash_bytes(&bytes),
+            size: bytes.len() as u64,
+            name,
+        };
+        if !note.attachments.iter().any(|a| a.hash == attachment.hash) {
+            note.attachments.push(attachment.clone());
+            files.push((attachment.clone(), Blob::File(path.to_owned())));
+        }
+        Some(attachment)
+    }
+
+    /// Rewrite Obsidian links and embeds, and Markdown links to local files, into this app's
+    /// `[[Title]]` links and attachment images.
+    fn convert(&self, content: &str, dir: &Path, note: &mut Note, files: &mut Vec<(Attachment, Blob)>) -> String {
+        let mut out = String::with_capacity(content.len());
+        let mut last = 0;
+        for caps in wiki_regex().captures_iter(content) {
+            let whole = caps.get(0).unwrap();
+            let embed = !caps[1].is_empty();
+            // `[[Target#Heading|Alias]]`: only the target matters here.
+            let inner = &caps[2];
+            let target = inner.split(['|', '#', '^']).next().unwrap_or("").trim();
+            let replacement = match self.find(dir, target) {
+                Some(path) => match self.title_for(&path) {
+                    Some(title) => Some(format!("[[{title}]]")),
+                    None => self.attach(&path, note, files).map(|a| {
+                        if embed && images::is_image(&a.name) {
+                            images::markdown_for(&a)
+                        } else {
+                            a.name.clone()
+                        }
+                    }),
+                },
+                // A link to a note that doesn't exist yet stays a link.
+                None if !embed => Some(format!("[[{}]]", target.rsplit('/').next().unwrap_or(target))),
+                None => None,
+            };
+            if let Some(replacement) = replacement {
+                out.push_str(&content[last..whole.start()]);
+                out.push_str(&replacement);
+                last = whole.end();
+            }
+        }
+        out.push_str(&content[last..]);
+
+        let content = out;
+        let mut out = String::with_capacity(content.len());
+        let mut last = 0;
+        for caps in markdown_link_regex().captures_iter(&content) {
+            let whole = caps.get(0).unwrap();
+            let image = !caps[1].is_empty();
+            let text = &caps[2];
+            // `<path with spaces> "title"`
+            let target = caps[3].trim();
+            let target = match target.strip_prefix('<') {
+                Some(rest) => rest.split('>').next().unwrap_or(""),
+                None => target.split(" \"").next().unwrap_or(""),
+            };
+            let Some(path) = self.find(dir, &decode_percent(target)) else {
+                continue;
+            };
+            let replacement = match self.title_for(&path) {
+                Some(title) => Some(format!("[[{title}]]")),
+                None => self.attach(&path, note, files).map(|a| {
+                    if image && images::is_image(&a.name) {
+                        format!("![{text}]({ATTACHMENT_SCHEME}{})", a.hash)
+                    } else if text.is_empty() {
+                        a.name.clone()
+                    } else {
+                        text.to_owned()
+                    }
+                }),
+            };
+            if let Some(replacement) = replacement {
+                out.push_str(&content[last..whole.start()]);
+                out.push_str(&replacement);
+                last = whole.end();
+            }
+        }
+        out.push_str(&content[last..]);
+        out
+    }
+}
+
+/// Read every `.md`/`.txt` file under `root` as a candidate note.
+pub fn scan_folder(root: &Path, title_source: TitleSource) -> io::Result<Vec<Candidate>> {
+    let mut paths = Vec::new();
+    walk(root, &mut paths)?;
+    let mut vault = Vault {
+        root: root.canonicalize()?,
+        by_name: HashMap::new(),
+        titles: HashMap::new(),
+    };
+    for path in &paths {
+        vault.by_name.entry(lowercase_name(path)).or_insert_with(|| path.clone());
+    }
+
+    // Titles first, so links between the files can be rewritten to them.
+    let mut parsed = Vec::new();
+    let mut taken = HashSet::new();
+    for path in paths.iter().filter(|p| is_note_file(p)) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("Error reading {}: {e}", path.display());
+                continue;
+            }
+        };
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let (fields, body) = parse_front_matter(&text);
+        let mut note = Note::new(0, &stem);
+        if let Some((created, edited)) = file_times(path) {
+            note.created = created;
+            note.edited = edited;
+        }
+        let front_title = apply_front_matter(&mut note, fields);
+        let mut body = body.trim_start_matches(['\n', '\r']).to_owned();
+        if title_source == TitleSource::Heading {
+            let first = body.lines().next().unwrap_or("");
+            if let Some(heading) = first.strip_prefix("# ").map(str::trim).filter(|h| !h.is_empty()) {
+                if front_title.is_none() {
+                    note.title = heading.to_owned();
+                }
+                body = body[first.len()..].trim_start_matches(['\n', '\r']).to_owned();
+            }
+        }
+        if let Some(title) = front_title {
+            note.title = title;
+        }
+        let mut warnings = Vec::new();
+        let title = claim_title(&note.title, &mut taken);
+        if title != note.title {
+            warnings.push(renamed_warning(&note.title));
+            note.title = title;
+        }
+        if let Ok(canonical) = path.canonicalize() {
+            vault.titles.insert(canonical, note.title.clone());
+        }
+        parsed.push((path, note, body, warnings));
+    }
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for (path, mut note, body, warnings) in parsed {
+        let mut files = Vec::new();
+        let dir = path.parent().unwrap_or(root);
+        note.content = vault.convert(&body, dir, &mut note, &mut files);
+        let source = path.strip_prefix(root).unwrap_or(path).display().to_string();
+        let resolution = if warnings.is_empty() { Resolution::Skip } else { Resolution::KeepBoth };
+        candidates.push(Candidate {
+            source,
+            note,
+            files,
+            warnings,
+            include: true,
+            resolution,
+        });
+    }
+    Ok(candidates)
+}
+
+/// `title`, or `title (2)`, `title (3)`... whichever isn't in `taken` yet, which then holds it.
+/// Keeps the titles of one import apart, so links between its notes stay unambiguous.
+pub fn claim_title(title: &str, taken: &mut HashSet<String>) -> String {
+    let mut n = 2;
+    let mut candidate = title.to_owned();
+    while !taken.insert(candidate.trim().to_lowercase()) {
+        candidate = format!("{title} ({n})");
+        n += 1;
+    }
+    candidate
+}
+
+/// The warning for a note renamed by `claim_title`.
+pub fn renamed_warning(original: &str) -> String {
+    format!("Renamed from \"{original}\": another note in this import has that title")
+}
+
+/// Give candidates that repeat an earlier title a numbered one, with a warning, and keep them
+/// alongside an existing note of the same title by default. Notes selected for import claim
+/// their titles first.
+pub fn find_duplicates(candidates: &mut [Candidate]) {
+    let mut taken = HashSet::new();
+    for include in [true, false] {
+        for candidate in candidates.iter_mut().filter(|c| c.include == include) {
+            let title = claim_title(&candidate.note.title, &mut taken);
+            if title != candidate.note.title {
+                candidate.warnings.push(renamed_warning(&candidate.note.title));
+                candidate.note.title = title;
+                candidate.resolution = Resolution::KeepBoth;
+            }
+        }
+    }
+}
+
+/// `title`, or `title (2)`, `title (3)`... whichever no note has yet.
+fn unique_title(title: &str, notes: &[Note]) -> String {
+    let mut n = 2;
+    let mut candidate = title.to_owned();
+    while links::find_by_title(notes, &candidate).is_some() {
+        candidate = format!("{title} ({n})");
+        n += 1;
+    }
+    candidate
+}
+
+/// Add the included candidates to `notes`, storing their attachments and binding their links.
+pub fn apply(candidates: Vec<Candidate>, notes: &mut Vec<Note>) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    // Original title of each imported note to its index, so links between them bind to each
+    // other even when one was renamed to avoid a conflict.
+    let mut imported: Vec<(String, usize)> = Vec::new();
+    for candidate in candidates {
+        let Candidate { mut note, files, include, resolution, .. } = candidate;
+        if !include {
+            summary.skipped += 1;
+            continue;
+        }
+        let original_title = note.title.clone();
+        let existing = links::find_by_title(notes, &note.title);
+        // A note of this batch is never skipped for or overwritten by another, and locked notes
+        // can't be overwritten without their passphrase.
+        let resolution = match existing {
+            Some(i) if imported.iter().any(|&(_, j)| j == i) => Resolution::KeepBoth,
+            Some(i) if resolution == Resolution::Replace && notes[i].locked => Resolution::KeepBoth,
+            _ => resolution,
+        };
+        let target = match (existing, resolution) {
+            (Some(_), Resolution::Skip) => {
+                summary.skipped += 1;
+                continue;
+            }
+            (Some(i), Resolution::Replace) => Some(i),
+            (Some(_), Resolution::KeepBoth) => {
+                note.title = unique_title(&note.title, notes);
+                None
+            }
+            (None, _) => None,
+        };
+        for (attachment, blob) in &files {
+            let stored = match blob {
+                Blob::File(path) => attachments::store_file(path),
+                Blob::Bytes(bytes) => attachments::store_bytes(&attachment.name, bytes),
+            };
+            match stored {
+                Ok(_) => {
+                    images::make_thumbnail(attachment);
+                    summary.attachments += 1;
+                }
+                Err(e) => eprintln!("Error importing {}: {e}", attachment.name),
+            }
+        }
+        let idx = match target {
+            Some(i) => {
+                note.id = notes[i].id;
+                notes[i] = note;
+                summary.replaced += 1;
+                i
+            }
+            None => {
+                note.id = Note::next_id(notes);
+                notes.push(note);
+                summary.added += 1;
+                notes.len() - 1
+            }
+        };
+        imported.push((original_title.to_lowercase(), idx));
+    }
+
+    for &(_, idx) in &imported {
+        let content = &notes[idx].content;
+        let mut out = String::with_capacity(content.len());
+        let mut last = 0;
+        for link in links::parse_links(content) {
+            if link.id.is_some() {
+                continue;
+            }
+            let title = link.title.to_lowercase();
+            let target = imported
+                .iter()
+                .find(|(t, _)| *t == title)
+                .map(|&(_, i)| i)
+                .or_else(|| links::find_by_title(notes, &link.title));
+            if let Some(target) = target {
+                out.push_str(&content[last..link.range.start]);
+                out.push_str(&links::link_to(&notes[target]));
+                last = link.range.end;
+            }
+        }
+        out.push_str(&content[last..]);
+        notes[idx].content = out;
+    }
+    summary
+}
+
+/// The notes in `after` that are new or differ from those in `notes`, to import as one command.
+fn changed(notes: &[Note], after: Vec<Note>) -> Vec<Note> {
+    after
+        .into_iter()
+        .filter(|note| notes.iter().find(|n| n.id == note.id) != Some(note))
+        .collect()
+}
+
+/// Preview table shared by the importers: what will be imported and how conflicts are handled.
+pub fn preview(ui: &mut egui::Ui, candidates: &mut [Candidate], notes: &[Note]) {
+    // The header and the rows look conflicts up the same way `apply` does.
+    let existing: Vec<Option<usize>> = candidates.iter().map(|c| links::find_by_title(notes, &c.note.title)).collect();
+    let conflicts = existing.iter().filter(|e| e.is_some()).count();
+    let warned = candidates.iter().filter(|c| !c.warnings.is_empty()).count();
+    ui.horizontal(|ui| {
+        ui.label(format!("{} note(s) found", candidates.len()));
+        if warned > 0 {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠ {warned} with warnings"));
+        }
+        if conflicts > 0 {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("{conflicts} with an existing title"));
+            ui.label("·  For all:");
+            for resolution in Resolution::ALL {
+                if ui.small_button(resolution.label()).clicked() {
+                    for (candidate, existing) in candidates.iter_mut().zip(&existing) {
+                        if existing.is_some_and(|i| resolution != Resolution::Replace || !notes[i].locked) {
+                            candidate.resolution = resolution;
+                        }
+                    }
+                }
+            }
+        }
+    });
+    egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+        egui::Grid::new("import_preview").num_columns(6).striped(true).show(ui, |ui| {
+            ui.strong("");
+            ui.strong("");
+            ui.strong("Title");
+            ui.strong("From");
+            ui.strong("Edited");
+            ui.strong("On conflict");
+            ui.end_row();
+            for (i, candidate) in candidates.iter_mut().enumerate() {
+                ui.checkbox(&mut candidate.include, "");
+                if candidate.warnings.is_empty() {
+                    ui.label("");
+                } else {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠ {}", candidate.warnings.len()))
+                        .on_hover_text(candidate.warnings.join("\n"));
+                }
+                let mut title = egui::RichText::new(&candidate.note.title);
+                if !candidate.include {
+                    title = title.weak();
+                }
+                let mut details = String::new();
+                if !candidate.note.tags.is_empty() {
+                    details.push_str(&candidate.note.tags.iter().map(|t| format!("#{t}")).collect::<Vec<_>>().join(" "));
+                }
+                if !candidate.note.attachments.is_empty() {
+                    details.push_str(&format!("  📎 {}", candidate.note.attachments.len()));
+                }
+                let response = ui.label(title);
+                if !details.is_empty() {
+                    response.on_hover_text(details.trim());
+                }
+                ui.label(egui::RichText::new(&candidate.source).size(12.0).weak());
+                ui.label(egui::RichText::new(format_date(candidate.note.edited)).size(12.0));
+                match existing[i] {
+                    Some(existing) => {
+                        egui::ComboBox::from_id_source(("import_resolution", i))
+                            .selected_text(candidate.resolution.label())
+                            .show_ui(ui, |ui| {
+                                for resolution in Resolution::ALL {
+                                    let enabled = resolution != Resolution::Replace || !notes[existing].locked;
+                                    ui.add_enabled_ui(enabled, |ui| {
+                                        ui.selectable_value(&mut candidate.resolution, resolution, resolution.label());
+                                    });
+                                }
+                            });
+                    }
+                    None => {
+                        ui.label(egui::RichText::new("New").size(12.0).weak());
+                    }
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// The kinds of export the import window reads.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Source {
+    Markdown,
+    Evernote,
+    Joplin,
+    Keep,
+    /// JSON Lines or CSV in this app's own schema, e.g. written by a script.
+    Records,
+}
+
+impl Source {
+    pub const ALL: [Source; 5] = [Source::Markdown, Source::Evernote, Source::Joplin, Source::Keep, Source::Records];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Markdown => "Markdown folder",
+            Source::Evernote => "Evernote",
+            Source::Joplin => "Joplin",
+            Source::Keep => "Google Keep",
+            Source::Records => "JSON Lines / CSV",
+        }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            Source::Markdown => "Path to folder...",
+            Source::Evernote => "Path to .enex file...",
+            Source::Joplin => "Path to .jex file or RAW export folder...",
+            Source::Keep => "Path to the Takeout Keep folder...",
+            Source::Records => "Path to .jsonl or .csv file...",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Source::Markdown => {
+                "Front matter sets the title, dates, tags and properties; file times are used otherwise. \
+                 [[Links]] and embedded files are converted."
+            }
+            Source::Evernote => {
+                "Notes are converted from Evernote's HTML to Markdown, keeping dates, tags, checklists, \
+                 tables and attachments. Export a notebook from Evernote as an .enex file."
+            }
+            Source::Joplin => {
+                "Keeps dates, tags, to-dos and attachments; the notebook becomes a property. \
+                 Export from Joplin as JEX, or as RAW to a folder."
+            }
+            Source::Keep => {
+                "Keeps labels, checklists, dates and attached images. Trashed notes are listed but not selected."
+            }
+            Source::Records => {
+                "Records with id, title, content, created, edited, tags, due and status, as exported. \
+                 Every line is checked first; if any is invalid, nothing is imported."
+            }
+        }
+    }
+}
+
+/// The import window for Markdown folders, Obsidian vaults and other apps' exports.
+pub struct ImportDialog {
+    pub open: bool,
+    source: Source,
+    path: String,
+    title_source: TitleSource,
+    candidates: Vec<Candidate>,
+    status: Option<Result<String, String>>,
+    /// Warnings of the last import, by note title.
+    report: Vec<(String, Vec<String>)>,
+    records: RecordsImport,
+}
+
+impl Default for ImportDialog {
+    fn default() -> Self {
+        Self {
+            open: false,
+            source: Source::Markdown,
+            path: String::new(),
+            title_source: TitleSource::FileName,
+            candidates: Vec::new(),
+            status: None,
+            report: Vec::new(),
+            records: RecordsImport::default(),
+        }
+    }
+}
+
+impl ImportDialog {
+    fn scan(&mut self, notes: &[Note]) {
+        self.candidates.clear();
+        self.report.clear();
+        let path = Path::new(self.path.trim());
+        let scanned = match self.source {
+            Source::Markdown => scan_folder(path, self.title_source),
+            Source::Evernote => enex::scan_enex(path),
+            Source::Joplin => joplin::scan_joplin(path),
+            Source::Keep => keep::scan_keep(path),
+            Source::Records => {
+                self.status = self.records.scan(path, notes).err().map(Err);
+                return;
+            }
+        };
+        self.status = match scanned {
+            Ok(candidates) if candidates.is_empty() => Some(Err("No notes found".to_owned())),
+            Ok(candidates) => {
+                self.candidates = candidates;
+                None
+            }
+            Err(e) => Some(Err(format!("Can't read {}: {e}", path.display()))),
+        };
+    }
+
+    /// Draw the import window. Returns the command adding and replacing notes once Import is
+    /// clicked; it is worked out on a copy of `notes`.
+    pub fn show(&mut self, ctx: &egui::Context, notes: &[Note]) -> Option<Command> {
+        let mut command = None;
+        let mut open = self.open;
+        egui::Window::new("📥 Import")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("From");
+                    let before = self.source;
+                    for source in Source::ALL {
+                        ui.selectable_value(&mut self.source, source, source.label());
+                    }
+                    if self.source != before {
+                        self.candidates.clear();
+                        self.records.clear();
+                        self.status = None;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Path");
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.path).hint_text(self.source.hint()));
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("🔍 Scan").clicked() || submitted) && !self.path.trim().is_empty() {
+                        self.scan(notes);
+                    }
+                });
+                if self.source == Source::Markdown {
+                    ui.horizontal(|ui| {
+                        ui.label("Title from");
+                        let before = self.title_source;
+                        ui.radio_value(&mut self.title_source, TitleSource::FileName, "File name");
+                        ui.radio_value(&mut self.title_source, TitleSource::Heading, "First heading");
+                        if self.title_source != before && !self.candidates.is_empty() {
+                            self.scan(notes);
+                        }
+                    });
+                }
+                if self.source == Source::Records {
+                    self.records.options(ui, notes);
+                }
+                ui.label(egui::RichText::new(self.source.description()).size(12.0).weak());
+
+                if self.source == Source::Records && self.records.is_scanned() {
+                    ui.separator();
+                    if let Some((message, merged)) = self.records.preview(ui, notes) {
+                        self.status = Some(Ok(message));
+                        command = Some(Command::Import(changed(notes, merged)));
+                    }
+                }
+
+                if !self.candidates.is_empty() {
+                    ui.separator();
+                    preview(ui, &mut self.candidates, notes);
+                    ui.add_space(6.0);
+                    let count = self.candidates.iter().filter(|c| c.include).count();
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(count > 0, egui::Button::new(format!("📥 Import {count} note(s)")))
+                            .clicked()
+                        {
+                            let candidates = std::mem::take(&mut self.candidates);
+                            self.report = candidates
+                                .iter()
+                                .filter(|c| c.include && !c.warnings.is_empty())
+                                .map(|c| (c.note.title.clone(), c.warnings.clone()))
+                                .collect();
+                            let mut after = notes.to_vec();
+                            let summary = apply(candidates, &mut after);
+                            self.status = Some(Ok(summary.message()));
+                            command = Some(Command::Import(changed(notes, after)));
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.candidates.clear();
+                        }
+                    });
+                }
+                match &self.status {
+                    Some(Ok(message)) => {
+                        ui.label(egui::RichText::new(message).size(13.0).weak());
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(egui::Color32::from_rgb(255, 80, 80), error);
+                    }
+                    None => {}
+                }
+                if !self.report.is_empty() {
+                    egui::CollapsingHeader::new(format!("⚠ {} note(s) imported with warnings", self.report.len()))
+                        .id_source("import_report")
+                        .show(ui, |ui| {
+                            egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                for (title, warnings) in &self.report {
+                                    for warning in warnings {
+                                        ui.label(egui::RichText::new(format!("{title}: {warning}")).size(12.0));
+                                    }
+                                }
+                            });
+                        });
+                }
+            });
+        self.open = open;
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn candidate(title: &str, resolution: Resolution) -> Candidate {
+        Candidate {
+            source: String::new(),
+            note: Note::new(0, title),
+            files: Vec::new(),
+            warnings: Vec::new(),
+            include: true,
+            resolution,
+        }
+    }
+
+    #[test]
+    fn repeated_titles_are_numbered_and_kept_apart_from_existing_notes() {
+        let mut notes = vec![Note::new(1, "Ideas")];
+        let mut candidates = vec![candidate("Ideas", Resolution::Replace), candidate("ideas", Resolution::Replace)];
+        find_duplicates(&mut candidates);
+        assert_eq!(candidates[1].note.title, "ideas (2)");
+        assert_eq!(candidates[1].warnings.len(), 1);
+        assert!(candidates[1].resolution == Resolution::KeepBoth);
+
+        let summary = apply(candidates, &mut notes);
+        assert_eq!((summary.added, summary.replaced), (1, 1));
+        assert_eq!(notes[0].id, 1);
+        assert_eq!(notes[1].title, "ideas (2)");
+    }
+
+    #[test]
+    fn a_note_of_the_batch_is_never_replaced_by_another() {
+        let mut notes = Vec::new();
+        let candidates = vec![candidate("Ideas", Resolution::Replace), candidate("Ideas", Resolution::Replace)];
+        let summary = apply(candidates, &mut notes);
+        assert_eq!(summary.added, 2);
+        assert_eq!(notes[1].title, "Ideas (2)");
+    }
+
+    #[test]
+    fn links_to_a_renamed_file_follow_it() {
+        let dir = TempDir::new().unwrap();
+        for (path, text) in [("a/Ideas.md", "one"), ("b/Ideas.md", "two"), ("c/Links.md", "[two](../b/Ideas.md)")] {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+        let candidates = scan_folder(dir.path(), TitleSource::FileName).unwrap();
+        let titles: Vec<&str> = candidates.iter().map(|c| c.note.title.as_str()).collect();
+        assert_eq!(titles, vec!["Ideas", "Ideas (2)", "Links"]);
+        assert!(candidates[1].resolution == Resolution::KeepBoth);
+        assert_eq!(candidates[2].note.content, "[[Ideas (2)]]");
+
+        let mut notes = Vec::new();
+        apply(candidates, &mut notes);
+        assert_eq!(notes[2].content, links::link_to(&notes[1]));
+        assert_eq!(notes[1].content, "two");
+    }
+
+    #[test]
+    fn files_outside_the_folder_are_not_attached() {
+        let dir = TempDir::new().unwrap();
+        let secret = dir.path().join("secret.txt");
+        fs::write(&secret, "private").unwrap();
+        fs::create_dir(dir.path().join("vault")).unwrap();
+        let text = format!("[a](../secret.txt) [b](<{}>) ![[../secret.txt]]", secret.display());
+        fs::write(dir.path().join("vault/Note.md"), &text).unwrap();
+
+        let candidates = scan_folder(&dir.path().join("vault"), TitleSource::FileName).unwrap();
+        assert!(candidates[0].files.is_empty());
+        assert!(candidates[0].note.attachments.is_empty());
+        assert_eq!(candidates[0].note.content, text);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::attachments::{self, Attachment};
+use crate::enex;
+use crate::images::{self, ATTACHMENT_SCHEME};
+use crate::import::{self, parse_timestamp, Blob, Candidate, Resolution};
+use note_core::note::Note;
+use crate::properties::PropertyValue;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Item types in a Joplin export.
+const TYPE_NOTE: &str = "1";
+const TYPE_FOLDER: &str = "2";
+const TYPE_RESOURCE: &str = "4";
+const TYPE_TAG: &str = "5";
+const TYPE_NOTE_TAG: &str = "6";
+
+/// One item of a Joplin export: a title line, an optional body, then `key: value` metadata.
+struct Item {
+    title: String,
+    body: String,
+    meta: HashMap<String, String>,
+}
+
+impl Item {
+    fn get(&self, key: &str) -> &str {
+        self.meta.get(key).map_or("", String::as_str)
+    }
+
+    /// A timestamp, preferring the user-facing one Joplin keeps next to the sync time.
+    fn time(&self, key: &str) -> Option<u64> {
+        parse_timestamp(self.get(&format!("user_{key}"))).or_else(|| parse_timestamp(self.get(key)))
+    }
+
+    /// A timestamp stored as milliseconds, where 0 means unset.
+    fn millis(&self, key: &str) -> Option<u64> {
+        self.get(key).parse::<u64>().ok().filter(|&ms| ms > 0).map(|ms| ms / 1000)
+    }
+}
+
+fn meta_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^([a-z_]+): ?(.*)$").unwrap())
+}
+
+fn parse_item(text: &str) -> Option<Item> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut end = lines.len();
+    while end > 0 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    let mut start = end;
+    while start > 0 && meta_regex().is_match(lines[start - 1]) {
+        start -= 1;
+    }
+    let meta: HashMap<String, String> = lines[start..end]
+        .iter()
+        .filter_map(|line| meta_regex().captures(line))
+        .map(|caps| (caps[1].to_owned(), caps[2].replace("\\n", "\n")))
+        .collect();
+    if !meta.contains_key("id") || !meta.contains_key("type_") {
+        return None;
+    }
+    let title = if start > 0 { lines[0].to_owned() } else { String::new() };
+    let body = if start > 2 { lines[2..start].join("\n").trim_end().to_owned() } else { String::new() };
+    Some(Item { title, body, meta })
+}
+
+/// The files of a `.jex` archive or a RAW export folder, by path relative to its root.
+fn read_export(path: &Path) -> io::Result<HashMap<String, Vec<u8>>> {
+    let mut files = HashMap::new();
+    if path.is_dir() {
+        for dir in [path.to_owned(), path.join("resources")] {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten().filter(|e| e.path().is_file()) {
+                let relative = entry.path().strip_prefix(path).unwrap_or(&entry.path()).to_string_lossy().replace('\\', "/");
+                files.insert(relative, fs::read(entry.path())?);
+            }
+        }
+    } else {
+        // A JEX file is a tar archive of a RAW export.
+        let mut archive = tar::Archive::new(File::open(path)?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let relative = entry.path()?.to_string_lossy().trim_start_matches("./").to_owned();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes)?;
+            files.insert(relative, bytes);
+        }
+    }
+    Ok(files)
+}
+
+fn of_type<'a>(items: &'a [Item], kind: &'static str) -> impl Iterator<Item = &'a Item> {
+    items.iter().filter(move |i| i.get("type_") == kind)
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(!?)\[([^\]\n]*)\]\(:/([0-9a-f]{32})(?:#[^)\s]*)?(?:\s+"[^"]*")?\)|<img[^>]*src=":/([0-9a-f]{32})"[^>]*>"#).unwrap()
+    })
+}
+
+/// Read a Joplin `.jex` export or RAW export folder.
+pub fn scan_joplin(path: &Path) -> io::Result<Vec<Candidate>> {
+    let files = read_export(path)?;
+    let mut items: Vec<Item> = files
+        .iter()
+        .filter(|(name, _)| !name.contains('/') && name.ends_with(".md"))
+        .filter_map(|(_, bytes)| parse_item(&String::from_utf8_lossy(bytes)))
+        .collect();
+    if items.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a Joplin export"));
+    }
+    items.sort_by_key(|a| a.title.to_lowercase());
+    let folders: HashMap<&str, &Item> = of_type(&items, TYPE_FOLDER).map(|f| (f.get("id"), f)).collect();
+    let folder_path = |id: &str| {
+        let mut id = id;
+        let mut parts = Vec::new();
+        while let Some(folder) = folders.get(id) {
+            // Guard against a cycle in damaged data.
+            if parts.len() > 32 {
+                break;
+            }
+            parts.push(folder.title.as_str());
+            id = folder.get("parent_id");
+        }
+        parts.reverse();
+        parts.join(" / ")
+    };
+    let tags: HashMap<&str, &str> = of_type(&items, TYPE_TAG).map(|t| (t.get("id"), t.title.as_str())).collect();
+    let mut note_tags: HashMap<&str, Vec<&str>> = HashMap::new();
+    for link in of_type(&items, TYPE_NOTE_TAG) {
+        if let Some(tag) = tags.get(link.get("tag_id")) {
+            note_tags.entry(link.get("note_id")).or_default().push(tag);
+        }
+    }
+    let base_title = |item: &Item| if item.title.trim().is_empty() { "Untitled".to_owned() } else { item.title.trim().to_owned() };
+    let selected = |item: &Item| item.millis("deleted_time").is_none() && item.get("is_conflict") != "1";
+    // Notes sharing a title are numbered before links are rewritten, so each link keeps its
+    // target. Notes selected for import claim their titles first.
+    let mut taken = HashSet::new();
+    let mut note_titles: HashMap<&str, String> = HashMap::new();
+    for include in [true, false] {
+        for item in of_type(&items, TYPE_NOTE).filter(|n| selected(n) == include) {
+            note_titles.insert(item.get("id"), import::claim_title(&base_title(item), &mut taken));
+        }
+    }
+    let resources: HashMap<&str, &Item> = of_type(&items, TYPE_RESOURCE).map(|r| (r.get("id"), r)).collect();
+    let resource_bytes = |id: &str| {
+        files
+            .iter()
+            .find(|(name, _)| {
+                name.strip_prefix("resources/")
+                    .is_some_and(|rest| rest == id || rest.starts_with(&format!("{id}.")))
+            })
+            .map(|(_, bytes)| bytes.clone())
+    };
+
+    let mut candidates = Vec::new();
+    for item in of_type(&items, TYPE_NOTE) {
+        let title = note_titles.get(item.get("id")).cloned().unwrap_or_else(|| base_title(item));
+        let mut note = Note::new(0, &title);
+        let mut warnings = Vec::new();
+        let renamed = title != base_title(item);
+        if renamed {
+            warnings.push(import::renamed_warning(&base_title(item)));
+        }
+        let mut blobs = Vec::new();
+        if let Some(created) = item.time("created_time") {
+            note.created = created;
+        }
+        note.edited = item.time("updated_time").unwrap_or(note.created);
+        if let Some(tags) = note_tags.get(item.get("id")) {
+            note.tags = Note::parse_tags(&tags.join(","));
+        }
+        if item.get("is_todo") == "1" {
+            note.due = item.millis("todo_due");
+            let done = item.millis("todo_completed").is_some();
+            note.status = Some(if done { "Done" } else { "To Do" }.to_owned());
+        }
+        let notebook = folder_path(item.get("parent_id"));
+        if !notebook.is_empty() {
+            note.properties.insert("Notebook".to_owned(), PropertyValue::Text(notebook));
+        }
+        for (key, name) in [("source_url", "Source"), ("author", "Author")] {
+            if !item.get(key).is_empty() {
+                note.properties.insert(name.to_owned(), PropertyValue::Text(item.get(key).to_owned()));
+            }
+        }
+
+        // Markup language 2 is HTML.
+        let body = if item.get("markup_language") == "2" {
+            let (markdown, html_warnings) = enex::html_to_markdown(&item.body, &HashMap::new());
+            warnings.extend(html_warnings);
+            markdown
+        } else {
+            item.body.clone()
+        };
+        let mut content = String::with_capacity(body.len());
+        let mut last = 0;
+        for caps in reference_regex().captures_iter(&body) {
+            let whole = caps.get(0).unwrap();
+            let html_image = caps.get(4).is_some();
+            let id = caps.get(3).or(caps.get(4)).map_or("", |m| m.as_str());
+            let image = html_image || !caps[1].is_empty();
+            let text = caps.get(2).map_or("", |m| m.as_str());
+            let replacement = if let Some(title) = note_titles.get(id) {
+                format!("[[{title}]]")
+            } else if let Some(resource) = resources.get(id) {
+                let name = match (resource.get("filename"), resource.title.as_str()) {
+                    ("", "") => format!("{id}.{}", resource.get("file_extension")),
+                    ("", title) => title.to_owned(),
+                    (filename, _) => filename.to_owned(),
+                };
+                match resource_bytes(id) {
+                    Some(bytes) => {
+                        let attachment = Attachment {
+                            name,
+                            hash: attachments::hash_bytes(&bytes),
+                            size: bytes.len() as u64,
+                        };
+                        if !note.attachments.contains(&attachment) {
+                            note.attachments.push(attachment.clone());
+                            blobs.push((attachment.clone(), Blob::Bytes(bytes)));
+                        }
+                        if image && images::is_image(&attachment.name) {
+                            format!("![{text}]({ATTACHMENT_SCHEME}{})", attachment.hash)
+                        } else {
+                            format!("📎 {}", if text.is_empty() { &attachment.name } else { text })
+                        }
+                    }
+                    None => {
+                        warnings.push(format!("Attachment \"{name}\" is missing from the export"));
+                        continue;
+                    }
+                }
+            } else {
+                warnings.push(format!("Link to an item that isn't in the export ({id})"));
+                continue;
+            };
+            content.push_str(&body[last..whole.start()]);
+            content.push_str(&replacement);
+            last = whole.end();
+        }
+        content.push_str(&body[last..]);
+        note.content = content;
+
+        let mut include = true;
+        if item.millis("deleted_time").is_some() {
+            warnings.push("In Joplin's trash; not selected".to_owned());
+            include = false;
+        }
+        if item.get("is_conflict") == "1" {
+            warnings.push("A sync conflict copy; not selected".to_owned());
+            include = false;
+        }
+        candidates.push(Candidate {
+            source: format!("{}.md", item.get("id")),
+            note,
+            files: blobs,
+            warnings,
+            include,
+            resolution: if renamed { Resolution::KeepBoth } else { Resolution::Skip },
+        });
+    }
+    import::find_duplicates(&mut candidates);
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FIRST: &str = "0123456789abcdef0123456789abcdef";
+    const SECOND: &str = "11111111111111111111111111111111";
+    const LINKS: &str = "22222222222222222222222222222222";
+
+    fn write_item(dir: &Path, id: &str, title: &str, body: &str) {
+        fs::write(dir.join(format!("{id}.md")), format!("{title}\n\n{body}\n\nid: {id}\ntype_: 1\n")).unwrap();
+    }
+
+    #[test]
+    fn notes_sharing_a_title_are_numbered_and_links_keep_their_target() {
+        let dir = TempDir::new().unwrap();
+        write_item(dir.path(), FIRST, "Ideas", "one");
+        write_item(dir.path(), SECOND, "Ideas", "two");
+        write_item(dir.path(), LINKS, "Links", &format!("See [two](:/{SECOND})"));
+        let candidates = scan_joplin(dir.path()).unwrap();
+        let by_id = |id: &str| candidates.iter().find(|c| c.source == format!("{id}.md")).unwrap();
+
+        let (first, second) = (by_id(FIRST), by_id(SECOND));
+        let mut titles = [first.note.title.as_str(), second.note.title.as_str()];
+        titles.sort();
+        assert_eq!(titles, ["Ideas", "Ideas (2)"]);
+        let renamed = if first.note.title == "Ideas" { second } else { first };
+        assert_eq!(renamed.warnings, vec![import::renamed_warning("Ideas")]);
+        assert!(renamed.resolution == Resolution::KeepBoth);
+        assert_eq!(by_id(LINKS).note.content, format!("See [[{}]]", second.note.title));
+    }
+}
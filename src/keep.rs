@@ -0,0 +1,220 @@
+use crate::attachments;
+use crate::attachments::Attachment;
+use crate::images;
+use crate::import::{self, Blob, Candidate, Resolution};
+use note_core::note::Note;
+use crate::properties::PropertyValue;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Untitled Keep notes are named after the start of their text.
+const TITLE_LENGTH: usize = 60;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepLabel {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepListItem {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    is_checked: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepAttachment {
+    file_path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepAnnotation {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    url: String,
+}
+
+/// A note in a Google Keep Takeout export.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KeepNote {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    text_content: String,
+    #[serde(default)]
+    list_content: Vec<KeepListItem>,
+    #[serde(default)]
+    labels: Vec<KeepLabel>,
+    #[serde(default)]
+    attachments: Vec<KeepAttachment>,
+    #[serde(default)]
+    annotations: Vec<KeepAnnotation>,
+    #[serde(default)]
+    created_timestamp_usec: u64,
+    #[serde(default)]
+    user_edited_timestamp_usec: u64,
+    #[serde(default)]
+    is_trashed: bool,
+    #[serde(default)]
+    is_archived: bool,
+}
+
+/// Takeout sometimes names a file `.jpg` where the note says `.jpeg`, or the other way round.
+fn find_attachment(dir: &Path, file_path: &str) -> Option<PathBuf> {
+    let path = dir.join(file_path);
+    if path.is_file() {
+        return Some(path);
+    }
+    let stem = path.file_stem()?.to_string_lossy().into_owned();
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.file_stem().is_some_and(|s| s.to_string_lossy() == stem) && p.is_file())
+}
+
+impl KeepNote {
+    fn into_candidate(self, source: String, dir: &Path) -> Candidate {
+        let mut warnings = Vec::new();
+        let mut content = self.text_content.trim_end().to_owned();
+        for item in &self.list_content {
+            if !content.is_empty() && !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push_str(&format!("- [{}] {}", if item.is_checked { "x" } else { " " }, item.text.trim()));
+        }
+        if !self.annotations.is_empty() {
+            content.push_str("\n\n");
+            for annotation in &self.annotations {
+                let title = if annotation.title.is_empty() { &annotation.url } else { &annotation.title };
+                content.push_str(&format!("- [{title}]({})\n", annotation.url));
+            }
+        }
+
+        let title = if self.title.trim().is_empty() {
+            let first = content.lines().find(|l| !l.trim().is_empty()).unwrap_or("Untitled");
+            let first = first.trim().trim_start_matches("- [ ] ").trim_start_matches("- [x] ");
+            first.chars().take(TITLE_LENGTH).collect()
+        } else {
+            self.title.trim().to_owned()
+        };
+        let mut note = Note::new(0, &title);
+        if self.created_timestamp_usec > 0 {
+            note.created = self.created_timestamp_usec / 1_000_000;
+            note.edited = note.created;
+        }
+        if self.user_edited_timestamp_usec > 0 {
+            note.edited = self.user_edited_timestamp_usec / 1_000_000;
+        }
+        let labels: Vec<&str> = self.labels.iter().map(|l| l.name.as_str()).collect();
+        note.tags = Note::parse_tags(&labels.join(","));
+        if self.is_archived {
+            note.properties.insert("Archived".to_owned(), PropertyValue::Checkbox(true));
+        }
+
+        let mut files = Vec::new();
+        for attachment in &self.attachments {
+            let Some(path) = find_attachment(dir, &attachment.file_path) else {
+                warnings.push(format!("Attachment {} is missing from the export", attachment.file_path));
+                continue;
+            };
+            let bytes = match fs::read(&path) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    warnings.push(format!("Attachment {} couldn't be read: {e}", attachment.file_path));
+                    continue;
+                }
+            };
+            let stored = Attachment {
+                name: path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+                hash: attachments::hash_bytes(&bytes),
+                size: bytes.len() as u64,
+            };
+            if note.attachments.contains(&stored) {
+                continue;
+            }
+            // Keep shows images above the text.
+            if images::is_image(&stored.name) {
+                content = format!("{}\n\n{content}", images::markdown_for(&stored));
+            }
+            note.attachments.push(stored.clone());
+            files.push((stored, Blob::File(path)));
+        }
+        note.content = content.trim().to_owned();
+
+        let include = !self.is_trashed;
+        if self.is_trashed {
+            warnings.push("In Keep's trash; not selected".to_owned());
+        }
+        Candidate {
+            source,
+            note,
+            files,
+            warnings,
+            include,
+            resolution: Resolution::Skip,
+        }
+    }
+}
+
+/// Read the notes in the Keep folder of a Google Takeout export.
+pub fn scan_keep(dir: &Path) -> io::Result<Vec<Candidate>> {
+    // Accept the Takeout folder itself as well as its Keep folder.
+    let dir = if dir.join("Keep").is_dir() { dir.join("Keep") } else { dir.to_owned() };
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("json")))
+        .collect();
+    paths.sort();
+    let mut candidates = Vec::new();
+    for path in paths {
+        let text = fs::read_to_string(&path)?;
+        // Takeout also has JSON files that aren't notes, such as the list of labels.
+        let Ok(keep_note) = serde_json::from_str::<KeepNote>(&text) else {
+            continue;
+        };
+        if keep_note.created_timestamp_usec == 0 && keep_note.user_edited_timestamp_usec == 0 {
+            continue;
+        }
+        let source = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        candidates.push(keep_note.into_candidate(source, &dir));
+    }
+    import::find_duplicates(&mut candidates);
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn untitled_notes_with_the_same_text_are_numbered() {
+        let dir = TempDir::new().unwrap();
+        let note = |text: &str, trashed: bool| {
+            format!(r#"{{"textContent": "{text}", "createdTimestampUsec": 1700000000000000, "isTrashed": {trashed}}}"#)
+        };
+        fs::write(dir.path().join("a.json"), note("milk\\neggs", false)).unwrap();
+        fs::write(dir.path().join("b.json"), note("milk", true)).unwrap();
+        fs::write(dir.path().join("c.json"), note("milk", false)).unwrap();
+        fs::write(dir.path().join("Labels.json"), "[]").unwrap();
+
+        let candidates = scan_keep(dir.path()).unwrap();
+        let titles: Vec<&str> = candidates.iter().map(|c| c.note.title.as_str()).collect();
+        // Notes selected for import claim their titles before the trashed one.
+        assert_eq!(titles, vec!["milk", "milk (3)", "milk (2)"]);
+        assert!(!candidates[1].include);
+        assert_eq!(candidates[2].warnings, vec![import::renamed_warning("milk")]);
+        assert_eq!(candidates[0].note.created, 1_700_000_000);
+    }
+}
@@ -1,13 +1,46 @@
-mod note;
-mod storage;
 mod theme;
 mod app;
+mod find_replace;
+mod note_find;
+mod render;
+mod graph;
+mod templates;
+mod settings;
+mod daily;
+mod calendar;
+mod tasks;
+mod reminders;
+mod board;
+mod properties;
+mod attachments;
+mod images;
+mod encryption;
+mod note_lock;
+mod export;
+mod html;
+mod pdf;
+mod import;
+mod enex;
+mod joplin;
+mod keep;
+mod records;
+mod cli;
+mod palette;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
     let native_options = eframe::NativeOptions::default();
-    eframe::run_native(
+    if let Err(e) = eframe::run_native(
         "Advanced Note Taking App",
         native_options,
-        Box::new(|_cc| Box::new(app::NoteApp::default())),
-    );
+        Box::new(|cc| {
+            egui_extras::install_image_loaders(&cc.egui_ctx);
+            Box::new(app::NoteApp::default())
+        }),
+    ) {
+        eprintln!("Error running app: {e}");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,206 @@
+use crate::find_replace::{build_regex, expand, replace_text, FindOptions};
+use eframe::egui;
+use std::ops::Range;
+
+/// What the find bar asks the caller to do after it was drawn.
+#[derive(Default)]
+pub struct FindBarOutput {
+    /// The text was changed by a replace and the caller should treat it as edited.
+    pub replaced: bool,
+    /// The user navigated to a match; editors should move the cursor there.
+    pub jump_to: Option<Range<usize>>,
+}
+
+/// State of the Ctrl+F find bar scoped to the note currently shown.
+#[derive(Default)]
+pub struct NoteFindState {
+    pub open: bool,
+    pub query: String,
+    pub replace: String,
+    pub options: FindOptions,
+    pub current: usize,
+    pub error: Option<String>,
+    focus_query: bool,
+}
+
+impl NoteFindState {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.focus_query = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.error = None;
+    }
+
+    /// Byte ranges of every match of the current query in `text`.
+    pub fn matches(&self, text: &str) -> Vec<Range<usize>> {
+        if !self.open || self.query.is_empty() {
+            return Vec::new();
+        }
+        match build_regex(&self.query, self.options) {
+            Ok(re) => re
+                .find_iter(text)
+                .filter(|m| !m.as_str().is_empty())
+                .map(|m| m.range())
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Draw the find bar. When `editable` is true the replace controls are shown
+    /// and replacements are written into `text`.
+    pub fn show_bar(&mut self, ui: &mut egui::Ui, text: &mut String, editable: bool) -> FindBarOutput {
+        let mut output = FindBarOutput::default();
+        if !self.open {
+            return output;
+        }
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.close();
+            return output;
+        }
+
+        self.error = match build_regex(&self.query, self.options) {
+            Err(e) if !self.query.is_empty() => Some(e.to_string()),
+            _ => None,
+        };
+        let matches = self.matches(text);
+        if self.current >= matches.len() {
+            self.current = 0;
+        }
+
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let query_response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Find in note...")
+                        .desired_width(200.0),
+                );
+                if self.focus_query {
+                    query_response.request_focus();
+                    self.focus_query = false;
+                }
+                if query_response.changed() {
+                    self.current = 0;
+                    output.jump_to = self.matches(text).first().cloned();
+                }
+                let enter = query_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let shift = ui.input(|i| i.modifiers.shift);
+
+                let count_text = if self.error.is_some() {
+                    "Invalid pattern".to_owned()
+                } else if matches.is_empty() {
+                    "No matches".to_owned()
+                } else {
+                    format!("{} of {}", self.current + 1, matches.len())
+                };
+                ui.label(egui::RichText::new(count_text).weak());
+
+                let prev = ui.button("⬆").on_hover_text("Previous match (Shift+Enter)").clicked();
+                let next = ui.button("⬇").on_hover_text("Next match (Enter)").clicked();
+                if !matches.is_empty() && (prev || next || enter) {
+                    if prev || (enter && shift) {
+                        self.current = (self.current + matches.len() - 1) % matches.len();
+                    } else {
+                        self.current = (self.current + 1) % matches.len();
+                    }
+                    output.jump_to = Some(matches[self.current].clone());
+                }
+                if enter {
+                    query_response.request_focus();
+                }
+
+                let case_changed = ui
+                    .toggle_value(&mut self.options.case_sensitive, "Aa")
+                    .on_hover_text("Match case")
+                    .changed();
+                let regex_changed = ui
+                    .toggle_value(&mut self.options.regex, ".*")
+                    .on_hover_text("Use regular expression")
+                    .changed();
+                if case_changed || regex_changed {
+                    self.current = 0;
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("✖").on_hover_text("Close (Esc)").clicked() {
+                        self.close();
+                    }
+                });
+            });
+
+            if editable {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.replace)
+                            .hint_text("Replace with...")
+                            .desired_width(200.0),
+                    );
+                    let has_matches = !matches.is_empty();
+                    if ui.add_enabled(has_matches, egui::Button::new("Replace")).clicked() {
+                        if let Ok(re) = build_regex(&self.query, self.options) {
+                            let range = matches[self.current].clone();
+                            if let Some(caps) = re.captures_at(text, range.start) {
+                                let replacement = expand(&caps, &self.replace, self.options);
+                                text.replace_range(range.clone(), &replacement);
+                                output.replaced = true;
+                                let remaining = self.matches(text);
+                                self.current = remaining
+                                    .iter()
+                                    .position(|m| m.start >= range.start + replacement.len())
+                                    .unwrap_or(0);
+                                output.jump_to = remaining.get(self.current).cloned();
+                            }
+                        }
+                    }
+                    if ui.add_enabled(has_matches, egui::Button::new("Replace All")).clicked() {
+                        if let Ok(re) = build_regex(&self.query, self.options) {
+                            *text = replace_text(&re, text, &self.replace, self.options);
+                            output.replaced = true;
+                            self.current = 0;
+                        }
+                    }
+                });
+            }
+        });
+        output
+    }
+
+    /// Lay out `text` with every match highlighted and the current match emphasised.
+    pub fn highlight(&self, ui: &egui::Ui, text: &str, font: egui::FontId) -> egui::text::LayoutJob {
+        use egui::text::{LayoutJob, TextFormat};
+        let normal = TextFormat::simple(font, ui.visuals().text_color());
+        let mut job = LayoutJob::default();
+        let mut last = 0;
+        for (i, range) in self.matches(text).into_iter().enumerate() {
+            job.append(&text[last..range.start], 0.0, normal.clone());
+            let background = if i == self.current {
+                egui::Color32::from_rgb(255, 170, 40).gamma_multiply(0.8)
+            } else {
+                egui::Color32::from_rgb(60, 155, 255).gamma_multiply(0.35)
+            };
+            job.append(
+                &text[range.clone()],
+                0.0,
+                TextFormat {
+                    background,
+                    ..normal.clone()
+                },
+            );
+            last = range.end;
+        }
+        job.append(&text[last..], 0.0, normal);
+        job
+    }
+}
+
+/// Select `range` (byte offsets into the editor's text) in the `TextEdit` with the given id.
+pub fn select_in_editor(ctx: &egui::Context, id: egui::Id, text: &str, range: Range<usize>) {
+    use egui::text::{CCursor, CCursorRange};
+    let mut state = egui::text_edit::TextEditState::load(ctx, id).unwrap_or_default();
+    let start = text[..range.start].chars().count();
+    let end = start + text[range].chars().count();
+    state.cursor.set_char_range(Some(CCursorRange::two(CCursor::new(start), CCursor::new(end))));
+    state.store(ctx, id);
+}
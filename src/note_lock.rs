@@ -0,0 +1,106 @@
+pub use note_core::note_lock::*;
+
+use note_core::note::Note;
+use eframe::egui;
+use zeroize::Zeroize;
+
+/// Unlock form shown in place of a locked note's content, and the passphrase form for locking one.
+#[derive(Default)]
+pub struct NoteLockPanel {
+    passphrase: String,
+    confirm: String,
+    /// The lock form is open for this note.
+    pub locking: Option<u64>,
+    error: Option<String>,
+}
+
+impl NoteLockPanel {
+    /// Draw the unlock form. Returns true once notes were unlocked.
+    pub fn show_unlock(&mut self, ui: &mut egui::Ui, notes: &mut [Note]) -> bool {
+        let mut unlocked = false;
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.label(egui::RichText::new("🔒 This note is locked").size(18.0).strong());
+            ui.label(egui::RichText::new("Its content is encrypted. Enter the locked-notes passphrase to read it.").weak());
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.passphrase)
+                        .password(true)
+                        .hint_text("Passphrase"),
+                );
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("🔓 Unlock").clicked() || submitted {
+                    match unlock(notes, &self.passphrase) {
+                        Ok(_) => {
+                            self.error = None;
+                            unlocked = true;
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                    self.passphrase.zeroize();
+                }
+            });
+            self.show_error(ui);
+        });
+        unlocked
+    }
+
+    /// Draw the passphrase form for locking the note at `idx`. Returns true once it is locked.
+    pub fn show_lock(&mut self, ui: &mut egui::Ui, notes: &mut [Note], idx: usize) -> bool {
+        let mut locked = false;
+        let first_lock = !notes.iter().any(|n| n.locked);
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.label(if first_lock {
+                "Choose a passphrase for locked notes. It can't be recovered if forgotten."
+            } else {
+                "Enter the passphrase used for your other locked notes."
+            });
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.passphrase)
+                        .password(true)
+                        .hint_text("Passphrase"),
+                );
+                if first_lock {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.confirm)
+                            .password(true)
+                            .hint_text("Confirm"),
+                    );
+                }
+                if ui.button("🔒 Lock").clicked() {
+                    let result = if first_lock && self.passphrase != self.confirm {
+                        Err("Passphrases don't match".to_owned())
+                    } else if self.passphrase.is_empty() {
+                        Err("Enter a passphrase".to_owned())
+                    } else {
+                        lock_note(notes, idx, &self.passphrase)
+                    };
+                    match result {
+                        Ok(()) => {
+                            self.error = None;
+                            self.locking = None;
+                            locked = true;
+                        }
+                        Err(e) => self.error = Some(e),
+                    }
+                    self.passphrase.zeroize();
+                    self.confirm.zeroize();
+                }
+                if ui.button("Cancel").clicked() {
+                    self.locking = None;
+                    self.error = None;
+                }
+            });
+            self.show_error(ui);
+        });
+        locked
+    }
+
+    fn show_error(&self, ui: &mut egui::Ui) {
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::from_rgb(255, 80, 80), error);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use eframe::egui;
+
+/// A keyboard shortcut: Ctrl (Cmd on macOS), optionally Shift, and a key.
+#[derive(Clone, Copy)]
+pub struct Shortcut {
+    pub shift: bool,
+    pub key: egui::Key,
+}
+
+impl Shortcut {
+    const fn ctrl(key: egui::Key) -> Self {
+        Self { shift: false, key }
+    }
+
+    const fn ctrl_shift(key: egui::Key) -> Self {
+        Self { shift: true, key }
+    }
+
+    pub fn pressed(self, ctx: &egui::Context) -> bool {
+        ctx.input(|i| i.modifiers.command && i.modifiers.shift == self.shift && i.key_pressed(self.key))
+    }
+
+    pub fn label(self) -> String {
+        let command = if cfg!(target_os = "macos") { "Cmd" } else { "Ctrl" };
+        format!("{command}+{}{}", if self.shift { "Shift+" } else { "" }, self.key.symbol_or_name())
+    }
+}
+
+/// Opens and closes the command palette.
+pub const PALETTE_SHORTCUT: Shortcut = Shortcut::ctrl_shift(egui::Key::P);
+
+/// Everything that can be run from the command palette or a keyboard shortcut.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    NewNote,
+    EditNote,
+    DeleteNote,
+    Home,
+    Today,
+    PickDay,
+    Graph,
+    Calendar,
+    Tasks,
+    Board,
+    FindReplace,
+    FindInNote,
+    Undo,
+    Redo,
+    Import,
+    Export,
+    Settings,
+    ToggleTheme,
+    LockStore,
+    LockNotes,
+}
+
+impl Action {
+    /// The registry, in the order the palette lists actions before any are used.
+    pub const ALL: [Action; 20] = [
+        Action::NewNote,
+        Action::EditNote,
+        Action::DeleteNote,
+        Action::Home,
+        Action::Today,
+        Action::PickDay,
+        Action::Graph,
+        Action::Calendar,
+        Action::Tasks,
+        Action::Board,
+        Action::FindReplace,
+        Action::FindInNote,
+        Action::Undo,
+        Action::Redo,
+        Action::Import,
+        Action::Export,
+        Action::Settings,
+        Action::ToggleTheme,
+        Action::LockStore,
+        Action::LockNotes,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::NewNote => "➕ New note",
+            Action::EditNote => "✏️ Edit note",
+            Action::DeleteNote => "🗑️ Delete note",
+            Action::Home => "🏠 Go home",
+            Action::Today => "📅 Open today's note",
+            Action::PickDay => "🗓 Jump to a day",
+            Action::Graph => "🕸 Graph view",
+            Action::Calendar => "📆 Calendar view",
+            Action::Tasks => "☑ Tasks view",
+            Action::Board => "📋 Board view",
+            Action::FindReplace => "🔁 Find and replace in all notes",
+            Action::FindInNote => "🔍 Find in note",
+            Action::Undo => "↩ Undo",
+            Action::Redo => "↪ Redo",
+            Action::Import => "📥 Import notes",
+            Action::Export => "📤 Export notes",
+            Action::Settings => "⚙ Open settings",
+            Action::ToggleTheme => "🌙 Toggle dark mode",
+            Action::LockStore => "🔒 Lock encrypted notes",
+            Action::LockNotes => "🔒 Lock unlocked notes",
+        }
+    }
+
+    /// Keyboard shortcuts; the first is the one shown.
+    pub fn shortcuts(self) -> &'static [Shortcut] {
+        const NEW_NOTE: &[Shortcut] = &[Shortcut::ctrl(egui::Key::N)];
+        const FIND_REPLACE: &[Shortcut] = &[Shortcut::ctrl_shift(egui::Key::F)];
+        const FIND_IN_NOTE: &[Shortcut] = &[Shortcut::ctrl(egui::Key::F)];
+        const UNDO: &[Shortcut] = &[Shortcut::ctrl(egui::Key::Z)];
+        const REDO: &[Shortcut] = &[Shortcut::ctrl_shift(egui::Key::Z), Shortcut::ctrl(egui::Key::Y)];
+        const SETTINGS: &[Shortcut] = &[Shortcut::ctrl(egui::Key::Comma)];
+        match self {
+            Action::NewNote => NEW_NOTE,
+            Action::FindReplace => FIND_REPLACE,
+            Action::FindInNote => FIND_IN_NOTE,
+            Action::Undo => UNDO,
+            Action::Redo => REDO,
+            Action::Settings => SETTINGS,
+            _ => &[],
+        }
+    }
+}
+
+/// How well `query` matches `text` as a subsequence, ignoring case and spaces in the query.
+/// Matches at word starts and runs of adjacent letters score higher. `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut from = 0;
+    let mut last: Option<usize> = None;
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (from..text.len()).find(|&i| text[i] == c)?;
+        score += 1;
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 2;
+        }
+        last = Some(found);
+        from = found + 1;
+    }
+    Some(score)
+}
+
+/// The Ctrl+Shift+P overlay for finding and running an action by name.
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    query: String,
+    /// Row highlighted with the arrow keys.
+    current: usize,
+    /// Actions run from the palette, most recent first.
+    recent: Vec<Action>,
+    focus_query: bool,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.current = 0;
+        self.focus_query = true;
+    }
+
+    /// `available` actions matching the query, best first. Ties go to the most recently used.
+    fn matches(&self, available: &[Action]) -> Vec<Action> {
+        let mut scored: Vec<(i32, usize, usize, Action)> = available
+            .iter()
+            .enumerate()
+            .filter_map(|(order, &action)| {
+                let score = fuzzy_score(&self.query, action.label())?;
+                let recency = self.recent.iter().position(|&a| a == action).unwrap_or(usize::MAX);
+                Some((score, recency, order, action))
+            })
+            .collect();
+        scored.sort_by_key(|&(score, recency, order, _)| (-score, recency, order));
+        scored.into_iter().map(|(.., action)| action).collect()
+    }
+
+    /// Draw the palette over the window. Returns the action to run once one is picked.
+    pub fn show(&mut self, ctx: &egui::Context, available: &[Action]) -> Option<Action> {
+        if !self.open {
+            return None;
+        }
+        let matches = self.matches(available);
+        let mut picked = None;
+        let mut close = false;
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 80.0])
+            .fixed_size([460.0, 0.0])
+            .show(ctx, |ui| {
+                // Taken before the text field sees them, so they move the highlight instead.
+                let (up, down) = ui.input_mut(|i| {
+                    (
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                        i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                    )
+                });
+                if down && self.current + 1 < matches.len() {
+                    self.current += 1;
+                }
+                if up {
+                    self.current = self.current.saturating_sub(1);
+                }
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Type a command...")
+                        .desired_width(f32::INFINITY),
+                );
+                if self.focus_query {
+                    response.request_focus();
+                    self.focus_query = false;
+                }
+                if response.changed() {
+                    self.current = 0;
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    close = true;
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    match matches.get(self.current) {
+                        Some(&action) => picked = Some(action),
+                        None => close = true,
+                    }
+                }
+                ui.add_space(4.0);
+                if matches.is_empty() {
+                    ui.label(egui::RichText::new("No matching command").italics().weak());
+                }
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    for (i, &action) in matches.iter().enumerate() {
+                        let row = ui.horizontal(|ui| {
+                            let label = ui.selectable_label(i == self.current, action.label());
+                            if let Some(shortcut) = action.shortcuts().first() {
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    ui.label(egui::RichText::new(shortcut.label()).size(12.0).weak());
+                                });
+                            }
+                            label
+                        });
+                        if i == self.current && (up || down) {
+                            row.inner.scroll_to_me(None);
+                        }
+                        if row.inner.clicked() {
+                            picked = Some(action);
+                        }
+                    }
+                });
+            });
+        if let Some(action) = picked {
+            self.recent.retain(|&a| a != action);
+            self.recent.insert(0, action);
+            close = true;
+        }
+        if close {
+            self.open = false;
+        }
+        picked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_starts_and_runs_score_higher() {
+        assert_eq!(fuzzy_score("", "New note"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "New note"), None);
+        assert_eq!(fuzzy_score("nn", "New note"), Some(8));
+        assert!(fuzzy_score("NN", "New note") > fuzzy_score("ee", "New note"));
+        assert!(fuzzy_score("new", "New note") > fuzzy_score("new", "Renew"));
+        assert_eq!(fuzzy_score("n n", "New note"), fuzzy_score("nn", "New note"));
+    }
+
+    #[test]
+    fn shortcut_labels_name_the_platform_modifier() {
+        let command = if cfg!(target_os = "macos") { "Cmd" } else { "Ctrl" };
+        assert_eq!(Shortcut::ctrl(egui::Key::N).label(), format!("{command}+N"));
+        assert_eq!(PALETTE_SHORTCUT.label(), format!("{command}+Shift+P"));
+    }
+}
@@ -0,0 +1,583 @@
+use crate::attachments;
+use crate::html::{self, format_date};
+use crate::images;
+use note_core::links;
+use note_core::note::Note;
+use ab_glyph::{Font, FontArc};
+use eframe::egui;
+use printpdf::{
+    Color, IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerIndex, PdfLayerReference,
+    PdfPageIndex, Point, Rect, Rgb, TextRenderingMode,
+};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+
+// A4 portrait, in millimetres.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+/// Top of the content area, below the page header.
+const CONTENT_TOP: f32 = PAGE_HEIGHT - MARGIN - 6.0;
+const CONTENT_BOTTOM: f32 = MARGIN;
+const MM_PER_PT: f32 = 25.4 / 72.0;
+
+const BODY_SIZE: f32 = 11.0;
+const CODE_SIZE: f32 = 9.5;
+const SMALL_SIZE: f32 = 8.5;
+const INDENT: f32 = 7.0;
+const MAX_IMAGE_HEIGHT: f32 = 120.0;
+
+const TEXT: [f32; 3] = [0.12, 0.12, 0.12];
+const WEAK: [f32; 3] = [0.42, 0.45, 0.5];
+const ACCENT: [f32; 3] = [0.16, 0.45, 0.8];
+const CODE_BG: [f32; 3] = [0.93, 0.94, 0.96];
+const INLINE_CODE: [f32; 3] = [0.75, 0.22, 0.17];
+
+/// Fonts are the app's own, so notes print with the same coverage they display with.
+struct Fonts {
+    regular: FontArc,
+    mono: FontArc,
+    regular_ref: IndirectFontRef,
+    mono_ref: IndirectFontRef,
+}
+
+impl Fonts {
+    fn load(doc: &PdfDocumentReference) -> Result<Self, String> {
+        let definitions = egui::FontDefinitions::default();
+        let data = |family: egui::FontFamily| {
+            definitions.families[&family]
+                .first()
+                .and_then(|name| definitions.font_data.get(name))
+                .map(|data| data.font.to_vec())
+                .ok_or_else(|| "No built-in font to embed".to_owned())
+        };
+        let regular = data(egui::FontFamily::Proportional)?;
+        let mono = data(egui::FontFamily::Monospace)?;
+        Ok(Self {
+            regular_ref: doc.add_external_font(regular.as_slice()).map_err(|e| e.to_string())?,
+            mono_ref: doc.add_external_font(mono.as_slice()).map_err(|e| e.to_string())?,
+            regular: FontArc::try_from_vec(regular).map_err(|e| e.to_string())?,
+            mono: FontArc::try_from_vec(mono).map_err(|e| e.to_string())?,
+        })
+    }
+
+    fn get(&self, mono: bool) -> (&FontArc, &IndirectFontRef) {
+        if mono {
+            (&self.mono, &self.mono_ref)
+        } else {
+            (&self.regular, &self.regular_ref)
+        }
+    }
+
+    /// Width of `text` in millimetres.
+    fn width(&self, text: &str, mono: bool, size: f32) -> f32 {
+        let (font, _) = self.get(mono);
+        let units = font.units_per_em().unwrap_or(1000.0);
+        let advance: f32 = text.chars().map(|c| font.h_advance_unscaled(font.glyph_id(c))).sum();
+        advance / units * size * MM_PER_PT
+    }
+
+    /// Replace characters the font can't draw, which would otherwise print as empty boxes.
+    fn printable(&self, text: &str, mono: bool) -> String {
+        let (font, _) = self.get(mono);
+        text.chars()
+            .map(|c| if c.is_whitespace() || font.glyph_id(c).0 != 0 { c } else { '?' })
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Style {
+    mono: bool,
+    bold: bool,
+    color: [f32; 3],
+}
+
+/// A piece of text on a line, with its width in millimetres.
+struct Fragment {
+    text: String,
+    style: Style,
+    width: f32,
+}
+
+fn color(rgb: [f32; 3]) -> Color {
+    Color::Rgb(Rgb::new(rgb[0], rgb[1], rgb[2], None))
+}
+
+fn heading_size(level: HeadingLevel) -> f32 {
+    match level {
+        HeadingLevel::H1 => 20.0,
+        HeadingLevel::H2 => 16.0,
+        HeadingLevel::H3 => 14.0,
+        HeadingLevel::H4 => 12.5,
+        _ => BODY_SIZE,
+    }
+}
+
+/// Lays out notes page by page, top to bottom.
+struct Writer<'a> {
+    doc: &'a PdfDocumentReference,
+    fonts: Fonts,
+    layer: PdfLayerReference,
+    /// Every page with the note it belongs to, for the headers and page numbers.
+    pages: Vec<(PdfPageIndex, PdfLayerIndex, usize)>,
+    note: usize,
+    /// Distance of the next line's top from the bottom of the page.
+    y: f32,
+}
+
+impl<'a> Writer<'a> {
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.pages.push((page, layer, self.note));
+        self.y = CONTENT_TOP;
+    }
+
+    /// Start a new page unless `height` still fits on this one.
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < CONTENT_BOTTOM && self.y < CONTENT_TOP {
+            self.new_page();
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y -= height;
+        if self.y < CONTENT_BOTTOM {
+            self.new_page();
+        }
+    }
+
+    fn draw_text(&self, text: &str, style: Style, size: f32, x: f32, baseline: f32) {
+        let (_, font) = self.fonts.get(style.mono);
+        self.layer.set_fill_color(color(style.color));
+        if style.bold {
+            // There is no bold face to embed, so bold text is stroked as well as filled.
+            self.layer.set_outline_color(color(style.color));
+            self.layer.set_outline_thickness(size * 0.04);
+            self.layer.set_text_rendering_mode(TextRenderingMode::FillStroke);
+        }
+        self.layer.use_text(text, size, Mm(x), Mm(baseline), font);
+        if style.bold {
+            self.layer.set_text_rendering_mode(TextRenderingMode::Fill);
+        }
+    }
+
+    fn fill_rect(&self, x0: f32, y0: f32, x1: f32, y1: f32, rgb: [f32; 3]) {
+        self.layer.set_fill_color(color(rgb));
+        self.layer.add_rect(Rect::new(Mm(x0), Mm(y0), Mm(x1), Mm(y1)));
+    }
+
+    fn rule(&self, x0: f32, x1: f32, y: f32, rgb: [f32; 3]) {
+        self.layer.set_outline_color(color(rgb));
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_line(Line {
+            points: vec![(Point::new(Mm(x0), Mm(y)), false), (Point::new(Mm(x1), Mm(y)), false)],
+            is_closed: false,
+        });
+    }
+
+    /// Break `runs` into lines no wider than `width`.
+    fn wrap(&self, runs: &[(String, Style)], size: f32, width: f32) -> Vec<Vec<Fragment>> {
+        let mut lines = vec![Vec::new()];
+        let mut line_width = 0.0;
+        for (text, style) in runs {
+            let text = self.fonts.printable(text, style.mono);
+            for (i, piece) in text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(Vec::new());
+                    line_width = 0.0;
+                }
+                for word in piece.split_inclusive(' ') {
+                    let mut word = word.to_owned();
+                    let mut word_width = self.fonts.width(&word, style.mono, size);
+                    let trimmed_width = self.fonts.width(word.trim_end(), style.mono, size);
+                    if line_width + trimmed_width > width && line_width > 0.0 {
+                        lines.push(Vec::new());
+                        line_width = 0.0;
+                        if word.trim().is_empty() {
+                            continue;
+                        }
+                    }
+                    // A word longer than the whole line is split wherever it runs out of room.
+                    while word_width > width && word.chars().count() > 1 {
+                        let mut split = 0;
+                        let mut taken = 0.0;
+                        for (idx, c) in word.char_indices() {
+                            let w = self.fonts.width(c.encode_utf8(&mut [0; 4]), style.mono, size);
+                            if taken + w > width - line_width && idx > 0 {
+                                break;
+                            }
+                            taken += w;
+                            split = idx + c.len_utf8();
+                        }
+                        let rest = word.split_off(split);
+                        lines.last_mut().unwrap().push(Fragment {
+                            text: word,
+                            style: *style,
+                            width: taken,
+                        });
+                        lines.push(Vec::new());
+                        line_width = 0.0;
+                        word = rest;
+                        word_width = self.fonts.width(&word, style.mono, size);
+                    }
+                    line_width += word_width;
+                    let line = lines.last_mut().unwrap();
+                    match line.last_mut() {
+                        Some(last) if last.style == *style => {
+                            last.text.push_str(&word);
+                            last.width += word_width;
+                        }
+                        _ => line.push(Fragment {
+                            text: word,
+                            style: *style,
+                            width: word_width,
+                        }),
+                    }
+                }
+            }
+        }
+        lines
+    }
+
+    /// Lay out a block of text at `indent`, with `marker` (a bullet or number) before its first line.
+    fn paragraph(&mut self, runs: &[(String, Style)], size: f32, indent: f32, marker: Option<&str>, quote: usize) {
+        if runs.iter().all(|(text, _)| text.trim().is_empty()) && marker.is_none() {
+            return;
+        }
+        let line_height = size * 1.45 * MM_PER_PT;
+        let left = MARGIN + indent;
+        for (i, line) in self.wrap(runs, size, CONTENT_WIDTH - indent).into_iter().enumerate() {
+            self.ensure(line_height);
+            self.y -= line_height;
+            let baseline = self.y + line_height * 0.28;
+            for depth in 0..quote {
+                let x = MARGIN + depth as f32 * INDENT;
+                self.fill_rect(x, self.y, x + 0.8, self.y + line_height, ACCENT);
+            }
+            if let (0, Some(marker)) = (i, marker) {
+                let width = self.fonts.width(marker, false, size);
+                let style = Style { mono: false, bold: false, color: TEXT };
+                self.draw_text(marker, style, size, left - width - 1.5, baseline);
+            }
+            let mut x = left;
+            for fragment in line {
+                self.draw_text(fragment.text.trim_end_matches('\n'), fragment.style, size, x, baseline);
+                x += fragment.width;
+            }
+        }
+    }
+
+    /// Lay out the pending body text, if any.
+    fn flush(&mut self, runs: &mut Vec<(String, Style)>, marker: &mut Option<String>, indent: f32, quote: usize) {
+        if !runs.is_empty() || marker.is_some() {
+            self.paragraph(&std::mem::take(runs), BODY_SIZE, indent, marker.take().as_deref(), quote);
+        }
+    }
+
+    fn code_block(&mut self, code: &str, indent: f32) {
+        let line_height = CODE_SIZE * 1.4 * MM_PER_PT;
+        let style = Style { mono: true, bold: false, color: TEXT };
+        let left = MARGIN + indent;
+        let runs = [(code.trim_end_matches('\n').to_owned(), style)];
+        let lines = self.wrap(&runs, CODE_SIZE, CONTENT_WIDTH - indent - 6.0);
+        let count = lines.len();
+        for (i, line) in lines.into_iter().enumerate() {
+            // The shaded box grows line by line, so it continues cleanly across a page break.
+            let pad_top = if i == 0 { 2.0 } else { 0.0 };
+            let pad_bottom = if i + 1 == count { 2.0 } else { 0.0 };
+            self.ensure(line_height + pad_top + pad_bottom);
+            let top = self.y;
+            self.y -= line_height + pad_top;
+            self.fill_rect(left, self.y - pad_bottom, PAGE_WIDTH - MARGIN, top, CODE_BG);
+            let mut x = left + 3.0;
+            for fragment in line {
+                self.draw_text(&fragment.text, fragment.style, CODE_SIZE, x, self.y + line_height * 0.28);
+                x += fragment.width;
+            }
+            self.y -= pad_bottom;
+        }
+        self.space(3.0);
+    }
+
+    /// Draw an attachment image scaled to fit the page. Returns false if it can't be read.
+    fn image(&mut self, hash: &str, indent: f32) -> bool {
+        let thumbnail = images::thumbnail_path(hash);
+        let path = if thumbnail.exists() { thumbnail } else { attachments::blob_path(hash) };
+        let image = match image::io::Reader::open(&path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(image::ImageError::IoError)
+            .and_then(|reader| reader.decode())
+        {
+            Ok(image) => image,
+            Err(e) => {
+                eprintln!("Error reading image {hash}: {e}");
+                return false;
+            }
+        };
+        let (px_width, px_height) = (image.width() as f32, image.height() as f32);
+        // At most the content width and MAX_IMAGE_HEIGHT, and never enlarged past 150 dpi.
+        let natural = px_width / 150.0 * 25.4;
+        let scale = (CONTENT_WIDTH - indent)
+            .min(natural)
+            .min(MAX_IMAGE_HEIGHT * px_width / px_height)
+            / px_width;
+        let (width, height) = (px_width * scale, px_height * scale);
+        self.ensure(height + 2.0);
+        self.y -= height + 1.0;
+        printpdf::Image::from_dynamic_image(&image).add_to_layer(
+            self.layer.clone(),
+            printpdf::ImageTransform {
+                translate_x: Some(Mm(MARGIN + indent)),
+                translate_y: Some(Mm(self.y)),
+                dpi: Some(px_width / (width / 25.4)),
+                ..Default::default()
+            },
+        );
+        self.space(3.0);
+        true
+    }
+
+    /// The note's title, tags and content.
+    fn note(&mut self, note: &Note, notes: &[Note]) {
+        let title = Style { mono: false, bold: true, color: TEXT };
+        self.paragraph(&[(note.title.clone(), title)], 22.0, 0.0, None, 0);
+        if !note.tags.is_empty() {
+            let tags: Vec<String> = note.tags.iter().map(|t| format!("#{t}")).collect();
+            let style = Style { mono: false, bold: false, color: ACCENT };
+            self.paragraph(&[(tags.join("  "), style)], SMALL_SIZE + 1.0, 0.0, None, 0);
+        }
+        self.space(4.0);
+        self.markdown(&plain_links(&note.content, notes));
+    }
+
+    fn markdown(&mut self, content: &str) {
+        let base = Style { mono: false, bold: false, color: TEXT };
+        let mut runs: Vec<(String, Style)> = Vec::new();
+        let mut bold = 0;
+        let mut link = 0;
+        let mut quote = 0;
+        // Each open list, with the next number for ordered ones.
+        let mut lists: Vec<Option<u64>> = Vec::new();
+        let mut marker: Option<String> = None;
+        let mut code: Option<String> = None;
+        let mut row: Vec<String> = Vec::new();
+        let mut table_head = false;
+        let mut hidden_alt = 0;
+
+        let indent = |lists: &Vec<Option<u64>>, quote: usize| (lists.len() + quote) as f32 * INDENT;
+        let style = |bold: i32, link: i32, quote: usize| Style {
+            mono: false,
+            bold: bold > 0,
+            color: if link > 0 {
+                ACCENT
+            } else if quote > 0 {
+                WEAK
+            } else {
+                TEXT
+            },
+        };
+
+        for event in Parser::new_ext(content, html::markdown_options()) {
+            match event {
+                Event::Start(Tag::Heading { level, .. }) => {
+                    self.space(if level <= HeadingLevel::H2 { 4.0 } else { 2.0 });
+                    bold += 1;
+                }
+                Event::End(TagEnd::Heading(level)) => {
+                    let size = heading_size(level);
+                    self.ensure(size * MM_PER_PT * 3.0);
+                    self.paragraph(&std::mem::take(&mut runs), size, indent(&lists, quote), None, quote);
+                    if level == HeadingLevel::H1 {
+                        self.rule(MARGIN, PAGE_WIDTH - MARGIN, self.y - 0.5, CODE_BG);
+                    }
+                    self.space(2.0);
+                    bold -= 1;
+                }
+                Event::End(TagEnd::Paragraph) => {
+                    self.flush(&mut runs, &mut marker, indent(&lists, quote), quote);
+                    self.space(2.5);
+                }
+                Event::Start(Tag::BlockQuote(_)) => quote += 1,
+                Event::End(TagEnd::BlockQuote(_)) => quote -= 1,
+                Event::Start(Tag::List(start)) => {
+                    self.flush(&mut runs, &mut marker, indent(&lists, quote), quote);
+                    lists.push(start);
+                }
+                Event::End(TagEnd::List(_)) => {
+                    lists.pop();
+                    if lists.is_empty() {
+                        self.space(2.5);
+                    }
+                }
+                Event::Start(Tag::Item) => {
+                    marker = Some(match lists.last_mut() {
+                        Some(Some(number)) => {
+                            *number += 1;
+                            format!("{}.", *number - 1)
+                        }
+                        _ => "•".to_owned(),
+                    });
+                }
+                Event::End(TagEnd::Item) => {
+                    // Tight list items have no paragraph of their own.
+                    self.flush(&mut runs, &mut marker, indent(&lists, quote), quote);
+                    self.space(0.8);
+                }
+                Event::TaskListMarker(done) => marker = Some(if done { "[x]" } else { "[ ]" }.to_owned()),
+                Event::Start(Tag::CodeBlock(_)) => code = Some(String::new()),
+                Event::End(TagEnd::CodeBlock) => {
+                    if let Some(text) = code.take() {
+                        self.flush(&mut runs, &mut marker, indent(&lists, quote), quote);
+                        self.code_block(&text, indent(&lists, quote));
+                    }
+                }
+                Event::Start(Tag::Strong) => bold += 1,
+                Event::End(TagEnd::Strong) => bold -= 1,
+                Event::Start(Tag::Link { .. }) => link += 1,
+                Event::End(TagEnd::Link) => link -= 1,
+                Event::Start(Tag::Image { dest_url, .. }) => {
+                    if let Some(hash) = images::attachment_hash(&dest_url) {
+                        self.flush(&mut runs, &mut marker, indent(&lists, quote), quote);
+                        if self.image(hash, indent(&lists, quote)) {
+                            hidden_alt += 1;
+                            continue;
+                        }
+                    }
+                    // Images that can't be embedded print as their alt text.
+                    runs.push(("[".to_owned(), style(bold, link, quote)));
+                }
+                Event::End(TagEnd::Image) => {
+                    if hidden_alt > 0 {
+                        hidden_alt -= 1;
+                    } else {
+                        runs.push(("]".to_owned(), style(bold, link, quote)));
+                    }
+                }
+                Event::Start(Tag::TableHead) => table_head = true,
+                Event::End(TagEnd::TableCell) => {
+                    row.push(runs.drain(..).map(|(text, _)| text).collect());
+                }
+                Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {
+                    let cells = std::mem::take(&mut row).join("   |   ");
+                    let cell_style = Style { bold: table_head, ..base };
+                    self.paragraph(&[(cells, cell_style)], BODY_SIZE - 1.0, indent(&lists, quote), None, quote);
+                    if table_head {
+                        self.rule(MARGIN + indent(&lists, quote), PAGE_WIDTH - MARGIN, self.y, WEAK);
+                    }
+                    table_head = false;
+                }
+                Event::End(TagEnd::Table) => self.space(2.5),
+                Event::Rule => {
+                    self.space(3.0);
+                    self.rule(MARGIN, PAGE_WIDTH - MARGIN, self.y, WEAK);
+                    self.space(3.0);
+                }
+                Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                    if hidden_alt > 0 {
+                        continue;
+                    }
+                    match &mut code {
+                        Some(code) => code.push_str(&text),
+                        None => runs.push((text.into_string(), style(bold, link, quote))),
+                    }
+                }
+                Event::Code(text) => runs.push((
+                    text.into_string(),
+                    Style { mono: true, bold: false, color: INLINE_CODE },
+                )),
+                Event::SoftBreak => runs.push((" ".to_owned(), style(bold, link, quote))),
+                Event::HardBreak => runs.push(("\n".to_owned(), style(bold, link, quote))),
+                _ => {}
+            }
+        }
+        self.flush(&mut runs, &mut marker, 0.0, 0);
+    }
+
+    /// Draw the header with the note's dates, and "Page n of m", on every page.
+    fn headers_and_footers(&mut self, notes: &[Note]) {
+        let count = self.pages.len();
+        let weak = Style { mono: false, bold: false, color: WEAK };
+        for (number, (page, layer, idx)) in self.pages.clone().into_iter().enumerate() {
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            let note = &notes[idx];
+            let dates = format!("Created {} · Edited {}", format_date(note.created), format_date(note.edited));
+            let dates_width = self.fonts.width(&dates, false, SMALL_SIZE);
+            let mut title = self.fonts.printable(&note.title, false);
+            while self.fonts.width(&title, false, SMALL_SIZE) > CONTENT_WIDTH - dates_width - 8.0 && title.pop().is_some() {}
+            let header = PAGE_HEIGHT - MARGIN + 1.0;
+            self.draw_text(&title, weak, SMALL_SIZE, MARGIN, header);
+            self.draw_text(&dates, weak, SMALL_SIZE, PAGE_WIDTH - MARGIN - dates_width, header);
+            self.rule(MARGIN, PAGE_WIDTH - MARGIN, header - 2.0, CODE_BG);
+            let footer = format!("Page {} of {count}", number + 1);
+            let footer_width = self.fonts.width(&footer, false, SMALL_SIZE);
+            self.draw_text(&footer, weak, SMALL_SIZE, (PAGE_WIDTH - footer_width) / 2.0, MARGIN - 10.0);
+        }
+    }
+}
+
+/// Wiki links print as their title.
+fn plain_links(content: &str, notes: &[Note]) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for link in links::parse_links(content) {
+        out.push_str(&content[last..link.range.start]);
+        let title = links::resolve(&link, notes).map_or(link.title.as_str(), |i| notes[i].title.as_str());
+        out.push_str(title);
+        last = link.range.end;
+    }
+    out.push_str(&content[last..]);
+    out
+}
+
+/// A PDF with each note at `indices` starting on a new page.
+pub fn pdf_document(notes: &[Note], indices: &[usize], title: &str) -> Result<Vec<u8>, String> {
+    let Some(&first) = indices.first() else {
+        return Err("No notes to export".to_owned());
+    };
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+    let mut writer = Writer {
+        doc: &doc,
+        fonts: Fonts::load(&doc)?,
+        layer: doc.get_page(page).get_layer(layer),
+        pages: vec![(page, layer, first)],
+        note: first,
+        y: CONTENT_TOP,
+    };
+    for (n, &i) in indices.iter().enumerate() {
+        writer.note = i;
+        if n > 0 {
+            writer.new_page();
+        }
+        writer.note(&notes[i], notes);
+    }
+    writer.headers_and_footers(notes);
+    drop(writer);
+    doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes() -> Vec<Note> {
+        let mut plans = Note::new(1, "Plans");
+        plans.content = "# Week\nSee [[Groceries]] and [[Missing]].\n\n- [ ] call".to_owned();
+        vec![plans, Note::new(2, "Groceries")]
+    }
+
+    #[test]
+    fn links_print_as_their_title() {
+        let notes = notes();
+        assert_eq!(plain_links(&notes[0].content, &notes), "# Week\nSee Groceries and Missing.\n\n- [ ] call");
+    }
+
+    #[test]
+    fn a_document_needs_notes() {
+        let notes = notes();
+        assert!(pdf_document(&notes, &[], "Notes").is_err());
+        let bytes = pdf_document(&notes, &[0, 1], "Notes").unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+}
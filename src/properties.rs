@@ -0,0 +1,194 @@
+pub use note_core::properties::*;
+
+use note_core::note::Note;
+use eframe::egui;
+use std::cmp::Ordering;
+
+/// Draw the property rows of the note viewer.
+pub fn show_properties(ui: &mut egui::Ui, properties: &Properties) {
+    if properties.is_empty() {
+        return;
+    }
+    egui::Grid::new("viewer_properties").num_columns(2).spacing([16.0, 4.0]).show(ui, |ui| {
+        for (name, value) in properties {
+            ui.label(egui::RichText::new(name).size(13.0).weak());
+            ui.label(egui::RichText::new(value.display()).size(13.0));
+            ui.end_row();
+        }
+    });
+}
+
+/// Editor for a note's properties, plus the inputs for adding a new one.
+pub struct PropertyEditor {
+    new_name: String,
+    new_kind: PropertyKind,
+}
+
+impl Default for PropertyEditor {
+    fn default() -> Self {
+        Self {
+            new_name: String::new(),
+            new_kind: PropertyKind::Text,
+        }
+    }
+}
+
+impl PropertyEditor {
+    pub fn show(&mut self, ui: &mut egui::Ui, properties: &mut Properties, notes: &[Note]) {
+        let mut remove = None;
+        egui::Grid::new("editor_properties").num_columns(3).spacing([12.0, 6.0]).show(ui, |ui| {
+            for (name, value) in properties.iter_mut() {
+                ui.label(name.as_str());
+                match value {
+                    PropertyValue::Text(text) => {
+                        ui.text_edit_singleline(text);
+                    }
+                    PropertyValue::Number(number) => {
+                        ui.add(egui::DragValue::new(number).speed(0.1));
+                    }
+                    PropertyValue::Date(date) => {
+                        ui.add(egui_extras::DatePickerButton::new(date).id_source(name.as_str()));
+                    }
+                    PropertyValue::Checkbox(checked) => {
+                        ui.checkbox(checked, "");
+                    }
+                    PropertyValue::Select(selected) => {
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source(("select_property", name.as_str()))
+                                .selected_text(selected.as_str())
+                                .show_ui(ui, |ui| {
+                                    for option in select_options(notes, name) {
+                                        ui.selectable_value(selected, option.clone(), option);
+                                    }
+                                });
+                            ui.add(egui::TextEdit::singleline(selected).hint_text("New option").desired_width(100.0));
+                        });
+                    }
+                }
+                if ui.small_button("✖").on_hover_text("Remove property").clicked() {
+                    remove = Some(name.clone());
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(name) = remove {
+            properties.remove(&name);
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_name)
+                    .hint_text("New property, e.g. priority")
+                    .desired_width(180.0),
+            );
+            egui::ComboBox::from_id_source("new_property_kind")
+                .selected_text(self.new_kind.label())
+                .show_ui(ui, |ui| {
+                    for kind in PropertyKind::ALL {
+                        ui.selectable_value(&mut self.new_kind, kind, kind.label());
+                    }
+                });
+            let name = self.new_name.trim().to_owned();
+            if ui
+                .add_enabled(!name.is_empty() && !properties.contains_key(&name), egui::Button::new("➕ Add"))
+                .clicked()
+            {
+                properties.insert(name, self.new_kind.empty_value());
+                self.new_name.clear();
+            }
+        });
+    }
+}
+
+/// Sidebar sorting and filtering by property.
+#[derive(Default)]
+pub struct PropertyQuery {
+    pub sort_by: Option<String>,
+    pub descending: bool,
+    pub filter_by: Option<String>,
+    pub filter_value: String,
+}
+
+impl PropertyQuery {
+    pub fn matches(&self, note: &Note) -> bool {
+        match &self.filter_by {
+            Some(name) => note
+                .properties
+                .get(name)
+                .is_some_and(|value| value.matches(&self.filter_value)),
+            None => true,
+        }
+    }
+
+    /// Sort note indices by the chosen property; notes without it go last.
+    pub fn sort(&self, notes: &[Note], indices: &mut [usize]) {
+        let Some(name) = &self.sort_by else {
+            return;
+        };
+        indices.sort_by(|&a, &b| {
+            match (notes[a].properties.get(name), notes[b].properties.get(name)) {
+                (Some(x), Some(y)) => {
+                    let order = x.compare(y);
+                    if self.descending {
+                        order.reverse()
+                    } else {
+                        order
+                    }
+                }
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        });
+    }
+
+    /// Draw the sort/filter controls under the sidebar search bar.
+    pub fn controls(&mut self, ui: &mut egui::Ui, notes: &[Note]) {
+        let names = all_names(notes);
+        // Drop a sort or filter on a property no note has any more, so it can't hide notes invisibly.
+        if self.sort_by.as_ref().is_some_and(|n| !names.contains(n)) {
+            self.sort_by = None;
+        }
+        if self.filter_by.as_ref().is_some_and(|n| !names.contains(n)) {
+            self.filter_by = None;
+        }
+        if names.is_empty() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("sidebar_sort")
+                .width(110.0)
+                .selected_text(format!("Sort: {}", self.sort_by.as_deref().unwrap_or("default")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.sort_by, None, "default");
+                    for name in &names {
+                        ui.selectable_value(&mut self.sort_by, Some(name.clone()), name);
+                    }
+                });
+            if self.sort_by.is_some() {
+                let arrow = if self.descending { "⬇" } else { "⬆" };
+                if ui.small_button(arrow).on_hover_text("Toggle sort direction").clicked() {
+                    self.descending = !self.descending;
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("sidebar_property_filter")
+                .width(110.0)
+                .selected_text(format!("Filter: {}", self.filter_by.as_deref().unwrap_or("none")))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter_by, None, "none");
+                    for name in &names {
+                        ui.selectable_value(&mut self.filter_by, Some(name.clone()), name);
+                    }
+                });
+            if self.filter_by.is_some() {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.filter_value)
+                        .hint_text("value")
+                        .desired_width(90.0),
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,117 @@
+pub use note_core::records::*;
+
+use note_core::note::Note;
+use eframe::egui;
+use std::fs;
+use std::path::Path;
+
+/// The JSON Lines / CSV part of the import window.
+pub struct RecordsImport {
+    pub match_on: MatchOn,
+    pub on_match: OnMatch,
+    records: Vec<Record>,
+    planned: Option<Result<Vec<Action>, Vec<RowError>>>,
+}
+
+impl Default for RecordsImport {
+    fn default() -> Self {
+        Self {
+            match_on: MatchOn::Id,
+            on_match: OnMatch::Newer,
+            records: Vec::new(),
+            planned: None,
+        }
+    }
+}
+
+impl RecordsImport {
+    /// Read and check the file at `path`. Errors reading the file itself are returned; problems
+    /// with its contents are listed by [`Self::preview`].
+    pub fn scan(&mut self, path: &Path, notes: &[Note]) -> Result<(), String> {
+        self.records.clear();
+        self.planned = None;
+        let text = fs::read_to_string(path).map_err(|e| format!("Can't read {}: {e}", path.display()))?;
+        match parse(&text, RecordFormat::from_path(path)) {
+            Ok(records) if records.is_empty() => return Err("No notes found".to_owned()),
+            Ok(records) => {
+                self.records = records;
+                self.replan(notes);
+            }
+            Err(errors) => self.planned = Some(Err(errors)),
+        }
+        Ok(())
+    }
+
+    fn replan(&mut self, notes: &[Note]) {
+        self.planned = Some(plan(self.records.clone(), notes, self.match_on, self.on_match));
+    }
+
+    pub fn is_scanned(&self) -> bool {
+        self.planned.is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+        self.planned = None;
+    }
+
+    /// Merge rule options. Changing them re-checks the scanned records.
+    pub fn options(&mut self, ui: &mut egui::Ui, notes: &[Note]) {
+        let before = (self.match_on, self.on_match);
+        ui.horizontal(|ui| {
+            ui.label("Match notes by");
+            ui.radio_value(&mut self.match_on, MatchOn::Id, "ID");
+            ui.radio_value(&mut self.match_on, MatchOn::Title, "Title");
+        });
+        ui.horizontal(|ui| {
+            ui.label("When a note matches");
+            for on_match in OnMatch::ALL {
+                ui.radio_value(&mut self.on_match, on_match, on_match.label());
+            }
+        });
+        if (self.match_on, self.on_match) != before && !self.records.is_empty() {
+            self.replan(notes);
+        }
+    }
+
+    /// What the import will do, or every problem found. Once Import is clicked, returns a
+    /// summary and a copy of `notes` with the records merged in.
+    pub fn preview(&mut self, ui: &mut egui::Ui, notes: &[Note]) -> Option<(String, Vec<Note>)> {
+        let mut result = None;
+        match &self.planned {
+            Some(Ok(actions)) => {
+                ui.label(MergeSummary::of(actions).message());
+                let count = actions.iter().filter(|a| !matches!(a, Action::Skip)).count();
+                let (mut import, mut cancel) = (false, false);
+                ui.horizontal(|ui| {
+                    import = ui.add_enabled(count > 0, egui::Button::new(format!("📥 Import {count} note(s)"))).clicked();
+                    cancel = ui.button("Cancel").clicked();
+                });
+                if import {
+                    // Notes may have changed since the preview was drawn, so check again first.
+                    self.replan(notes);
+                    if let Some(Ok(actions)) = self.planned.take() {
+                        let mut merged = notes.to_vec();
+                        result = Some((apply(actions, &mut merged).message(), merged));
+                        self.records.clear();
+                    }
+                } else if cancel {
+                    self.clear();
+                }
+            }
+            Some(Err(errors)) => {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 80, 80),
+                    format!("{} problem(s) found; nothing was imported", errors.len()),
+                );
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for error in errors {
+                        ui.label(egui::RichText::new(error.to_string()).size(12.0));
+                    }
+                });
+            }
+            None => {}
+        }
+        result
+    }
+}
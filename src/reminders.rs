@@ -0,0 +1,222 @@
+pub use note_core::note::{format_due, parse_due};
+use note_core::note::Note;
+use note_core::storage::Storage;
+use crate::tasks;
+use eframe::egui;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const REMINDERS_FILE: &str = "reminders.json";
+/// How far ahead the Home page looks for upcoming items.
+pub const UPCOMING_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+fn task_due_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"@due\(([^)]*)\)").unwrap())
+}
+
+/// Due date of a task written as `- [ ] text @due(2024-05-01 14:00)`.
+pub fn task_due(text: &str) -> Option<u64> {
+    task_due_regex().captures(text).and_then(|caps| parse_due(&caps[1]))
+}
+
+/// Task text without its `@due(...)` marker.
+pub fn strip_due(text: &str) -> String {
+    task_due_regex().replace_all(text, "").trim().to_owned()
+}
+
+/// A note or open task with a due date.
+pub struct DueItem {
+    pub note_idx: usize,
+    /// Identifies the item and its due date in the persisted reminder state,
+    /// so changing the due date arms a fresh reminder. Starts with the kind and note ID, and
+    /// holds no note text since `reminders.json` is never encrypted.
+    pub key: String,
+    pub label: String,
+    pub due: u64,
+}
+
+/// Every dated note and open task, soonest first.
+pub fn due_items(notes: &[Note]) -> Vec<DueItem> {
+    let mut items = Vec::new();
+    for (note_idx, note) in notes.iter().enumerate() {
+        if let Some(due) = note.due {
+            items.push(DueItem {
+                note_idx,
+                key: format!("note:{}:{due}", note.id),
+                label: note.title.clone(),
+                due,
+            });
+        }
+        // Tasks are told apart by their place among the note's tasks due at the same time,
+        // counting checked ones so checking a task doesn't re-key the others.
+        let mut same_due: HashMap<u64, usize> = HashMap::new();
+        for task in tasks::parse_tasks(&note.content) {
+            let Some(due) = task_due(task.text) else {
+                continue;
+            };
+            let n = same_due.entry(due).or_default();
+            *n += 1;
+            if !task.checked {
+                let label = strip_due(task.text);
+                items.push(DueItem {
+                    note_idx,
+                    key: format!("task:{}:{due}:{n}", note.id),
+                    label: format!("{label} ({})", note.title),
+                    due,
+                });
+            }
+        }
+    }
+    items.sort_by_key(|i| i.due);
+    items
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ReminderEntry {
+    #[serde(default)]
+    dismissed: bool,
+    #[serde(default)]
+    snoozed_until: Option<u64>,
+}
+
+/// Reminder scheduler. Dismissals and snoozes are saved to `reminders.json`,
+/// so reminders missed while the app was closed fire on the next launch.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Reminders {
+    entries: HashMap<String, ReminderEntry>,
+}
+
+pub enum ReminderAction {
+    OpenNote(usize),
+}
+
+impl Reminders {
+    pub fn load() -> Self {
+        Storage::load_json(REMINDERS_FILE)
+    }
+
+    fn save(&self) {
+        Storage::save_json(REMINDERS_FILE, self);
+    }
+
+    fn is_firing(&self, item: &DueItem, now: u64) -> bool {
+        if item.due > now {
+            return false;
+        }
+        match self.entries.get(&item.key) {
+            Some(entry) => !entry.dismissed && entry.snoozed_until.is_none_or(|until| until <= now),
+            None => true,
+        }
+    }
+
+    fn snooze(&mut self, key: &str, until: u64) {
+        self.entries.entry(key.to_owned()).or_default().snoozed_until = Some(until);
+        self.save();
+    }
+
+    fn dismiss(&mut self, key: &str) {
+        self.entries.entry(key.to_owned()).or_default().dismissed = true;
+        self.save();
+    }
+
+    /// Forget state for items that no longer exist, e.g. completed tasks or changed due dates.
+    /// The tasks of a locked note can't be read, so its state is kept until it is unlocked.
+    fn prune(&mut self, items: &[DueItem], notes: &[Note]) {
+        let before = self.entries.len();
+        let sealed = |key: &str| {
+            let id = key.split(':').nth(1).and_then(|id| id.parse::<u64>().ok());
+            notes.iter().any(|n| Some(n.id) == id && n.is_sealed())
+        };
+        self.entries.retain(|key, _| items.iter().any(|i| &i.key == key) || sealed(key));
+        if self.entries.len() != before {
+            self.save();
+        }
+    }
+
+    /// Check for due reminders and draw a notification for each one firing.
+    pub fn show(&mut self, ctx: &egui::Context, notes: &[Note]) -> Option<ReminderAction> {
+        // Keep checking while the app is idle.
+        ctx.request_repaint_after(Duration::from_secs(30));
+
+        let now = Note::now_ts();
+        let items = due_items(notes);
+        self.prune(&items, notes);
+        let firing: Vec<&DueItem> = items.iter().filter(|i| self.is_firing(i, now)).collect();
+        if firing.is_empty() {
+            return None;
+        }
+
+        let mut action = None;
+        let mut snooze = None;
+        let mut dismiss = None;
+        egui::Area::new(egui::Id::new("reminder_notifications"))
+            .order(egui::Order::Foreground)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -16.0])
+            .show(ctx, |ui| {
+                for item in firing.iter().take(5) {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_width(300.0);
+                        ui.label(egui::RichText::new("⏰ Reminder").strong());
+                        ui.label(&item.label);
+                        ui.label(
+                            egui::RichText::new(format!("Due {}", format_due(item.due)))
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(255, 80, 80)),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button("Open").clicked() {
+                                action = Some(ReminderAction::OpenNote(item.note_idx));
+                                dismiss = Some(item.key.clone());
+                            }
+                            if ui.button("Snooze 10 min").clicked() {
+                                snooze = Some((item.key.clone(), now + 10 * 60));
+                            }
+                            if ui.button("Snooze 1 h").clicked() {
+                                snooze = Some((item.key.clone(), now + 60 * 60));
+                            }
+                            if ui.button("Dismiss").clicked() {
+                                dismiss = Some(item.key.clone());
+                            }
+                        });
+                    });
+                    ui.add_space(6.0);
+                }
+                if firing.len() > 5 {
+                    ui.label(egui::RichText::new(format!("+{} more reminders", firing.len() - 5)).weak());
+                }
+            });
+        if let Some((key, until)) = snooze {
+            self.snooze(&key, until);
+        }
+        if let Some(key) = dismiss {
+            self.dismiss(&key);
+        }
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_keys_count_tasks_due_together_and_hold_no_text() {
+        let mut note = Note::new(7, "Secret plans");
+        note.content = "- [x] call bank @due(2030-01-02)\n- [ ] pay rent @due(2030-01-02)\n- [ ] other @due(2030-01-03)".to_owned();
+        let items = due_items(&[note.clone()]);
+        let rent = parse_due("2030-01-02").unwrap();
+        let other = parse_due("2030-01-03").unwrap();
+        let keys: Vec<&str> = items.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(keys, vec![format!("task:7:{rent}:2"), format!("task:7:{other}:1")]);
+        assert_eq!(items[0].label, "pay rent (Secret plans)");
+
+        // Checking off the first task leaves the other keys as they were.
+        note.content = note.content.replacen("[ ] pay", "[x] pay", 1);
+        let keys: Vec<String> = due_items(&[note]).into_iter().map(|i| i.key).collect();
+        assert_eq!(keys, vec![format!("task:7:{other}:1")]);
+    }
+}
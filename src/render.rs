@@ -0,0 +1,154 @@
+use crate::attachments::Attachment;
+use crate::images::{self, ImageRef};
+use note_core::links::{parse_links, resolve, Link};
+use note_core::note::Note;
+use crate::tasks;
+use eframe::egui;
+
+/// What the user clicked in rendered note content.
+pub enum ContentAction {
+    OpenNote(usize),
+    /// A task checkbox on this line of the content was toggled.
+    ToggleTask(usize),
+    /// An inline image was clicked; open the full-size attachment.
+    OpenAttachment(Attachment),
+}
+
+/// Largest height an inline image is drawn at; wider images are scaled to the content width.
+const MAX_IMAGE_HEIGHT: f32 = 480.0;
+
+enum Span {
+    Link(Link),
+    Image(ImageRef),
+}
+
+impl Span {
+    fn range(&self) -> std::ops::Range<usize> {
+        match self {
+            Span::Link(link) => link.range.clone(),
+            Span::Image(image) => image.range.clone(),
+        }
+    }
+}
+
+fn text_run(text: &str, font: &egui::FontId, done: bool) -> egui::RichText {
+    let text = egui::RichText::new(text).font(font.clone());
+    if done {
+        text.strikethrough().weak()
+    } else {
+        text
+    }
+}
+
+/// Render one line of text, turning `[[links]]` into clickable links and `![images](...)` into images.
+fn inline(
+    ui: &mut egui::Ui,
+    line: &str,
+    notes: &[Note],
+    font: &egui::FontId,
+    done: bool,
+    action: &mut Option<ContentAction>,
+) {
+    if line.is_empty() {
+        // Keep blank lines visible in the wrapped layout.
+        ui.label(text_run(" ", font, done));
+        return;
+    }
+    let mut spans: Vec<Span> = parse_links(line).into_iter().map(Span::Link).collect();
+    spans.extend(images::parse_images(line).into_iter().map(Span::Image));
+    spans.sort_by_key(|span| span.range().start);
+    let mut last = 0;
+    for span in spans {
+        let range = span.range();
+        if range.start < last {
+            // Overlaps the previous span, e.g. a link inside image alt text.
+            continue;
+        }
+        if range.start > last {
+            ui.label(text_run(&line[last..range.start], font, done));
+        }
+        match span {
+            Span::Link(link) => link_run(ui, &link, notes, font, action),
+            Span::Image(image) => image_run(ui, &image, notes, action),
+        }
+        last = range.end;
+    }
+    if last < line.len() {
+        ui.label(text_run(&line[last..], font, done));
+    }
+}
+
+fn link_run(ui: &mut egui::Ui, link: &Link, notes: &[Note], font: &egui::FontId, action: &mut Option<ContentAction>) {
+    match resolve(link, notes) {
+        Some(idx) => {
+            let response = ui
+                .link(egui::RichText::new(&notes[idx].title).font(font.clone()))
+                .on_hover_text("Open linked note");
+            if response.clicked() {
+                *action = Some(ContentAction::OpenNote(idx));
+            }
+        }
+        None => {
+            ui.label(
+                egui::RichText::new(&link.title)
+                    .font(font.clone())
+                    .color(egui::Color32::from_rgb(255, 80, 80))
+                    .underline(),
+            )
+            .on_hover_text("No note with this title");
+        }
+    }
+}
+
+fn image_run(ui: &mut egui::Ui, image: &ImageRef, notes: &[Note], action: &mut Option<ContentAction>) {
+    let attachment = images::attachment_hash(&image.target).and_then(|hash| {
+        notes
+            .iter()
+            .flat_map(|n| &n.attachments)
+            .find(|a| a.hash == hash)
+    });
+    let response = ui.add(
+        egui::Image::new(images::uri_for(&image.target))
+            .max_width(ui.max_rect().width())
+            .max_height(MAX_IMAGE_HEIGHT)
+            .rounding(egui::Rounding::same(4.0))
+            .sense(egui::Sense::click()),
+    );
+    let response = if image.alt.is_empty() {
+        response
+    } else {
+        response.on_hover_text(&image.alt)
+    };
+    if let Some(attachment) = attachment {
+        if response.on_hover_cursor(egui::CursorIcon::PointingHand).clicked() {
+            *action = Some(ContentAction::OpenAttachment(attachment.clone()));
+        }
+    }
+}
+
+/// Render note content for the viewer: `[[links]]` become clickable links,
+/// `- [ ]` task items become checkboxes and `![alt](attachment:...)` images are shown inline.
+pub fn show_content(ui: &mut egui::Ui, content: &str, notes: &[Note]) -> Option<ContentAction> {
+    let font = egui::FontId::proportional(17.0);
+    let mut action = None;
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 0.0;
+        for (line_no, line) in content.split('\n').enumerate() {
+            if line_no > 0 {
+                ui.end_row();
+            }
+            match tasks::parse_line(line, line_no) {
+                Some(task) => {
+                    ui.add_space(task.indent as f32 * 8.0);
+                    let mut checked = task.checked;
+                    if ui.checkbox(&mut checked, "").changed() {
+                        action = Some(ContentAction::ToggleTask(line_no));
+                    }
+                    inline(ui, task.text, notes, &font, task.checked, &mut action);
+                }
+                None => inline(ui, line, notes, &font, false, &mut action),
+            }
+        }
+    });
+    action
+}
@@ -0,0 +1,186 @@
+use crate::attachments;
+use crate::daily;
+use crate::encryption::EncryptionPanel;
+use note_core::history::History;
+use note_core::note::Note;
+use note_core::storage::Storage;
+use crate::templates;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// User preferences persisted next to the notes file.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// `strftime`-style format used for daily note titles.
+    pub daily_title_format: String,
+    /// ID of the template note used for new daily notes. Not its title, as this file is
+    /// never encrypted.
+    #[serde(rename = "daily_template_id")]
+    pub daily_template: Option<u64>,
+    /// Template title from settings saved before IDs were used, read once and then replaced.
+    #[serde(rename = "daily_template", skip_serializing)]
+    pub old_daily_template: String,
+    /// Board columns, in order. Each is a value of `Note::status`.
+    pub board_columns: Vec<String>,
+    /// Minutes without input before an encrypted store locks itself; 0 for never.
+    pub auto_lock_minutes: u32,
+    /// Minutes locked notes stay readable after unlocking; 0 for the rest of the session.
+    pub note_unlock_minutes: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            daily_title_format: "%Y-%m-%d".to_owned(),
+            daily_template: None,
+            old_daily_template: String::new(),
+            board_columns: vec!["To Do".to_owned(), "In Progress".to_owned(), "Done".to_owned()],
+            auto_lock_minutes: 10,
+            note_unlock_minutes: 15,
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from disk, falling back to defaults if missing or invalid.
+    pub fn load() -> Self {
+        Storage::load_json(SETTINGS_FILE)
+    }
+
+    pub fn save(&self) {
+        Storage::save_json(SETTINGS_FILE, self);
+    }
+
+    /// Draw the settings window. Changes are saved as soon as they are made.
+    pub fn show_window(
+        &mut self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        notes: &[Note],
+        history: &History,
+        encryption: &mut EncryptionPanel,
+    ) {
+        let before = self.clone();
+        if !self.old_daily_template.is_empty() {
+            let title = std::mem::take(&mut self.old_daily_template);
+            self.daily_template = templates::templates(notes)
+                .into_iter()
+                .find(|&i| notes[i].title == title)
+                .map(|i| notes[i].id);
+        }
+        egui::Window::new("⚙ Settings")
+            .open(open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                ui.heading(egui::RichText::new("Daily notes").size(18.0).strong());
+                egui::Grid::new("settings_daily").num_columns(2).show(ui, |ui| {
+                    ui.label("Title format");
+                    ui.text_edit_singleline(&mut self.daily_title_format)
+                        .on_hover_text("strftime format, e.g. %Y-%m-%d or %A, %B %d %Y");
+                    ui.end_row();
+                    if !daily::is_readable_format(&self.daily_title_format) {
+                        ui.label("");
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 165, 0),
+                            "⚠ Titles in this format can't be read back as dates; %Y-%m-%d is used",
+                        );
+                        ui.end_row();
+                    }
+                    ui.label("Template");
+                    let selected = self
+                        .daily_template
+                        .and_then(|id| notes.iter().find(|n| n.id == id))
+                        .map_or("None", |n| n.title.as_str());
+                    egui::ComboBox::from_id_source("settings_daily_template")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.daily_template, None, "None");
+                            for idx in templates::templates(notes) {
+                                let note = &notes[idx];
+                                ui.selectable_value(&mut self.daily_template, Some(note.id), &note.title);
+                            }
+                        });
+                    ui.end_row();
+                });
+
+                ui.add_space(10.0);
+                ui.heading(egui::RichText::new("Board columns").size(18.0).strong());
+                let mut remove = None;
+                let mut move_up = None;
+                let count = self.board_columns.len();
+                for (i, column) in self.board_columns.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(column);
+                        if ui.add_enabled(i > 0, egui::Button::new("⬆")).clicked() {
+                            move_up = Some(i);
+                        }
+                        if ui.add_enabled(i + 1 < count, egui::Button::new("⬇")).clicked() {
+                            move_up = Some(i + 1);
+                        }
+                        if ui.button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = move_up {
+                    self.board_columns.swap(i - 1, i);
+                }
+                if let Some(i) = remove {
+                    self.board_columns.remove(i);
+                }
+                if ui.button("➕ Add column").clicked() {
+                    self.board_columns.push(format!("Column {}", count + 1));
+                }
+
+                ui.add_space(10.0);
+                ui.heading(egui::RichText::new("Data").size(18.0).strong());
+                let status_id = egui::Id::new("settings_data_status");
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("🗄 Back up now")
+                        .on_hover_text("Copy notes, settings and attachments into the backups folder")
+                        .clicked()
+                    {
+                        let status = match Storage::backup() {
+                            Ok(dir) => format!("Backed up to {}", dir.display()),
+                            Err(e) => format!("Backup failed: {e}"),
+                        };
+                        ui.data_mut(|d| d.insert_temp(status_id, status));
+                    }
+                    if ui
+                        .button("🧹 Clean up attachments")
+                        .on_hover_text("Delete stored files no note refers to")
+                        .clicked()
+                    {
+                        // Files of notes that undo can bring back are kept too.
+                        let removed = attachments::collect_garbage(notes.iter().chain(history.notes()));
+                        ui.data_mut(|d| d.insert_temp(status_id, format!("Removed {removed} unused file(s)")));
+                    }
+                });
+                if let Some(status) = ui.data(|d| d.get_temp::<String>(status_id)) {
+                    ui.label(egui::RichText::new(status).size(13.0).weak());
+                }
+
+                ui.add_space(10.0);
+                ui.heading(egui::RichText::new("Encryption").size(18.0).strong());
+                encryption.show(ui, notes, &mut self.auto_lock_minutes);
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    ui.label("Locked notes stay unlocked for");
+                    ui.add(
+                        egui::DragValue::new(&mut self.note_unlock_minutes)
+                            .clamp_range(0..=1440)
+                            .suffix(" min"),
+                    );
+                })
+                .response
+                .on_hover_text("0 keeps them unlocked until the app closes");
+            });
+        if *self != before {
+            self.save();
+        }
+    }
+}
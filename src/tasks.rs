@@ -0,0 +1,177 @@
+use note_core::note::Note;
+use eframe::egui;
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+/// A Markdown task item such as `- [ ] write report` or `  * [x] done`.
+pub struct Task<'a> {
+    /// Zero-based line number within the note content.
+    pub line: usize,
+    pub indent: usize,
+    pub checked: bool,
+    pub text: &'a str,
+}
+
+fn task_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(\s*)[-*+] \[([ xX])\](?: (.*))?$").unwrap())
+}
+
+/// Parse a single line as a task item.
+pub fn parse_line(line: &str, line_no: usize) -> Option<Task<'_>> {
+    let caps = task_regex().captures(line)?;
+    Some(Task {
+        line: line_no,
+        indent: caps[1].chars().count(),
+        checked: &caps[2] != " ",
+        text: caps.get(3).map_or("", |m| m.as_str()),
+    })
+}
+
+/// Every task item in `content`, in order.
+pub fn parse_tasks(content: &str) -> Vec<Task<'_>> {
+    content
+        .split('\n')
+        .enumerate()
+        .filter_map(|(i, line)| parse_line(line, i))
+        .collect()
+}
+
+/// Flip the checkbox of the task on `line`. Returns `None` if that line is not a task.
+pub fn toggle(content: &str, line: usize) -> Option<String> {
+    let mut lines: Vec<String> = content.split('\n').map(str::to_owned).collect();
+    let target = lines.get_mut(line)?;
+    let caps = task_regex().captures(target)?;
+    let mark = caps.get(2).unwrap().range();
+    let new_mark = if &caps[2] == " " { "x" } else { " " };
+    target.replace_range(mark, new_mark);
+    Some(lines.join("\n"))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum StatusFilter {
+    Open,
+    Done,
+    All,
+}
+
+pub enum TasksAction {
+    OpenNote(usize),
+    Toggle { note: usize, line: usize },
+}
+
+/// The aggregated "Tasks" view listing task items from every note.
+pub struct TasksView {
+    pub status: StatusFilter,
+    pub search: String,
+    pub tag: Option<String>,
+}
+
+impl Default for TasksView {
+    fn default() -> Self {
+        Self {
+            status: StatusFilter::Open,
+            search: String::new(),
+            tag: None,
+        }
+    }
+}
+
+impl TasksView {
+    pub fn show(&mut self, ui: &mut egui::Ui, notes: &[Note]) -> Option<TasksAction> {
+        let mut action = None;
+        let all_tags: BTreeSet<&str> = notes.iter().flat_map(|n| n.tags.iter().map(String::as_str)).collect();
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.status, StatusFilter::Open, "Open");
+            ui.selectable_value(&mut self.status, StatusFilter::Done, "Done");
+            ui.selectable_value(&mut self.status, StatusFilter::All, "All");
+            ui.separator();
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search)
+                    .hint_text("Filter tasks...")
+                    .desired_width(180.0),
+            );
+            egui::ComboBox::from_id_source("tasks_tag_filter")
+                .selected_text(self.tag.as_deref().unwrap_or("Any tag"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.tag, None, "Any tag");
+                    for tag in &all_tags {
+                        ui.selectable_value(&mut self.tag, Some((*tag).to_owned()), *tag);
+                    }
+                });
+        });
+        ui.add_space(8.0);
+
+        let search = self.search.to_lowercase();
+        let mut shown = 0;
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            for (idx, note) in notes.iter().enumerate() {
+                if self.tag.as_ref().is_some_and(|t| !note.has_tag(t)) {
+                    continue;
+                }
+                let tasks: Vec<Task> = parse_tasks(&note.content)
+                    .into_iter()
+                    .filter(|t| match self.status {
+                        StatusFilter::Open => !t.checked,
+                        StatusFilter::Done => t.checked,
+                        StatusFilter::All => true,
+                    })
+                    .filter(|t| search.is_empty() || t.text.to_lowercase().contains(&search))
+                    .collect();
+                if tasks.is_empty() {
+                    continue;
+                }
+                shown += tasks.len();
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&note.title).size(16.0).strong());
+                    if ui.small_button("Open ➡").on_hover_text("Jump to this note").clicked() {
+                        action = Some(TasksAction::OpenNote(idx));
+                    }
+                });
+                for task in tasks {
+                    ui.horizontal(|ui| {
+                        ui.add_space(12.0 + task.indent as f32 * 8.0);
+                        let mut checked = task.checked;
+                        let mut text = egui::RichText::new(task.text).size(15.0);
+                        if checked {
+                            text = text.strikethrough().weak();
+                        }
+                        if ui.checkbox(&mut checked, text).changed() {
+                            action = Some(TasksAction::Toggle { note: idx, line: task.line });
+                        }
+                    });
+                }
+                ui.add_space(8.0);
+            }
+            if shown == 0 {
+                ui.label(egui::RichText::new("No tasks match.").italics().weak());
+            }
+        });
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tasks_are_found_with_their_line_and_state() {
+        let content = "Shopping\n- [ ] milk\n  * [x] eggs\n- not a task\n+ [X]";
+        let tasks = parse_tasks(content);
+        assert_eq!(tasks.len(), 3);
+        assert_eq!((tasks[0].line, tasks[0].indent, tasks[0].checked, tasks[0].text), (1, 0, false, "milk"));
+        assert_eq!((tasks[1].line, tasks[1].indent, tasks[1].checked), (2, 2, true));
+        assert_eq!((tasks[2].checked, tasks[2].text), (true, ""));
+    }
+
+    #[test]
+    fn toggling_flips_only_that_checkbox() {
+        let content = "- [ ] milk\n- [x] eggs";
+        assert_eq!(toggle(content, 0).as_deref(), Some("- [x] milk\n- [x] eggs"));
+        assert_eq!(toggle(content, 1).as_deref(), Some("- [ ] milk\n- [ ] eggs"));
+        assert_eq!(toggle(content, 2), None);
+        assert_eq!(toggle("plain text", 0), None);
+    }
+}
@@ -0,0 +1,133 @@
+use note_core::note::Note;
+use chrono::{DateTime, Local};
+use eframe::egui;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Notes carrying this tag are offered as templates.
+pub const TEMPLATE_TAG: &str = "template";
+
+/// Placeholders filled in automatically; anything else is asked for when the template is used.
+const BUILT_INS: [&str; 5] = ["title", "date", "time", "datetime", "weekday"];
+
+fn placeholder_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap())
+}
+
+/// Indices of all template notes, sorted by title.
+pub fn templates(notes: &[Note]) -> Vec<usize> {
+    let mut found: Vec<usize> = (0..notes.len()).filter(|&i| notes[i].has_tag(TEMPLATE_TAG)).collect();
+    found.sort_by_key(|&i| notes[i].title.to_lowercase());
+    found
+}
+
+/// Names of the custom `{{field}}` placeholders in `text`, in order of first use.
+pub fn custom_fields(text: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    for caps in placeholder_regex().captures_iter(text) {
+        let name = caps[1].to_owned();
+        if !BUILT_INS.contains(&name.to_lowercase().as_str()) && !fields.contains(&name) {
+            fields.push(name);
+        }
+    }
+    fields
+}
+
+/// Replace every placeholder in `text`. Unknown fields not present in `fields` are left as they are.
+pub fn expand(text: &str, title: &str, fields: &[(String, String)], now: DateTime<Local>) -> String {
+    placeholder_regex()
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            match name.to_lowercase().as_str() {
+                "title" => title.to_owned(),
+                "date" => now.format("%Y-%m-%d").to_string(),
+                "time" => now.format("%H:%M").to_string(),
+                "datetime" => now.format("%Y-%m-%d %H:%M").to_string(),
+                "weekday" => now.format("%A").to_string(),
+                _ => fields
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_else(|| caps[0].to_owned()),
+            }
+        })
+        .into_owned()
+}
+
+/// A note ready to be opened in the editor.
+pub struct Draft {
+    pub title: String,
+    pub content: String,
+    pub tags: Vec<String>,
+}
+
+/// Expand a template note's content into a draft with the given title.
+pub fn instantiate(template: &Note, title: &str, fields: &[(String, String)], now: DateTime<Local>) -> Draft {
+    Draft {
+        title: title.to_owned(),
+        content: expand(&template.content, title, fields, now),
+        tags: template
+            .tags
+            .iter()
+            .filter(|t| !t.eq_ignore_ascii_case(TEMPLATE_TAG))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// The "New from template" window asking for the title and any custom fields.
+#[derive(Default)]
+pub struct TemplateDialog {
+    template: Option<usize>,
+    title: String,
+    fields: Vec<(String, String)>,
+}
+
+impl TemplateDialog {
+    pub fn open(&mut self, notes: &[Note], template: usize) {
+        let note = &notes[template];
+        self.template = Some(template);
+        self.title = expand(&note.title, "", &[], Local::now()).trim().to_owned();
+        self.fields = custom_fields(&format!("{}\n{}", note.title, note.content))
+            .into_iter()
+            .map(|name| (name, String::new()))
+            .collect();
+    }
+
+    /// Draw the window. Returns the draft once the user confirms.
+    pub fn show(&mut self, ctx: &egui::Context, notes: &[Note]) -> Option<Draft> {
+        let template = self.template.filter(|&i| i < notes.len())?;
+        let mut open = true;
+        let mut draft = None;
+        egui::Window::new(format!("📄 New from \"{}\"", notes[template].title))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("template_fields").num_columns(2).show(ui, |ui| {
+                    ui.label("Title");
+                    ui.text_edit_singleline(&mut self.title);
+                    ui.end_row();
+                    for (name, value) in &mut self.fields {
+                        ui.label(name.as_str());
+                        ui.text_edit_singleline(value);
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(8.0);
+                if ui
+                    .add_enabled(!self.title.trim().is_empty(), egui::Button::new("Create"))
+                    .clicked()
+                {
+                    let now = Local::now();
+                    let title = expand(&self.title, "", &self.fields, now);
+                    draft = Some(instantiate(&notes[template], title.trim(), &self.fields, now));
+                }
+            });
+        if !open || draft.is_some() {
+            self.template = None;
+        }
+        draft
+    }
+}